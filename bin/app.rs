@@ -13,8 +13,67 @@ pub struct App {
     #[clap(long)]
     pub def: Option<Vec<String>>,
 
+    /// Persist parsed tables in this directory, so repeated runs do not need to re-parse files.
+    #[clap(long)]
+    pub cache_dir: Option<String>,
+
+    /// Approximate memory limit, in megabytes, of files cached in memory (default 1024).
+    #[clap(long)]
+    pub cache_limit: Option<usize>,
+
+    /// Allow running external processes with the `exec` command.
+    #[clap(long)]
+    pub allow_exec: bool,
+
+    /// Filesystem access of commands, paths are confined to the working directory by default.
+    #[clap(long, arg_enum, default_value = "root-confined")]
+    pub sandbox: Access,
+
+    /// Reject paths through symlinks, rather than following them.
+    #[clap(long)]
+    pub deny_symlinks: bool,
+
+    /// Evaluate an expression, using stdin (parsed as a CSV table) as the input.
+    /// Can not be used with files to process.
+    #[clap(short, long, conflicts_with = "files")]
+    pub eval: Option<String>,
+
+    /// Pass stdin to the expression as a string, rather than parsing it as a table.
+    #[clap(long = "str")]
+    pub str_input: bool,
+
+    /// The format of the evaluated output written to stdout.
+    #[clap(long, arg_enum, default_value = "table")]
+    pub output: Output,
+
     /// Files to process. If none specified, a REPL instance is started.
     pub files: Vec<String>,
+
+    /// Script arguments, as `key=value` pairs, accessible using the `arg` command.
+    #[clap(last = true)]
+    pub args: Vec<String>,
+}
+
+/// The output format of an evaluated expression.
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Output {
+    /// Comma separated values.
+    Csv,
+    /// JSON, tables are written as an array of objects.
+    Json,
+    /// A pretty printed table.
+    Table,
+}
+
+/// The filesystem access of commands.
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Access {
+    /// Paths are confined to the working directory, and nothing can be written.
+    ReadOnly,
+    /// Paths are confined to the working directory.
+    RootConfined,
+    /// Any path can be read and written.
+    Unrestricted,
 }
 
 #[cfg(test)]
@@ -35,7 +94,16 @@ mod tests {
             App {
                 quiet: false,
                 def: None,
-                files: vec![]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec![],
+                args: vec![]
             }
         );
 
@@ -45,7 +113,16 @@ mod tests {
             App {
                 quiet: true,
                 def: None,
-                files: vec![]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec![],
+                args: vec![]
             }
         );
 
@@ -55,7 +132,16 @@ mod tests {
             App {
                 quiet: false,
                 def: Some(vec!["foo".to_string()]),
-                files: vec![]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec![],
+                args: vec![]
             }
         );
 
@@ -65,7 +151,16 @@ mod tests {
             App {
                 quiet: false,
                 def: Some(vec!["foo".to_string(), "bar".to_string()]),
-                files: vec![]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec![],
+                args: vec![]
             }
         );
 
@@ -75,7 +170,16 @@ mod tests {
             App {
                 quiet: false,
                 def: None,
-                files: vec!["foo".to_string()]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec![]
             }
         );
 
@@ -85,17 +189,172 @@ mod tests {
             App {
                 quiet: false,
                 def: None,
-                files: vec!["foo".to_string(), "bar".to_string()]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string(), "bar".to_string()],
+                args: vec![]
+            }
+        );
+
+        let a = App::try_parse_from("ogma --cache-dir .cache foo".split(' ')).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: Some(".cache".to_string()),
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec![]
+            }
+        );
+
+        let a = App::try_parse_from("ogma --cache-limit 256 foo".split(' ')).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: Some(256),
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec![]
+            }
+        );
+
+        let a = App::try_parse_from("ogma --allow-exec foo".split(' ')).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: true,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec![]
             }
         );
 
+        let a =
+            App::try_parse_from("ogma --sandbox read-only --deny-symlinks foo".split(' ')).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::ReadOnly,
+                deny_symlinks: true,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec![]
+            }
+        );
+
+        let a = App::try_parse_from("ogma foo -- a=1 b=c".split(' ')).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string()],
+                args: vec!["a=1".to_string(), "b=c".to_string()]
+            }
+        );
+
+        let a = App::try_parse_from(["ogma", "-e", "filter size > 5", "--output", "json"]).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: Some("filter size > 5".to_string()),
+                str_input: false,
+                output: Output::Json,
+                files: vec![],
+                args: vec![]
+            }
+        );
+
+        let a = App::try_parse_from(["ogma", "--eval", "len", "--str"]).unwrap();
+        assert_eq!(
+            a,
+            App {
+                quiet: false,
+                def: None,
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: Some("len".to_string()),
+                str_input: true,
+                output: Output::Table,
+                files: vec![],
+                args: vec![]
+            }
+        );
+
+        // files can not be processed alongside an expression
+        assert!(App::try_parse_from(["ogma", "-e", "len", "foo"]).is_err());
+
         let a = App::try_parse_from("ogma --def=foo --def bar foo bar".split(' ')).unwrap();
         assert_eq!(
             a,
             App {
                 quiet: false,
                 def: Some(vec!["foo".to_string(), "bar".to_string()]),
-                files: vec!["foo".to_string(), "bar".to_string()]
+                cache_dir: None,
+                cache_limit: None,
+                allow_exec: false,
+                sandbox: Access::RootConfined,
+                deny_symlinks: false,
+                eval: None,
+                str_input: false,
+                output: Output::Table,
+                files: vec!["foo".to_string(), "bar".to_string()],
+                args: vec![]
             }
         );
     }
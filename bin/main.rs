@@ -3,23 +3,73 @@ use std::path::{Path, PathBuf};
 
 mod app;
 
-use crate::app::App;
+use crate::app::{Access, App, Output};
 use clap::Parser;
 
 fn main() {
-    let App { quiet, def, files } = App::parse();
+    let App {
+        quiet,
+        def,
+        cache_dir,
+        cache_limit,
+        allow_exec,
+        sandbox,
+        deny_symlinks,
+        eval,
+        str_input,
+        output,
+        files,
+        args,
+    } = App::parse();
+
+    if let Some(dir) = cache_dir {
+        if let Err(e) = ogma::rt::set_cache_dir(Some(PathBuf::from(&dir))) {
+            panic!("failed setting cache directory '{}': {}", dir, e);
+        }
+    }
+
+    if let Some(mb) = cache_limit {
+        ogma::rt::set_cache_limit(mb.saturating_mul(1024 * 1024));
+    }
 
     let defs = def
         .map(|x| expand_globs(x, "definition", true))
         .unwrap_or_default();
     let files = expand_globs(files, "processing", true);
+    let args = match parse_args(args) {
+        Ok(x) => x,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(1); // failed
+        }
+    };
     let verbose = !quiet;
+    let sandbox = ogma::rt::Sandbox {
+        policy: match sandbox {
+            Access::ReadOnly => ogma::rt::Policy::ReadOnly,
+            Access::RootConfined => ogma::rt::Policy::RootConfined,
+            Access::Unrestricted => ogma::rt::Policy::Unrestricted,
+        },
+        symlinks: if deny_symlinks {
+            ogma::rt::Symlinks::Deny
+        } else {
+            ogma::rt::Symlinks::Follow
+        },
+    };
 
-    if files.is_empty() {
+    if let Some(expr) = eval {
+        let definitions = load_definitions(defs, &args, allow_exec, sandbox);
+        if eval_expression(expr, &definitions, str_input, output).is_err() {
+            std::process::exit(1); // failed
+        }
+    } else if files.is_empty() {
         // run the ogma-shell/REPL since no processing files were given
-        run_shell(defs)
-    } else if process_files(defs, files, verbose).is_err() {
-        std::process::exit(1); // failed
+        run_shell(defs, args, allow_exec, sandbox)
+    } else {
+        let definitions = load_definitions(defs, &args, allow_exec, sandbox);
+        if process_files(files, &definitions, verbose).is_err() {
+            std::process::exit(1); // failed
+        }
     }
 }
 
@@ -49,7 +99,25 @@ fn expand_globs(values: Vec<String>, file_ty: &str, panic_on_empty: bool) -> Vec
     paths
 }
 
-fn run_shell(defs: Vec<PathBuf>) {
+/// Parse the script arguments, which are in the form `key=value`.
+fn parse_args(args: Vec<String>) -> Result<Vec<(String, String)>, String> {
+    args.into_iter()
+        .map(|arg| match arg.split_once('=') {
+            Some((k, v)) if !k.is_empty() => Ok((k.to_string(), v.to_string())),
+            _ => Err(format!(
+                "could not parse argument '{}', expecting `key=value`",
+                arg
+            )),
+        })
+        .collect()
+}
+
+fn run_shell(
+    defs: Vec<PathBuf>,
+    args: Vec<(String, String)>,
+    allow_exec: bool,
+    sandbox: ogma::rt::Sandbox,
+) {
     let root = Path::new(".")
         .canonicalize()
         .expect("should be able to canonicalize root");
@@ -59,16 +127,32 @@ fn run_shell(defs: Vec<PathBuf>) {
         root.display().to_string().green()
     );
 
-    let mut shell = ogma_shell::OgmaShell::init(PathBuf::from(".")).title(title);
+    let mut shell = ogma_shell::OgmaShell::init(PathBuf::from("."))
+        .title(title)
+        .allow_exec(allow_exec)
+        .sandbox(sandbox);
     for def in defs {
         shell = shell.defs_file_path(def);
     }
+    for (name, value) in args {
+        shell = shell.arg(name, value);
+    }
 
     shell.run().expect("failed running the ogma shell")
 }
 
-fn process_files(defs: Vec<PathBuf>, files: Vec<PathBuf>, verbose: bool) -> Result<(), ()> {
+fn load_definitions(
+    defs: Vec<PathBuf>,
+    args: &[(String, String)],
+    allow_exec: bool,
+    sandbox: ogma::rt::Sandbox,
+) -> ogma::lang::Definitions {
     let mut definitions = ogma::lang::Definitions::default();
+    definitions.set_allow_exec(allow_exec);
+    definitions.set_sandbox(sandbox);
+    for (name, value) in args {
+        definitions.set_arg(name, value);
+    }
 
     // add defs
     for def in defs {
@@ -77,6 +161,84 @@ fn process_files(defs: Vec<PathBuf>, files: Vec<PathBuf>, verbose: bool) -> Resu
         }
     }
 
+    definitions
+}
+
+/// Evaluate `expr`, seeding it with stdin, and write the result to stdout.
+/// If stdin is a terminal, the expression is seeded with nil.
+fn eval_expression(
+    expr: String,
+    defs: &ogma::lang::Definitions,
+    str_input: bool,
+    output: Output,
+) -> Result<(), ()> {
+    use std::io::{self, IsTerminal, Read};
+
+    let stdin = io::stdin();
+    let input = if stdin.is_terminal() {
+        None
+    } else {
+        let mut input = String::new();
+        if let Err(e) = stdin.lock().read_to_string(&mut input) {
+            eprintln!("failed reading stdin: {}", e);
+            return Err(());
+        }
+        Some(input)
+    };
+
+    let stdout = &mut io::stdout().lock();
+    eval_input(expr, defs, input, str_input, output, stdout).map_err(|e| eprintln!("{}", e))
+}
+
+/// Evaluate `expr`, seeding it with `input` (nil if `None`), and write the result to `wtr`.
+/// Errors are returned as the message to print.
+fn eval_input(
+    expr: String,
+    defs: &ogma::lang::Definitions,
+    input: Option<String>,
+    str_input: bool,
+    output: Output,
+    wtr: &mut dyn std::io::Write,
+) -> Result<(), String> {
+    use ogma::{lang::ast::Location, rt::process_expression};
+    use std::io::Write;
+
+    let p = Path::new(".");
+    let loc = Location::Shell;
+
+    let value = match input {
+        None => process_expression((), expr, loc, defs, p, p),
+        Some(input) if str_input => {
+            let input = ::libs::divvy::Str::from(input);
+            process_expression(input, expr, loc, defs, p, p)
+        }
+        Some(input) => match ogma::rt::parse_table(&input, "csv") {
+            Ok(table) => process_expression(table, expr, loc, defs, p, p),
+            Err(e) => return Err(format!("failed parsing stdin as a table: {}", e)),
+        },
+    };
+
+    let value = value.map_err(|e| {
+        let mut buf = Vec::new();
+        ogma::output::print_error(&e, &mut buf).ok();
+        String::from_utf8_lossy(&buf).into_owned()
+    })?;
+
+    let written = match (output, value) {
+        // print_table ends with a newline
+        (Output::Table, ogma::lang::Value::Tab(table)) => ogma::output::print_table(&table, wtr),
+        (Output::Json, x) => ogma::rt::write_as("json", wtr, x).and_then(|_| writeln!(wtr)),
+        (_, x) => ogma::rt::write_as("csv", wtr, x).and_then(|_| writeln!(wtr)),
+    };
+
+    written.map_err(|e| format!("failed writing output: {}", e))
+}
+
+fn process_files(
+    files: Vec<PathBuf>,
+    definitions: &ogma::lang::Definitions,
+    verbose: bool,
+) -> Result<(), ()> {
     // turn files into batchs and process
 
     let batches = files
@@ -93,7 +255,7 @@ fn process_files(defs: Vec<PathBuf>, files: Vec<PathBuf>, verbose: bool) -> Resu
         .collect::<Vec<_>>();
 
     for (path, batch) in files.iter().zip(batches) {
-        process_and_print_batch(path, &batch, &definitions, verbose)?;
+        process_and_print_batch(path, &batch, definitions, verbose)?;
     }
 
     Ok(())
@@ -160,3 +322,67 @@ fn process_and_print_batch(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(xs: &[&str]) -> Vec<String> {
+        xs.iter().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn parsing_args() {
+        assert_eq!(
+            parse_args(args(&["a=1", "b=c=d", "e="])),
+            Ok(vec![
+                ("a".to_string(), "1".to_string()),
+                ("b".to_string(), "c=d".to_string()),
+                ("e".to_string(), String::new()),
+            ])
+        );
+        assert_eq!(
+            parse_args(args(&["a=1", "foo"])),
+            Err("could not parse argument 'foo', expecting `key=value`".to_string())
+        );
+        assert!(parse_args(args(&["=1"])).is_err());
+    }
+
+    #[test]
+    fn evaluating_expressions() {
+        let defs = &ogma::lang::Definitions::new();
+        let eval = |expr: &str, input: Option<&str>, str_input, output| {
+            let mut wtr = Vec::new();
+            eval_input(
+                expr.to_string(),
+                defs,
+                input.map(String::from),
+                str_input,
+                output,
+                &mut wtr,
+            )
+            .map(|_| String::from_utf8(wtr).unwrap())
+        };
+
+        let csv = Some("a,b\n1,x\n-2,y\n");
+        assert_eq!(
+            eval("filter a > 0", csv, false, Output::Json),
+            Ok("[{\"a\":1,\"b\":\"x\"}]\n".to_string())
+        );
+        assert!(eval("filter a > 0", csv, false, Output::Csv)
+            .unwrap()
+            .starts_with("a,b\n1,x\n"));
+        assert_eq!(
+            eval("len", Some("hello"), true, Output::Csv),
+            Ok("5\n".to_string())
+        );
+        // no input is nil
+        assert_eq!(
+            eval("\\ 3 | + 4", None, false, Output::Table),
+            Ok("7\n".to_string())
+        );
+
+        let e = eval("filter foo > 0", csv, false, Output::Json).unwrap_err();
+        assert!(e.contains("foo"), "{}", e);
+    }
+}
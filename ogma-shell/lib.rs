@@ -25,6 +25,12 @@ pub struct OgmaShell {
     pub root: PathBuf,
     /// File paths to look for definitions.
     pub defs_file_paths: Vec<PathBuf>,
+    /// Allow running external processes with the `exec` command.
+    pub allow_exec: bool,
+    /// The filesystem access policy of commands.
+    pub sandbox: ogma::rt::Sandbox,
+    /// Script arguments, accessible using the `arg` command.
+    pub args: Vec<(String, String)>,
 }
 
 impl OgmaShell {
@@ -34,6 +40,9 @@ impl OgmaShell {
             title: String::new(),
             root,
             defs_file_paths: Vec::new(),
+            allow_exec: false,
+            sandbox: ogma::rt::Sandbox::new(),
+            args: Vec::new(),
         }
     }
 
@@ -57,6 +66,24 @@ impl OgmaShell {
         self
     }
 
+    /// Allow running external processes with the `exec` command (disabled by default).
+    pub fn allow_exec(mut self, allow: bool) -> Self {
+        self.allow_exec = allow;
+        self
+    }
+
+    /// Set the filesystem access policy of commands.
+    pub fn sandbox(mut self, sandbox: ogma::rt::Sandbox) -> Self {
+        self.sandbox = sandbox;
+        self
+    }
+
+    /// Add a script argument.
+    pub fn arg(mut self, name: String, value: String) -> Self {
+        self.args.push((name, value));
+        self
+    }
+
     /// Run the shell.
     ///
     /// > Blocking operation.
@@ -65,9 +92,20 @@ impl OgmaShell {
             title,
             root,
             defs_file_paths,
+            allow_exec,
+            sandbox,
+            args,
         } = self;
 
         let wsp = Workspace::init();
+        {
+            let defs = &mut wsp.defs.write();
+            defs.set_allow_exec(allow_exec);
+            defs.set_sandbox(sandbox);
+            for (name, value) in &args {
+                defs.set_arg(name, value);
+            }
+        }
         let mut state = RunState {
             wsp: wsp.clone(),
             root: root.clone(),
@@ -398,9 +436,14 @@ fn write_result<W: Write>(
             Value::Nil => write!(wtr, "()"),
             Value::Bool(b) => write!(wtr, "{}", b),
             Value::Num(num) => write!(wtr, "{}", num),
+            Value::Dec(d) => write!(wtr, "{}", d),
             Value::Str(s) => write!(wtr, "{}", s),
+            Value::Date(d) => write!(wtr, "{}", d),
+            Value::Dur(d) => write!(wtr, "{}", d),
             Value::Tab(table) => print_table(&table, &mut wtr),
             Value::TabRow(_) => write!(wtr, "<table row>"),
+            Value::List(list) => write!(wtr, "{}", print_list(&list, &mut Default::default())),
+            Value::Map(map) => write!(wtr, "{}", print_map(&map, &mut Default::default())),
             Value::Ogma(data) => write!(wtr, "{}", print_ogma_data(data)),
         },
         Err(e) => {
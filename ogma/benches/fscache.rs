@@ -0,0 +1,25 @@
+//! Benchmarks `open`ing a file, where the parsed table is served from the filesystem cache.
+use criterion::{criterion_group, criterion_main, Criterion};
+use ogma::lang::{ast::Location, Definitions};
+use std::path::Path;
+
+fn open_cached(c: &mut Criterion) {
+    let defs = &Definitions::new();
+    let (root, wd) = (Path::new("."), Path::new("."));
+    let open = |expr: &str| {
+        ogma::rt::process_expression((), expr, Location::Shell, defs, root, wd).unwrap()
+    };
+
+    // prime the cache
+    open("open tests/diamonds.csv");
+
+    c.bench_function("open cached table", |b| {
+        b.iter(|| open("open tests/diamonds.csv"))
+    });
+    c.bench_function("open cached string", |b| {
+        b.iter(|| open("open --Str tests/diamonds.csv"))
+    });
+}
+
+criterion_group!(benches, open_cached);
+criterion_main!(benches);
@@ -294,6 +294,20 @@ impl Error {
         }
     }
 
+    pub(crate) fn invalid_flag_value<D, H>(flag: &Tag, desc: D, help: H) -> Self
+    where
+        D: Into<String>,
+        H: Into<Option<String>>,
+    {
+        Error {
+            cat: Category::Semantics,
+            desc: desc.into(),
+            traces: trace(flag, "invalid flag value".to_string()),
+            help_msg: help.into(),
+            hard: true, // unrecoverable
+        }
+    }
+
     pub(crate) fn unused_args<'a, T>(args: T) -> Self
     where
         T: ExactSizeIterator<Item = &'a Tag>,
@@ -358,6 +372,42 @@ impl Error {
         }
     }
 
+    pub(crate) fn env_var_not_found(name: &str, tag: &Tag) -> Self {
+        Error {
+            cat: Category::Evaluation,
+            desc: format!("environment variable `{}` not found", name),
+            traces: trace(tag, format!("`{}` resolves to `{}`", tag.str(), name)),
+            help_msg: Some("supply a default value, ie `env NAME 'default'`".into()),
+            ..Self::default()
+        }
+    }
+
+    pub(crate) fn script_arg_not_found(name: &str, tag: &Tag) -> Self {
+        Error {
+            cat: Category::Semantics,
+            desc: format!("script argument `{}` not supplied", name),
+            traces: trace(tag, None),
+            help_msg: Some(format!(
+                "supply the argument with `ogma file.ogma -- {}=value`, or give `arg` a default",
+                name
+            )),
+            hard: true,
+        }
+    }
+
+    pub(crate) fn script_arg_ty(name: &str, value: &str, ty: &Type, tag: &Tag) -> Self {
+        Error {
+            cat: Category::Semantics,
+            desc: format!(
+                "script argument `{}` expects a `{}`, found `{}`",
+                name, ty, value
+            ),
+            traces: trace(tag, format!("the default value returns type `{}`", ty)),
+            help_msg: None,
+            hard: true,
+        }
+    }
+
     pub(crate) fn row_out_of_bounds(index: usize, tag: &Tag) -> Self {
         Error {
             cat: Category::Evaluation,
@@ -616,6 +666,16 @@ impl Error {
         }
     }
 
+    pub(crate) fn exec_disabled(op: &Tag) -> Self {
+        Error {
+            cat: Category::Semantics,
+            desc: "running external processes is disabled".into(),
+            traces: trace(op, "`exec` is not enabled for this session".to_string()),
+            help_msg: Some("enable process execution with `ogma --allow-exec`".into()),
+            hard: true,
+        }
+    }
+
     pub(crate) fn unknown_arg_input_type(arg: &Tag) -> Self {
         Error {
             cat: Category::Semantics,
@@ -120,10 +120,16 @@ impl fmt::Display for Display<'_> {
             Nil => "Nil",
             Bool => "Bool",
             Num => "Num",
+            Dec => "Dec",
             Str => "Str",
+            Date => "Date",
+            Dur => "Dur",
             Tab => "Table",
             TabRow => "TableRow",
+            Type::List(t) => return write!(f, "List<{}>", Display(t)),
+            Type::Map(k, v) => return write!(f, "Map<{},{}>", Display(k), Display(v)),
             Def(d) => d.name().str(),
+            Param(p) => return write!(f, "{}", p),
         };
 
         write!(f, "{}", x)
@@ -8,6 +8,8 @@ pub struct Context<'a> {
     pub env: Environment,
     pub root: &'a std::path::Path,
     pub wd: &'a std::path::Path,
+    /// The filesystem access policy paths are resolved through.
+    pub sandbox: rt::Sandbox,
 }
 
 impl<'a> Context<'a> {
@@ -60,11 +62,21 @@ impl Stack {
             "expecting input value into Stack to match Stack's input type"
         );
 
-        let Context { mut env, root, wd } = cx;
+        let Context {
+            mut env,
+            root,
+            wd,
+            sandbox,
+        } = cx;
 
         let mut input = value;
         for step in &self.steps {
-            let cx = Context { env, root, wd };
+            let cx = Context {
+                env,
+                root,
+                wd,
+                sandbox,
+            };
             let (output, new_env) = step.invoke(input, cx)?;
             input = output;
             env = new_env;
@@ -238,6 +250,7 @@ impl CodeInjector<Eval> {
                     env,
                     root: cx.root,
                     wd: cx.wd,
+                    sandbox: cx.sandbox,
                 },
             )
             .map(|x| x.0)
@@ -515,6 +515,8 @@ impl AstGraph {
 
         let opnode = NodeIndex::from(opnode);
 
+        let generic = in_ty.generic();
+        let mut generic_match = None;
         let mut fallback = None;
 
         for edge in self.edges(opnode) {
@@ -523,10 +525,12 @@ impl AstGraph {
                 fallback = Some(CmdNode(edge.target()));
             } else if matches!(wgt, Relation::Keyed(Some(t)) if t == in_ty) {
                 return Some(CmdNode(edge.target())); // found a specific impl
+            } else if generic.is_some() && wgt.keyed() == generic.as_ref() {
+                generic_match = Some(CmdNode(edge.target())); // ranks above untyped impls
             }
         }
 
-        fallback
+        generic_match.or(fallback)
     }
 
     /// Get the arguments into a command node _in positional order_.
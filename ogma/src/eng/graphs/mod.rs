@@ -139,6 +139,16 @@ mod tests {
         assert_eq!(g.edge_weight(edgeidx.into()), Some(&v));
     }
 
+    /// The position of the `op` implementation keyed on `ty`.
+    /// Implementations are added to the graph in this order, so it is used to find the index of
+    /// an implementation's node and edges.
+    fn impl_pos(defs: &Definitions, op: &str, ty: Option<Type>) -> u32 {
+        defs.impls()
+            .iter_op(op)
+            .position(|x| x.ty == ty.as_ref())
+            .expect("implementation should exist") as u32
+    }
+
     #[test]
     fn expression_decomposition_checks() {
         use AstNode::*;
@@ -155,20 +165,25 @@ mod tests {
         assert!(matches!(ag.node_weight(6.into()), Some(Num { .. }))); // 3
         assert!(matches!(ag.node_weight(7.into()), Some(Intrinsic { .. }))); // filter intrinsic
         assert!(matches!(ag.node_weight(8.into()), Some(Intrinsic { .. }))); // filter intrinsic
-        assert!(matches!(ag.node_weight(9.into()), Some(Intrinsic { .. }))); // len intrinsic
+        assert!(matches!(ag.node_weight(9.into()), Some(Intrinsic { .. }))); // filter intrinsic
         assert!(matches!(ag.node_weight(10.into()), Some(Intrinsic { .. }))); // len intrinsic
-        assert!(matches!(ag.node_weight(11.into()), Some(Intrinsic { .. }))); // eq intrinsic
-        assert!(matches!(ag.node_weight(12.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(11.into()), Some(Intrinsic { .. }))); // len intrinsic
+        assert!(matches!(ag.node_weight(12.into()), Some(Intrinsic { .. }))); // len intrinsic
         assert!(matches!(ag.node_weight(13.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(14.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(15.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(16.into()), Some(Intrinsic { .. }))); // eq intrinsic
-        assert!(matches!(ag.node_weight(17.into()), None));
+        assert!(matches!(ag.node_weight(17.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(18.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(19.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(20.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(21.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(22.into()), None));
 
-        assert_eq!(ag.node_count(), 17);
-        assert_eq!(ag.edge_count(), 26);
+        assert_eq!(ag.node_count(), 22);
+        assert_eq!(ag.edge_count(), 36);
 
-        assert_eq!(tg.node_count(), 17);
+        assert_eq!(tg.node_count(), 22);
         assert_eq!(tg.edge_count(), 0); // zeroed initially
 
         check_relation(&ag, 0, 1, 0, Normal); // root -> filter
@@ -177,16 +192,67 @@ mod tests {
         check_relation(&ag, 0, 4, 3, Normal); // root -> len
         check_relation(&ag, 3, 5, 4, Normal); // eq 3 -> eq
         check_relation(&ag, 5, 6, 5, Normal); // eq -> 3
-        check_relation(&ag, 1, 7, 6, Keyed(Some(Type::Tab))); // filter -> intrinsic
-        check_relation(&ag, 1, 8, 9, Keyed(Some(Type::Str))); // filter -> intrinsic
-        check_relation(&ag, 2, 7, 7, Term(0)); // foo -> intrinsic
-        check_relation(&ag, 3, 7, 8, Term(1)); // eq 3 -> intrinsic
-        check_relation(&ag, 4, 9, 12, Keyed(Some(Type::Tab))); // len -> intrinsic
-        check_relation(&ag, 4, 10, 13, Keyed(Some(Type::Str))); // len -> intrinsic
-        check_relation(&ag, 5, 11, 14, Keyed(Some(Type::Nil))); // eq -> intrinsic
-        check_relation(&ag, 6, 11, 15, Term(0)); // 3 -> intrinsic
-        check_relation(&ag, 5, 12, 16, Keyed(Some(Type::Num))); // eq -> intrinsic
-        check_relation(&ag, 5, 13, 18, Keyed(Some(Type::Bool))); // eq -> intrinsic
+
+        // implementation nodes and edges are indexed by the implementation position
+        let defs = &Definitions::new();
+        // filter: nodes from 7, edges from 6, with a keyed and two term edges each
+        let filter = |ty| {
+            let i = impl_pos(defs, "filter", ty);
+            (7 + i, 6 + i * 3)
+        };
+        // len: nodes from 10, edges from 15, with a keyed edge each
+        let len = |ty| {
+            let i = impl_pos(defs, "len", ty);
+            (10 + i, 15 + i)
+        };
+        // eq: nodes from 13, edges from 18, with a keyed and a term edge each
+        let eq = |ty| {
+            let i = impl_pos(defs, "eq", ty);
+            (13 + i, 18 + i * 2)
+        };
+
+        let (i, e) = filter(Some(Type::Tab));
+        check_relation(&ag, 1, i, e, Keyed(Some(Type::Tab))); // filter -> intrinsic
+        check_relation(&ag, 2, i, e + 1, Term(0)); // foo -> intrinsic
+        check_relation(&ag, 3, i, e + 2, Term(1)); // eq 3 -> intrinsic
+        let (i, e) = filter(Some(Type::Str));
+        check_relation(&ag, 1, i, e, Keyed(Some(Type::Str))); // filter -> intrinsic
+        check_relation(&ag, 2, i, e + 1, Term(0)); // foo -> intrinsic
+        check_relation(&ag, 3, i, e + 2, Term(1)); // eq 3 -> intrinsic
+        let (i, e) = filter(Some(List::generic_ty()));
+        check_relation(&ag, 1, i, e, Keyed(Some(List::generic_ty()))); // filter -> intrinsic
+        check_relation(&ag, 2, i, e + 1, Term(0)); // foo -> intrinsic
+        check_relation(&ag, 3, i, e + 2, Term(1)); // eq 3 -> intrinsic
+
+        let (i, e) = len(Some(Type::Tab));
+        check_relation(&ag, 4, i, e, Keyed(Some(Type::Tab))); // len -> intrinsic
+        let (i, e) = len(Some(Type::Str));
+        check_relation(&ag, 4, i, e, Keyed(Some(Type::Str))); // len -> intrinsic
+        let (i, e) = len(Some(List::generic_ty()));
+        check_relation(&ag, 4, i, e, Keyed(Some(List::generic_ty()))); // len -> intrinsic
+
+        let (i, e) = eq(Some(Type::Nil));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Nil))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(Some(Type::Num));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Num))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(Some(Type::Bool));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Bool))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(Some(Type::Date));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Date))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(Some(Type::Dur));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Dur))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(Some(Type::Dec));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Dec))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        let (i, e) = eq(None);
+        check_relation(&ag, 5, i, e, Keyed(None)); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // 3 -> intrinsic
+        assert_eq!((i, e), (21, 34)); // the agnostic impl is last
     }
 
     #[test]
@@ -195,7 +261,7 @@ mod tests {
 
         tg.apply_ast_types(&ag);
 
-        assert_eq!(tg.node_count(), 25);
+        assert_eq!(tg.node_count(), 33);
 
         use tygraph::{Knowledge, Node};
         let def = || Node {
@@ -242,10 +308,10 @@ mod tests {
         tg.apply_ast_types(&ag);
         tg.apply_ast_edges(&ag);
 
-        assert_eq!(ag.node_count(), 20);
-        assert_eq!(ag.edge_count(), 29);
+        assert_eq!(ag.node_count(), 25);
+        assert_eq!(ag.edge_count(), 39);
 
-        assert_eq!(tg.node_count(), 20);
+        assert_eq!(tg.node_count(), 25);
 
         // Check AST graph edges
         assert!(matches!(ag.node_weight(0.into()), Some(Expr(_)))); // root
@@ -260,15 +326,20 @@ mod tests {
         assert!(matches!(ag.node_weight(9.into()), Some(Intrinsic { .. }))); // ls intrinsic
         assert!(matches!(ag.node_weight(10.into()), Some(Intrinsic { .. }))); // filter intrinsic
         assert!(matches!(ag.node_weight(11.into()), Some(Intrinsic { .. }))); // filter intrinsic
-        assert!(matches!(ag.node_weight(12.into()), Some(Intrinsic { .. }))); // len intrinsic
+        assert!(matches!(ag.node_weight(12.into()), Some(Intrinsic { .. }))); // filter intrinsic
         assert!(matches!(ag.node_weight(13.into()), Some(Intrinsic { .. }))); // len intrinsic
-        assert!(matches!(ag.node_weight(14.into()), Some(Intrinsic { .. }))); // eq intrinsic
-        assert!(matches!(ag.node_weight(15.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(14.into()), Some(Intrinsic { .. }))); // len intrinsic
+        assert!(matches!(ag.node_weight(15.into()), Some(Intrinsic { .. }))); // len intrinsic
         assert!(matches!(ag.node_weight(16.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(17.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(18.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(19.into()), Some(Intrinsic { .. }))); // eq intrinsic
-        assert!(matches!(ag.node_weight(20.into()), None));
+        assert!(matches!(ag.node_weight(20.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(21.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(22.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(23.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(24.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(25.into()), None));
 
         check_relation(&ag, 0, 1, 0, Normal); // root -> ls
         check_relation(&ag, 0, 2, 1, Normal); // root -> filter
@@ -282,18 +353,52 @@ mod tests {
 
         check_relation(&ag, 1, 8, 7, Keyed(Some(Type::Tab))); // ls -> intrinsic
         check_relation(&ag, 1, 9, 8, Keyed(None)); // ls -> intrinsic
-        check_relation(&ag, 2, 10, 9, Keyed(Some(Type::Tab))); // filter -> intrinsic
-        check_relation(&ag, 2, 11, 12, Keyed(Some(Type::Str))); // filter -> intrinsic
-        check_relation(&ag, 5, 12, 15, Keyed(Some(Type::Tab))); // len -> intrinsic
-        check_relation(&ag, 5, 13, 16, Keyed(Some(Type::Str))); // len -> intrinsic
-        check_relation(&ag, 6, 14, 17, Keyed(Some(Type::Nil))); // eq -> intrinsic
 
-        check_relation(&ag, 3, 10, 10, Term(0)); // foo -> filter intrinsic
-        check_relation(&ag, 4, 10, 11, Term(1)); // eq 0 -> filter intrinsic
-        check_relation(&ag, 3, 11, 13, Term(0)); // foo -> filter intrinsic
-        check_relation(&ag, 4, 11, 14, Term(1)); // eq 0 -> filter intrinsic
+        // implementation nodes and edges are indexed by the implementation position
+        let defs = &Definitions::new();
+        // filter: nodes from 10, edges from 9, with a keyed and two term edges each
+        let filter = |ty| {
+            let i = impl_pos(defs, "filter", ty);
+            (10 + i, 9 + i * 3)
+        };
+        // len: nodes from 13, edges from 18, with a keyed edge each
+        let len = |ty| {
+            let i = impl_pos(defs, "len", ty);
+            (13 + i, 18 + i)
+        };
+        // eq: nodes from 16, edges from 21, with a keyed and a term edge each
+        let eq = |ty| {
+            let i = impl_pos(defs, "eq", ty);
+            (16 + i, 21 + i * 2)
+        };
 
-        check_relation(&ag, 7, 14, 18, Term(0)); // 0 -> eq intrinsic
+        let (i, e) = filter(Some(Type::Tab));
+        check_relation(&ag, 2, i, e, Keyed(Some(Type::Tab))); // filter -> intrinsic
+        check_relation(&ag, 3, i, e + 1, Term(0)); // foo -> filter intrinsic
+        check_relation(&ag, 4, i, e + 2, Term(1)); // eq 0 -> filter intrinsic
+        let (i, e) = filter(Some(Type::Str));
+        check_relation(&ag, 2, i, e, Keyed(Some(Type::Str))); // filter -> intrinsic
+        check_relation(&ag, 3, i, e + 1, Term(0)); // foo -> filter intrinsic
+        check_relation(&ag, 4, i, e + 2, Term(1)); // eq 0 -> filter intrinsic
+        let (i, e) = filter(Some(List::generic_ty()));
+        check_relation(&ag, 2, i, e, Keyed(Some(List::generic_ty()))); // filter -> intrinsic
+        check_relation(&ag, 3, i, e + 1, Term(0)); // foo -> filter intrinsic
+        check_relation(&ag, 4, i, e + 2, Term(1)); // eq 0 -> filter intrinsic
+
+        let (i, e) = len(Some(Type::Tab));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Tab))); // len -> intrinsic
+        let (i, e) = len(Some(Type::Str));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Str))); // len -> intrinsic
+        let (i, e) = len(Some(List::generic_ty()));
+        check_relation(&ag, 5, i, e, Keyed(Some(List::generic_ty()))); // len -> intrinsic
+
+        let (i, e) = eq(Some(Type::Nil));
+        check_relation(&ag, 6, i, e, Keyed(Some(Type::Nil))); // eq -> intrinsic
+        check_relation(&ag, 7, i, e + 1, Term(0)); // 0 -> eq intrinsic
+        let (i, e) = eq(None);
+        check_relation(&ag, 6, i, e, Keyed(None)); // eq -> intrinsic
+        check_relation(&ag, 7, i, e + 1, Term(0)); // 0 -> eq intrinsic
+        assert_eq!((i, e), (24, 37)); // the agnostic impl is last
 
         // Type graph nodes
         use tygraph::{Flow, Knowledge, Node};
@@ -408,10 +513,13 @@ mod tests {
         assert!(matches!(ag.node_weight(10.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(11.into()), Some(Intrinsic { .. }))); // eq intrinsic
         assert!(matches!(ag.node_weight(12.into()), Some(Intrinsic { .. }))); // eq intrinsic
-        assert!(matches!(ag.node_weight(13.into()), None));
+        assert!(matches!(ag.node_weight(13.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(14.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(15.into()), Some(Intrinsic { .. }))); // eq intrinsic
+        assert!(matches!(ag.node_weight(16.into()), None));
 
-        assert_eq!(ag.node_count(), 13);
-        assert_eq!(ag.edge_count(), 19);
+        assert_eq!(ag.node_count(), 16);
+        assert_eq!(ag.edge_count(), 25);
 
         check_relation(&ag, 0, 1, 0, Normal); // root -> =
         check_relation(&ag, 1, 2, 1, Normal); // = -> 3
@@ -420,8 +528,23 @@ mod tests {
         check_relation(&ag, 3, 4, 4, Normal); // Def -> eq $rhs
         check_relation(&ag, 1, 3, 5, Keyed(None)); // = -> Def
         check_relation(&ag, 2, 3, 6, Term(0)); // 3 -> Def
-        check_relation(&ag, 5, 7, 7, Keyed(Some(Type::Nil))); // = -> Def
-        check_relation(&ag, 6, 7, 8, Term(0)); // 3 -> Def
+
+        // eq: nodes from 7, edges from 7, with a keyed and a term edge each
+        let defs = &Definitions::new();
+        let eq = |ty| {
+            let i = impl_pos(defs, "eq", ty);
+            (7 + i, 7 + i * 2)
+        };
+        let (i, e) = eq(Some(Type::Nil));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Nil))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // $rhs -> intrinsic
+        let (i, e) = eq(Some(Type::Dec));
+        check_relation(&ag, 5, i, e, Keyed(Some(Type::Dec))); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // $rhs -> intrinsic
+        let (i, e) = eq(None);
+        check_relation(&ag, 5, i, e, Keyed(None)); // eq -> intrinsic
+        check_relation(&ag, 6, i, e + 1, Term(0)); // $rhs -> intrinsic
+        assert_eq!((i, e), (15, 23)); // the agnostic impl is last
     }
 
     #[test]
@@ -432,8 +555,8 @@ mod tests {
         tg.apply_ast_edges(&ag);
 
         // Assert some info about the AST nodes
-        assert_eq!(ag.node_count(), 13);
-        assert_eq!(ag.edge_count(), 19);
+        assert_eq!(ag.node_count(), 16);
+        assert_eq!(ag.edge_count(), 25);
         // 0: = 3
         // 1: =
         // 2: 3
@@ -441,7 +564,7 @@ mod tests {
         // 4: eq $rhs
         // 5: eq
         // 6: $rhs
-        // 7: eq Intrinsic
+        // 7..: eq intrinsics
 
         // Type graph nodes
         use tygraph::{Flow, Knowledge, Node};
@@ -465,42 +588,25 @@ mod tests {
         assert_eq!(tg.node_weight(4.into()), Some(&def())); // eq $rhs
         assert_eq!(tg.node_weight(5.into()), Some(&def())); // eq
         assert_eq!(tg.node_weight(6.into()), Some(&def())); // $rhs
-        assert_eq!(
-            tg.node_weight(7.into()),
-            Some(&Node {
-                input: Knowledge::Known(Type::Nil),
-                ..def()
-            })
-        ); // eq intrinsic
-        assert_eq!(
-            tg.node_weight(8.into()),
-            Some(&Node {
-                input: Knowledge::Known(Type::Num),
-                ..def()
-            })
-        ); // eq intrinsic
-        assert_eq!(
-            tg.node_weight(9.into()),
-            Some(&Node {
-                input: Knowledge::Known(Type::Bool),
-                ..def()
-            })
-        ); // eq intrinsic
-        assert_eq!(
-            tg.node_weight(10.into()),
-            Some(&Node {
-                input: Knowledge::Known(std::cmp::Ordering::as_type()),
-                ..def()
-            })
-        ); // eq intrinsic
-        assert_eq!(
-            tg.node_weight(11.into()),
-            Some(&Node {
-                input: Knowledge::Known(Type::Str),
-                ..def()
-            })
-        ); // eq intrinsic
-        assert_eq!(tg.node_weight(12.into()), Some(&def())); // eq intrinsic (any type)
+
+        // eq intrinsics are from node 7, in the implementation order
+        let defs = &Definitions::new();
+        let eq = |ty| tg.node_weight((7 + impl_pos(defs, "eq", ty)).into());
+        let known = |ty| Node {
+            input: Knowledge::Known(ty),
+            ..def()
+        };
+        assert_eq!(eq(Some(Type::Nil)), Some(&known(Type::Nil))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Num)), Some(&known(Type::Num))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Bool)), Some(&known(Type::Bool))); // eq intrinsic
+        let ord = std::cmp::Ordering::as_type();
+        assert_eq!(eq(Some(ord.clone())), Some(&known(ord))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Str)), Some(&known(Type::Str))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Date)), Some(&known(Type::Date))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Dur)), Some(&known(Type::Dur))); // eq intrinsic
+        assert_eq!(eq(Some(Type::Dec)), Some(&known(Type::Dec))); // eq intrinsic
+        assert_eq!(eq(None), Some(&def())); // eq intrinsic (any type)
+        assert_eq!(tg.node_weight(15.into()), Some(&def())); // the agnostic impl is last
 
         // Type graph edges
         let getedge = |a: u32, b: u32| &tg[tg.find_edge(a.into(), b.into()).unwrap()];
@@ -535,8 +641,8 @@ mod tests {
         tg.apply_ast_edges(&ag);
 
         // Assert some info about the AST nodes
-        assert_eq!(ag.node_count(), 15);
-        assert_eq!(ag.edge_count(), 29);
+        assert_eq!(ag.node_count(), 18);
+        assert_eq!(ag.edge_count(), 38);
 
         // Type graph nodes
         use tygraph::Flow;
@@ -553,11 +659,14 @@ mod tests {
         assert_eq!(getedge(0, 1), &Flow::II); // root -> cmp: II
         assert_eq!(getedge(1, 0), &Flow::OO); // cmp -> root: OO
 
-        assert_eq!(getedge(7, 8), &Flow::II); // \ #t -> \: II
-        assert_eq!(getedge(8, 7), &Flow::OO); // \ -> \ #t: OO
+        // the def is keyed alongside the cmp intrinsics, its expression follows it
+        // the impls before it are intrinsics, a node each, from node 4
+        let d = 4 + impl_pos(defs, "cmp", Some(Type::Tab));
+        assert_eq!(getedge(d + 1, d + 2), &Flow::II); // \ #t -> \: II
+        assert_eq!(getedge(d + 2, d + 1), &Flow::OO); // \ -> \ #t: OO
 
-        assert_eq!(getedge(6, 7), &Flow::II); // Def -> Expr: II
-        assert_eq!(getedge(7, 6), &Flow::OO); // Expr -> Def: OO
+        assert_eq!(getedge(d, d + 1), &Flow::II); // Def -> Expr: II
+        assert_eq!(getedge(d + 1, d), &Flow::OO); // Expr -> Def: OO
 
         // NOTE: there is NO 6 -> 1 (Def -> cmp) since we do not know which path would be taken
         // NOTE: there is NO 1 -> 6 (cmp -> Def) since this is not a keyed type
@@ -182,12 +182,14 @@ impl TypeGraph {
         }
 
         // based on any Keyed(Some(type))
+        // generic keys (such as `List<T>`) are not a concrete input type, so the input stays
+        // inferred
         for (e, node) in ag
             .edge_indices()
             .filter(|&e| ag[e].is_key())
             .map(|e| (e, ag.edge_endpoints(e).expect("should exist").1))
         {
-            if let Some(ty) = ag[e].keyed().cloned() {
+            if let Some(ty) = ag[e].keyed().filter(|t| !t.is_generic()).cloned() {
                 self.g[node].input = Knowledge::Known(ty);
             }
         }
@@ -357,6 +359,19 @@ impl TypeGraph {
             let keys = op.cmds(ag).fold(Some(TypesSet::empty()), |set, cmd| {
                 let e = &ag[ag.find_edge(op.idx(), cmd.idx()).unwrap()];
                 match (set, e.keyed()) {
+                    // a generic key accepts any of the inferred types which match it, such as
+                    // `List<Num>` matching `List<T>`
+                    (Some(mut s), Some(t)) if t.is_generic() => {
+                        let tys = self[op.idx()]
+                            .input
+                            .tys()
+                            .into_iter()
+                            .flat_map(|x| x.iter());
+                        for x in tys.filter(|x| x.generic().as_ref() == Some(t)) {
+                            s.insert(x.clone());
+                        }
+                        Some(s)
+                    }
                     (Some(mut s), Some(t)) => {
                         s.insert(t.clone());
                         Some(s)
@@ -366,7 +381,8 @@ impl TypeGraph {
                 }
             });
 
-            if let Some(contrained) = keys {
+            // a generic key might not match any inferred types yet, do not reduce to nothing
+            if let Some(contrained) = keys.filter(|s| !s.is_empty()) {
                 self.g[op.idx()].input = contrained.into();
             }
         }
@@ -13,6 +13,9 @@ use lang::help::*;
 pub struct Definitions {
     impls: lang::impls::Implementations,
     types: types::Types,
+    allow_exec: bool,
+    sandbox: rt::Sandbox,
+    args: HashMap<Str, Str>,
 }
 
 impl Default for Definitions {
@@ -35,7 +38,13 @@ impl Definitions {
             lang::impls::add_typedef_init_impls(&mut impls, x);
         }
 
-        let mut definitions = Self { impls, types };
+        let mut definitions = Self {
+            impls,
+            types,
+            allow_exec: false,
+            sandbox: rt::Sandbox::new(),
+            args: HashMap::default(),
+        };
 
         let defs = &mut definitions;
 
@@ -219,6 +228,38 @@ the row is assumed to be populated with strings, if not an error occurs",
         self.types.clear(only_files);
     }
 
+    /// Allow, or disallow, running external processes with the `exec` command.
+    /// Process execution is disabled by default.
+    pub fn set_allow_exec(&mut self, allow: bool) {
+        self.allow_exec = allow;
+    }
+
+    /// Returns if running external processes with the `exec` command is allowed.
+    pub fn allow_exec(&self) -> bool {
+        self.allow_exec
+    }
+
+    /// Set the filesystem access policy of commands which touch the filesystem.
+    /// Paths are confined to the root directory by default.
+    pub fn set_sandbox(&mut self, sandbox: rt::Sandbox) {
+        self.sandbox = sandbox;
+    }
+
+    /// The filesystem access policy of commands which touch the filesystem.
+    pub fn sandbox(&self) -> rt::Sandbox {
+        self.sandbox
+    }
+
+    /// Set a script argument, accessible through the `arg` command.
+    pub fn set_arg(&mut self, name: &str, value: &str) {
+        self.args.insert(Str::new(name), Str::new(value));
+    }
+
+    /// Get a script argument's value, if it was set.
+    pub fn arg(&self, name: &str) -> Option<&str> {
+        self.args.get(name).map(|x| x.as_str())
+    }
+
     /// Provide access to the defined implementations.
     pub fn impls(&self) -> &Implementations {
         &self.impls
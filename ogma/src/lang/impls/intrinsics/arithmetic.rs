@@ -1,24 +1,39 @@
 use super::*;
+use ::rust_decimal::RoundingStrategy;
 use std::cmp;
 
 pub fn add_intrinsics(impls: &mut Implementations) {
     add! { impls,
         ("+", Number, add_num, Arithmetic)
         ("+", Str, add_str, Arithmetic)
+        ("+", DateTime, add_date, Arithmetic)
+        ("+", Decimal, add_dec, Arithmetic)
+        ("+", Duration, add_dur, Arithmetic)
         ("+", Table, add_table, Arithmetic)
 
         ("*", Number, mul_num, Arithmetic)
+        ("*", Decimal, mul_dec, Arithmetic)
         ("×", Number, mul_num, Arithmetic)
+        ("×", Decimal, mul_dec, Arithmetic)
 
         ("-", Number, sub_num, Arithmetic)
+        ("-", DateTime, sub_date, Arithmetic)
+        ("-", Decimal, sub_dec, Arithmetic)
+        ("-", Duration, sub_dur, Arithmetic)
 
         ("/", Number, div_num, Arithmetic)
+        ("/", Decimal, div_dec, Arithmetic)
         ("÷", Number, div_num, Arithmetic)
+        ("÷", Decimal, div_dec, Arithmetic)
 
         ("ceil", Number, ceil_num, Arithmetic)
         ("floor", Number, floor_num, Arithmetic)
         ("is-finite", Number, isfinite_num, Arithmetic)
         ("root", Number, root_num, Arithmetic)
+        ("round", Decimal, round_dec, Arithmetic)
+
+        ("to-dec", Number, to_dec_num, Arithmetic)
+        ("to-dec", Str, to_dec_str, Arithmetic)
     };
 }
 
@@ -32,6 +47,152 @@ where
     })
 }
 
+/// Applies `f` to the input decimal and each argument in turn.
+/// Number arguments are converted into decimals (see [`num_to_dec`]).
+fn variadic_intrinsic_dec<F>(mut blk: Block, f: F) -> Result<Step>
+where
+    F: Fn(Decimal, Decimal) -> std::result::Result<Decimal, String> + Send + Sync + 'static,
+{
+    blk.assert_input(&Ty::Dec)?;
+    blk.assert_output(Ty::Dec);
+
+    let len = blk.args_len();
+    if len == 0 {
+        return Err(Error::insufficient_args(blk.blk_tag(), 0, None));
+    }
+
+    let mut args = Vec::with_capacity(len);
+    for _ in 0..len {
+        let arg = blk.next_arg()?.supplied(None)?;
+        // table entries are generally numbers, so unknown arguments are assumed to be numbers
+        let arg = match arg.return_ty() {
+            Some(Ty::Dec) => arg.returns(Ty::Dec)?,
+            _ => arg.returns(Ty::Num)?,
+        };
+        args.push(arg.concrete()?);
+    }
+
+    let blktag = blk.blk_tag().clone();
+    let err = move |e: String| Error::eval(&blktag, e, String::from("within this block"), None);
+
+    blk.eval_o(move |input, cx| {
+        let mut prev = Decimal::try_from(input.clone())?;
+        for arg in &args {
+            let next = match arg.resolve(|| input.clone(), &cx)? {
+                Value::Num(n) => num_to_dec(&n).map_err(&err)?,
+                v => Decimal::try_from(v)?,
+            };
+            prev = f(prev, next).map_err(&err)?;
+        }
+
+        cx.done_o(prev)
+    })
+}
+
+/// Convert a number into a decimal using its shortest representation, so `0.1` is exactly `0.1`.
+pub(super) fn num_to_dec(n: &Number) -> std::result::Result<Decimal, String> {
+    let f = n.as_f64();
+    f.to_string()
+        .parse()
+        .map_err(|_| format!("`{}` cannot be represented as a decimal", f))
+}
+
+fn dec_overflow(lhs: Decimal, op: char, rhs: Decimal) -> String {
+    format!("`{} {} {}` overflows a decimal", lhs, op, rhs)
+}
+
+/// Offsets the input date by each argument, either a (fractional) number of days or a duration.
+/// `sign` is `1` for adding, `-1` for subtracting.
+///
+/// Subtracting a date instead returns the duration between the dates.
+fn variadic_intrinsic_date(mut blk: Block, sign: i32) -> Result<Step> {
+    blk.assert_input(&Ty::Date)?;
+
+    let len = blk.args_len();
+    if len == 0 {
+        return Err(Error::insufficient_args(blk.blk_tag(), 0, None));
+    }
+
+    let mut args = Vec::with_capacity(len);
+    for _ in 0..len {
+        let arg = blk.next_arg()?.supplied(None)?;
+        let arg = match arg.return_ty() {
+            Some(Ty::Date) if sign < 0 && args.is_empty() => {
+                let rhs = arg.concrete()?;
+                return date_diff(blk, rhs);
+            }
+            Some(Ty::Dur) => arg.returns(Ty::Dur)?,
+            _ => arg.returns(Ty::Num)?,
+        };
+        args.push(arg.concrete()?);
+    }
+
+    blk.assert_output(Ty::Date);
+
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |input, cx| {
+        let mut dt = DateTime::try_from(input.clone())?;
+        for arg in &args {
+            let (x, offset) = match arg.resolve(|| input.clone(), &cx)? {
+                Value::Dur(d) => {
+                    let x = if sign < 0 {
+                        dt.checked_sub(d.0)
+                    } else {
+                        dt.checked_add(d.0)
+                    };
+                    (x.map(DateTime), Duration(d.0 * sign).to_string())
+                }
+                v => {
+                    let days = Number::try_from(v)?.as_f64() * sign as f64;
+                    (add_days(dt, days), format!("{} days", days))
+                }
+            };
+            dt = x.ok_or_else(|| {
+                Error::eval(
+                    &blktag,
+                    format!("adding {} to {} is out of range", offset, dt),
+                    String::from("within this block"),
+                    None,
+                )
+            })?;
+        }
+
+        cx.done_o(dt)
+    })
+}
+
+/// The duration between the input date and `rhs`.
+fn date_diff(mut blk: Block, rhs: eng::Argument) -> Result<Step> {
+    blk.assert_output(Ty::Dur);
+    blk.eval_o(move |lhs, cx| {
+        let rhs = rhs
+            .resolve(|| lhs.clone(), &cx)
+            .and_then(DateTime::try_from)?;
+        let lhs = DateTime::try_from(lhs)?;
+        cx.done_o(Duration(lhs.0 - rhs.0))
+    })
+}
+
+fn variadic_intrinsic_dur<F>(blk: Block, f: F) -> Result<Step>
+where
+    F: Fn(::time::Duration, ::time::Duration) -> ::time::Duration + Send + Sync + 'static,
+{
+    variadic_intrinsic_in_constrained::<Duration, _>(blk, move |prev, next| {
+        (Duration(f(prev.0, next.0)), false)
+    })
+}
+
+/// Offset a date by a number of days, keeping its UTC offset.
+fn add_days(dt: DateTime, days: f64) -> Option<DateTime> {
+    let nanos = dt
+        .unix_timestamp_nanos()
+        .checked_add((days * 86_400e9) as i128)?;
+    ::time::OffsetDateTime::from_unix_timestamp_nanos(nanos)
+        .ok()
+        .map(|x| DateTime(x.to_offset(dt.offset())))
+}
+
 // ------ Add ------------------------------------------------------------------
 fn add_num_help() -> HelpMessage {
     variadic_help(
@@ -78,6 +239,65 @@ fn add_str_intrinsic(blk: Block) -> Result<Step> {
     })
 }
 
+fn add_date_help() -> HelpMessage {
+    variadic_help(
+        "+",
+        "add a number of days, or a duration, to a date
+fractional days add hours, minutes, and seconds",
+        vec![
+            HelpExample {
+                desc: "the date a week from now",
+                code: "now | + 7",
+            },
+            HelpExample {
+                desc: "add 12 hours to a date",
+                code: "\\ '2021-03-04' | to-date | + 0.5",
+            },
+            HelpExample {
+                desc: "add a duration to a date",
+                code: "now | + {dur 1h30min}",
+            },
+        ],
+    )
+}
+
+fn add_date_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_date(blk, 1)
+}
+
+fn add_dur_help() -> HelpMessage {
+    variadic_help(
+        "+",
+        "add durations together",
+        vec![HelpExample {
+            desc: "add 30 minutes to an hour",
+            code: "dur 1h | + {dur 30min}",
+        }],
+    )
+}
+
+fn add_dur_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dur(blk, ::time::Duration::saturating_add)
+}
+
+fn add_dec_help() -> HelpMessage {
+    variadic_help(
+        "+",
+        "add decimals together
+number arguments are converted to decimals",
+        vec![HelpExample {
+            desc: "add 0.2 to 0.1, exactly",
+            code: "\\ 0.1 | to-dec | + 0.2",
+        }],
+    )
+}
+
+fn add_dec_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dec(blk, |a, b| {
+        a.checked_add(b).ok_or_else(|| dec_overflow(a, '+', b))
+    })
+}
+
 fn add_table_help() -> HelpMessage {
     let h = variadic_help(
         "+",
@@ -227,6 +447,29 @@ fn div_num_intrinsic(blk: Block) -> Result<Step> {
     variadic_intrinsic_num(blk, std::ops::Div::div)
 }
 
+fn div_dec_help() -> HelpMessage {
+    variadic_help(
+        "/",
+        "divide a decimal by the arguments
+number arguments are converted to decimals
+dividing by 0 is an error",
+        vec![HelpExample {
+            desc: "split 10 four ways",
+            code: "\\ 10 | to-dec | / 4",
+        }],
+    )
+}
+
+fn div_dec_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dec(blk, |a, b| {
+        if b.is_zero() {
+            Err(format!("cannot divide `{}` by zero", a))
+        } else {
+            a.checked_div(b).ok_or_else(|| dec_overflow(a, '/', b))
+        }
+    })
+}
+
 // ------ Floor ----------------------------------------------------------------
 fn floor_num_help() -> HelpMessage {
     HelpMessage {
@@ -299,6 +542,24 @@ fn mul_num_intrinsic(blk: Block) -> Result<Step> {
     variadic_intrinsic_num(blk, std::ops::Mul::mul)
 }
 
+fn mul_dec_help() -> HelpMessage {
+    variadic_help(
+        "*",
+        "multiply decimals together
+number arguments are converted to decimals",
+        vec![HelpExample {
+            desc: "the price of three items",
+            code: "\\ '19.99' | to-dec | * 3",
+        }],
+    )
+}
+
+fn mul_dec_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dec(blk, |a, b| {
+        a.checked_mul(b).ok_or_else(|| dec_overflow(a, '*', b))
+    })
+}
+
 // ------ Root -----------------------------------------------------------------
 fn root_num_help() -> HelpMessage {
     HelpMessage {
@@ -344,6 +605,74 @@ fn root_num_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+// ------ Round ----------------------------------------------------------------
+fn round_dec_help() -> HelpMessage {
+    HelpMessage {
+        desc: "round a decimal to a number of decimal places, defaults to 0 places
+midpoints are rounded away from zero (half-up), use --even for banker's rounding"
+            .into(),
+        params: vec![HelpParameter::Optional("places".into())],
+        flags: vec![("even", "round midpoints to the nearest even digit")],
+        examples: vec![
+            HelpExample {
+                desc: "round to 2 places, half-up",
+                code: "\\ '2.345' | to-dec | round 2",
+            },
+            HelpExample {
+                desc: "round to 2 places, using banker's rounding",
+                code: "\\ '2.345' | to-dec | round --even 2",
+            },
+        ],
+        ..HelpMessage::new("round")
+    }
+}
+
+fn round_dec_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Dec)?;
+    blk.assert_output(Ty::Dec);
+
+    let strategy = if blk.get_flag("even").is_some() {
+        RoundingStrategy::MidpointNearestEven
+    } else {
+        RoundingStrategy::MidpointAwayFromZero
+    };
+
+    let places = if blk.args_len() > 0 {
+        let arg = blk
+            .next_arg()?
+            .supplied(None)?
+            .returns(Ty::Num)?
+            .concrete()?;
+        Some(arg)
+    } else {
+        None
+    };
+
+    blk.eval_o(move |input, cx| {
+        let dp = match &places {
+            Some(places) => {
+                let n = places
+                    .resolve(|| input.clone(), &cx)
+                    .and_then(Number::try_from)?
+                    .as_f64();
+                if n < 0.0 || n > 28.0 || n.fract() != 0.0 {
+                    return Err(Error::eval(
+                        &places.tag,
+                        format!("expecting a whole number of decimal places, found `{}`", n),
+                        String::from("decimal places must be from 0 to 28"),
+                        None,
+                    ));
+                }
+                n as u32
+            }
+            None => 0,
+        };
+
+        let d = Decimal::try_from(input)?;
+        cx.done_o(d.round_dp_with_strategy(dp, strategy))
+    })
+}
+
 // ------ Sub ------------------------------------------------------------------
 fn sub_num_help() -> HelpMessage {
     variadic_help(
@@ -362,6 +691,134 @@ fn sub_num_help() -> HelpMessage {
     )
 }
 
+fn sub_date_help() -> HelpMessage {
+    variadic_help(
+        "-",
+        "subtract a number of days, or a duration, from a date
+fractional days subtract hours, minutes, and seconds
+subtracting a date returns the duration between the dates",
+        vec![
+            HelpExample {
+                desc: "the date a week ago",
+                code: "now | - 7",
+            },
+            HelpExample {
+                desc: "subtract a duration from a date",
+                code: "now | - {dur 12h}",
+            },
+            HelpExample {
+                desc: "the time since a date",
+                code: "now | - {\\ '2021-03-04' | to-date}",
+            },
+        ],
+    )
+}
+
+fn sub_date_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_date(blk, -1)
+}
+
+fn sub_dur_help() -> HelpMessage {
+    variadic_help(
+        "-",
+        "subtract durations from one another",
+        vec![HelpExample {
+            desc: "subtract an hour from a day",
+            code: "dur 1d | - {dur 1h}",
+        }],
+    )
+}
+
+fn sub_dur_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dur(blk, ::time::Duration::saturating_sub)
+}
+
 fn sub_num_intrinsic(blk: Block) -> Result<Step> {
     variadic_intrinsic_num(blk, std::ops::Sub::sub)
 }
+
+fn sub_dec_help() -> HelpMessage {
+    variadic_help(
+        "-",
+        "subtract arguments from a decimal
+number arguments are converted to decimals",
+        vec![HelpExample {
+            desc: "subtract 0.9 from 1, exactly",
+            code: "\\ 1 | to-dec | - 0.9",
+        }],
+    )
+}
+
+fn sub_dec_intrinsic(blk: Block) -> Result<Step> {
+    variadic_intrinsic_dec(blk, |a, b| {
+        a.checked_sub(b).ok_or_else(|| dec_overflow(a, '-', b))
+    })
+}
+
+// ------ To Dec ---------------------------------------------------------------
+fn to_dec_num_help() -> HelpMessage {
+    HelpMessage {
+        desc: "convert a number into an exact decimal
+the shortest representation of the number is used, so 0.1 is exactly 0.1"
+            .into(),
+        examples: vec![
+            HelpExample {
+                desc: "convert 0.1 into a decimal",
+                code: "\\ 0.1 | to-dec",
+            },
+            HelpExample {
+                desc: "sum a column exactly",
+                code: "open ledger.csv | fold {\\ 0 | to-dec} + $row.amount",
+            },
+        ],
+        ..HelpMessage::new("to-dec")
+    }
+}
+
+fn to_dec_num_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Num)?;
+    blk.assert_output(Ty::Dec);
+
+    let blktag = blk.blk_tag().clone();
+    blk.eval_o(move |v, cx| {
+        let n = Number::try_from(v)?;
+        num_to_dec(&n)
+            .map_err(|e| Error::eval(&blktag, e, String::from("invalid decimal"), None))
+            .and_then(|d| cx.done_o(d))
+    })
+}
+
+fn to_dec_str_help() -> HelpMessage {
+    HelpMessage {
+        desc: "parse a string as an exact decimal
+the digits are kept exactly, including any trailing zeros"
+            .into(),
+        examples: vec![HelpExample {
+            desc: "parse a currency amount",
+            code: "\\ '1234.50' | to-dec",
+        }],
+        ..HelpMessage::new("to-dec")
+    }
+}
+
+fn to_dec_str_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Str)?;
+    blk.assert_output(Ty::Dec);
+
+    let blktag = blk.blk_tag().clone();
+    blk.eval_o(move |v, cx| {
+        let s = Str::try_from(v)?;
+        let t = s.trim();
+        t.parse::<Decimal>()
+            .or_else(|_| Decimal::from_scientific(t))
+            .map_err(|_| {
+                Error::eval(
+                    &blktag,
+                    format!("could not parse `{}` as a decimal", s),
+                    String::from("invalid decimal"),
+                    None,
+                )
+            })
+            .and_then(|d| cx.done_o(d))
+    })
+}
@@ -6,14 +6,20 @@ pub fn add_intrinsics(impls: &mut Implementations) {
         ("cmp", (), cmp_nil, Cmp)
         ("cmp", bool, cmp_bool, Cmp)
         ("cmp", Number, cmp_num, Cmp)
+        ("cmp", Decimal, cmp_dec, Cmp)
         ("cmp", Str, cmp_str, Cmp)
+        ("cmp", DateTime, cmp_date, Cmp)
+        ("cmp", Duration, cmp_dur, Cmp)
         ("cmp", cmp::Ordering, cmp_ord, Cmp)
         (cmp, Cmp)
 
         ("eq", (), eq_nil, Cmp)
         ("eq", bool, eq_bool, Cmp)
         ("eq", Number, eq_num, Cmp)
+        ("eq", Decimal, eq_dec, Cmp)
         ("eq", Str, eq_str, Cmp)
+        ("eq", DateTime, eq_date, Cmp)
+        ("eq", Duration, eq_dur, Cmp)
         ("eq", cmp::Ordering, eq_ord, Cmp)
         (eq, Cmp)
 
@@ -120,6 +126,118 @@ fn cmp_str_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn cmp_dec_help() -> HelpMessage {
+    HelpMessage {
+        desc: "compare <rhs> to input.
+decimals are compared by value, irrespective of trailing zeros
+a number <rhs> is converted to a decimal"
+            .into(),
+        params: vec![HelpParameter::Required("rhs:Dec".into())],
+        examples: vec![HelpExample {
+            desc: "compare a decimal to a number",
+            code: "\\ '0.30' | to-dec | cmp 0.3",
+        }],
+        ..HelpMessage::new("cmp")
+    }
+}
+
+fn cmp_dec_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Type::Dec)?;
+    blk.assert_output(cmp::Ordering::as_type()); // all 'cmp's return an Ord
+
+    let (rhs, to_dec) = dec_rhs(&mut blk)?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: Decimal = lhs.try_into()?;
+        let rhs = to_dec(rhs.resolve(|| lhs.into(), &cx)?)?;
+        cx.done_o(lhs.cmp(&rhs))
+    })
+}
+
+/// Decimals are compared to a decimal or number `rhs`, numbers are converted into decimals.
+///
+/// Returns the argument and a conversion of the resolved value.
+fn dec_rhs(
+    blk: &mut Block,
+) -> Result<(
+    eng::Argument,
+    impl Fn(Value) -> Result<Decimal> + Send + Sync + 'static,
+)> {
+    let arg = blk.next_arg()?.supplied(None)?;
+    // table entries are generally numbers, so unknown arguments are assumed to be numbers
+    let arg = match arg.return_ty() {
+        Some(Ty::Dec) => arg.returns(Ty::Dec)?,
+        _ => arg.returns(Ty::Num)?,
+    }
+    .concrete()?;
+
+    let blktag = blk.blk_tag().clone();
+    let to_dec = move |v: Value| match v {
+        Value::Num(n) => arithmetic::num_to_dec(&n)
+            .map_err(|e| Error::eval(&blktag, e, String::from("within this block"), None)),
+        v => Decimal::try_from(v),
+    };
+
+    Ok((arg, to_dec))
+}
+
+fn cmp_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "compare <rhs> to input.
+dates are compared by the instant they represent, irrespective of the UTC offset"
+            .into(),
+        params: vec![HelpParameter::Required("rhs:Date".into())],
+        examples: vec![HelpExample {
+            desc: "compare a date to now",
+            code: "\\ '2021-03-04' | to-date | cmp now",
+        }],
+        ..HelpMessage::new("cmp")
+    }
+}
+
+fn cmp_date_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Type::Date)?;
+    blk.assert_output(cmp::Ordering::as_type()); // all 'cmp's return an Ord
+
+    let rhs = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Date)?
+        .concrete()?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: DateTime = lhs.try_into()?;
+        let rhs: DateTime = rhs.resolve(|| lhs.into(), &cx)?.try_into()?;
+        cx.done_o(lhs.cmp(&rhs))
+    })
+}
+
+fn cmp_dur_help() -> HelpMessage {
+    HelpMessage {
+        desc: "compare <rhs> to input.".into(),
+        params: vec![HelpParameter::Required("rhs:Dur".into())],
+        examples: vec![HelpExample {
+            desc: "compare 90 minutes to an hour",
+            code: "dur 90min | cmp {dur 1h}",
+        }],
+        ..HelpMessage::new("cmp")
+    }
+}
+
+fn cmp_dur_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Type::Dur)?;
+    blk.assert_output(cmp::Ordering::as_type()); // all 'cmp's return an Ord
+
+    let rhs = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Dur)?
+        .concrete()?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: Duration = lhs.try_into()?;
+        let rhs: Duration = rhs.resolve(|| lhs.into(), &cx)?.try_into()?;
+        cx.done_o(lhs.cmp(&rhs))
+    })
+}
+
 fn cmp_ord_help() -> HelpMessage {
     HelpMessage {
         desc: "compare <rhs> to input.".into(),
@@ -336,6 +454,79 @@ fn eq_str_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn eq_dec_help() -> HelpMessage {
+    HelpMessage {
+        desc: "returns if <rhs> is equal to input
+decimals are equal if they have the same value, irrespective of trailing zeros
+a number <rhs> is converted to a decimal"
+            .into(),
+        params: vec![HelpParameter::Required("rhs:Dec".into())],
+        ..HelpMessage::new("eq")
+    }
+}
+
+fn eq_dec_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Dec)?;
+    blk.assert_output(Ty::Bool); // equals always returns a boolean value (at least our intrinsic does)
+
+    let (rhs, to_dec) = dec_rhs(&mut blk)?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: Decimal = lhs.try_into()?;
+        let rhs = to_dec(rhs.resolve(|| lhs.into(), &cx)?)?;
+        cx.done_o(lhs.eq(&rhs))
+    })
+}
+
+fn eq_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "returns if <rhs> is equal to input
+dates are equal if they represent the same instant, irrespective of the UTC offset"
+            .into(),
+        params: vec![HelpParameter::Required("rhs:Date".into())],
+        ..HelpMessage::new("eq")
+    }
+}
+
+fn eq_date_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Date)?;
+    blk.assert_output(Ty::Bool); // equals always returns a boolean value (at least our intrinsic does)
+
+    let rhs = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Date)?
+        .concrete()?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: DateTime = lhs.try_into()?;
+        let rhs: DateTime = rhs.resolve(|| lhs.into(), &cx)?.try_into()?;
+        cx.done_o(lhs.eq(&rhs))
+    })
+}
+
+fn eq_dur_help() -> HelpMessage {
+    HelpMessage {
+        desc: "returns if <rhs> is equal to input".into(),
+        params: vec![HelpParameter::Required("rhs:Dur".into())],
+        ..HelpMessage::new("eq")
+    }
+}
+
+fn eq_dur_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Dur)?;
+    blk.assert_output(Ty::Bool); // equals always returns a boolean value (at least our intrinsic does)
+
+    let rhs = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Dur)?
+        .concrete()?;
+    blk.eval_o(move |lhs, cx| {
+        let lhs: Duration = lhs.try_into()?;
+        let rhs: Duration = rhs.resolve(|| lhs.into(), &cx)?.try_into()?;
+        cx.done_o(lhs.eq(&rhs))
+    })
+}
+
 fn eq_ord_help() -> HelpMessage {
     HelpMessage {
         desc: "returns if <rhs> is equal to input".into(),
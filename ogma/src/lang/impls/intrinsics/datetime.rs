@@ -0,0 +1,462 @@
+//! Date-time commands: constructing dates with `to-date` and `now`, getting their components,
+//! and constructing durations with `dur`.
+//!
+//! Formats use `strftime` style specifiers (`%Y-%m-%d`), which are translated into the
+//! [`time`](::time) crate's format descriptions.
+use super::*;
+use ::time::{
+    error::{Parse, TryFromParsed},
+    format_description::{self, well_known::Rfc3339, FormatItem, OwnedFormatItem},
+    macros::format_description as fd,
+    parsing::Parsable,
+    Date, OffsetDateTime, PrimitiveDateTime,
+};
+
+pub fn add_intrinsics(impls: &mut Implementations) {
+    add! { impls,
+        (dur, Time)
+        (now, Time)
+
+        ("to-date", Number, to_date_num, Time)
+        ("to-date", Str, to_date_str, Time)
+
+        ("day", DateTime, day_date, Time)
+        ("hour", DateTime, hour_date, Time)
+        ("minute", DateTime, minute_date, Time)
+        ("month", DateTime, month_date, Time)
+        ("second", DateTime, second_date, Time)
+        ("weekday", DateTime, weekday_date, Time)
+        ("year", DateTime, year_date, Time)
+    };
+}
+
+// ------ Formats --------------------------------------------------------------
+/// A date-time format, translated from `strftime` specifiers.
+///
+/// The format description is parsed once, when the format is constructed.
+#[derive(Debug, Clone)]
+pub struct DateFmt(OwnedFormatItem);
+
+impl DateFmt {
+    /// Translate a `strftime` format, such as `%Y-%m-%d %H:%M`.
+    pub fn parse(fmt: &str) -> std::result::Result<Self, String> {
+        let mut desc = String::with_capacity(fmt.len() * 2);
+        let mut chars = fmt.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => desc.push_str(match chars.next() {
+                    Some('Y') => "[year]",
+                    Some('m') => "[month]",
+                    Some('d') => "[day]",
+                    Some('e') => "[day padding:space]",
+                    Some('j') => "[ordinal]",
+                    Some('H') => "[hour]",
+                    Some('I') => "[hour repr:12]",
+                    Some('p') => "[period]",
+                    Some('M') => "[minute]",
+                    Some('S') => "[second]",
+                    Some('f') => "[subsecond]",
+                    Some('b') | Some('h') => "[month repr:short]",
+                    Some('B') => "[month repr:long]",
+                    Some('a') => "[weekday repr:short]",
+                    Some('A') => "[weekday repr:long]",
+                    Some('z') => "[offset_hour sign:mandatory][offset_minute]",
+                    Some('F') => "[year]-[month]-[day]",
+                    Some('T') => "[hour]:[minute]:[second]",
+                    Some('%') => "%",
+                    Some(x) => return Err(format!("unsupported specifier `%{}`", x)),
+                    None => return Err("the format ends with a lone `%`".to_string()),
+                }),
+                '[' => desc.push_str("[["),
+                c => desc.push(c),
+            }
+        }
+
+        let items = format_description::parse(&desc).map_err(|e| e.to_string())?;
+        Ok(DateFmt(OwnedFormatItem::from(items.as_slice())))
+    }
+
+    /// Format the date-time.
+    pub fn format(&self, dt: &DateTime) -> std::result::Result<String, String> {
+        dt.format(&self.0).map_err(|e| e.to_string())
+    }
+
+    /// Parse a date-time. Formats without an offset are assumed to be UTC, and formats without a
+    /// time are assumed to be midnight.
+    pub fn parse_date(&self, s: &str) -> std::result::Result<DateTime, String> {
+        parse_with(s, &self.0).map_err(|e| e.to_string())
+    }
+}
+
+/// Parse using the most specific type the format has enough information for.
+fn parse_with<F>(s: &str, items: &F) -> std::result::Result<DateTime, Parse>
+where
+    F: Parsable + ?Sized,
+{
+    fn fallback<F>(e: Parse, f: F) -> std::result::Result<OffsetDateTime, Parse>
+    where
+        F: FnOnce() -> std::result::Result<OffsetDateTime, Parse>,
+    {
+        match e {
+            Parse::TryFromParsed(TryFromParsed::InsufficientInformation) => f(),
+            e => Err(e),
+        }
+    }
+
+    OffsetDateTime::parse(s, items)
+        .or_else(|e| {
+            fallback(e, || {
+                PrimitiveDateTime::parse(s, items).map(|x| x.assume_utc())
+            })
+        })
+        .or_else(|e| {
+            fallback(e, || {
+                Date::parse(s, items).map(|x| x.midnight().assume_utc())
+            })
+        })
+        .map(DateTime)
+}
+
+/// Parse an RFC 3339 date-time, or one of the ISO 8601 like formats that dates are displayed
+/// with.
+fn parse_date(s: &str) -> Option<DateTime> {
+    let fmts: [&[FormatItem]; 5] = [
+        fd!("[year]-[month]-[day] [hour]:[minute]:[second] [offset_hour sign:mandatory]:[offset_minute]"),
+        fd!("[year]-[month]-[day] [hour]:[minute]:[second]"),
+        fd!("[year]-[month]-[day]T[hour]:[minute]:[second]"),
+        fd!("[year]-[month]-[day] [hour]:[minute]"),
+        fd!("[year]-[month]-[day]"),
+    ];
+
+    OffsetDateTime::parse(s, &Rfc3339)
+        .map(DateTime)
+        .ok()
+        .or_else(|| fmts.iter().find_map(|f| parse_with(s, *f).ok()))
+}
+
+/// Take the optional format argument, which must be a literal so it can be checked before
+/// evaluation.
+pub fn fmt_arg(blk: &mut Block) -> Result<Option<DateFmt>> {
+    if blk.args_len() == 0 {
+        return Ok(None);
+    }
+
+    let f = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    DateFmt::parse(f.extract_literal::<Str>()?)
+        .map(Some)
+        .map_err(|e| Error {
+            cat: err::Category::Parsing,
+            desc: format!("invalid date format: {}", e),
+            traces: vec![err::Trace::from_tag(
+                &f.tag,
+                Some("invalid date format".into()),
+            )],
+            help_msg: Some(
+                "dates are formatted with `strftime` specifiers, ie `%Y-%m-%d %H:%M`".into(),
+            ),
+            hard: true,
+        })
+}
+
+/// Parse a duration such as `3d`, `90min`, or `1h30min`.
+///
+/// The span is a sequence of numbers (which can be fractional) each followed by a unit, and can be
+/// prefixed with `-` for a negative duration.
+pub fn parse_dur(s: &str) -> std::result::Result<Duration, String> {
+    const UNITS: &[(&[&str], f64)] = &[
+        (&["ns"], 1e-9),
+        (&["us", "µs"], 1e-6),
+        (&["ms"], 1e-3),
+        (&["s", "sec", "secs"], 1.0),
+        (&["m", "min", "mins"], 60.0),
+        (&["h", "hr", "hrs"], 3_600.0),
+        (&["d", "day", "days"], 86_400.0),
+        (&["w", "wk", "wks"], 604_800.0),
+    ];
+
+    let (neg, mut rem) = match s.trim().strip_prefix('-') {
+        Some(x) => (true, x),
+        None => (false, s.trim()),
+    };
+    if rem.is_empty() {
+        return Err("the duration is empty".to_string());
+    }
+
+    let mut secs = 0.0;
+    while !rem.is_empty() {
+        let i = rem
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rem.len());
+        let (num, r) = rem.split_at(i);
+        let num: f64 = num
+            .parse()
+            .map_err(|_| format!("expecting a number at `{}`", rem))?;
+
+        let i = r.find(|c: char| !c.is_alphabetic()).unwrap_or(r.len());
+        let (unit, r) = r.split_at(i);
+        let scale = UNITS
+            .iter()
+            .find(|(names, _)| names.contains(&unit))
+            .map(|x| x.1)
+            .ok_or_else(|| match unit {
+                "" => format!("`{}` is missing a unit", num),
+                x => format!("unknown unit `{}`", x),
+            })?;
+
+        secs += num * scale;
+        rem = r.trim_start();
+    }
+
+    if !secs.is_finite() || secs >= i64::MAX as f64 {
+        return Err("the duration is out of range".to_string());
+    }
+
+    let (whole, frac) = (secs.trunc(), secs.fract());
+    let d = ::time::Duration::new(whole as i64, (frac * 1e9).round() as i32);
+    Ok(Duration(if neg { -d } else { d }))
+}
+
+// ------ Dur ------------------------------------------------------------------
+fn dur_help() -> HelpMessage {
+    HelpMessage {
+        desc: "construct a duration from a span such as `3d` or `1h30min`
+units: ns, us, ms, s, m|min, h|hr, d|day, w|wk
+numbers can be fractional, ie `1.5h`"
+            .into(),
+        params: vec![HelpParameter::Required("span".into())],
+        examples: vec![
+            HelpExample {
+                desc: "three days",
+                code: "dur 3d",
+            },
+            HelpExample {
+                desc: "list the files modified in the last 90 minutes",
+                code: "ls | filter modified --Date > {now | - {dur 90min}}",
+            },
+        ],
+        ..HelpMessage::new("dur")
+    }
+}
+
+fn dur_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Dur);
+
+    let span = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |v, cx| {
+        let span: Str = span.resolve(|| v, &cx)?.try_into()?;
+        parse_dur(&span)
+            .map_err(|e| {
+                Error::eval(
+                    &blktag,
+                    format!("could not parse `{}` as a duration: {}", span, e),
+                    String::from("invalid duration"),
+                    String::from("durations are numbers followed by a unit, ie `3d` or `1h30min`"),
+                )
+            })
+            .and_then(|d| cx.done_o(d))
+    })
+}
+
+// ------ Now ------------------------------------------------------------------
+fn now_help() -> HelpMessage {
+    HelpMessage {
+        desc: "return the current date-time, in UTC".into(),
+        examples: vec![HelpExample {
+            desc: "list the files modified in the past week",
+            code: "ls | filter modified --Date > {now | - 7}",
+        }],
+        ..HelpMessage::new("now")
+    }
+}
+
+fn now_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Date);
+    blk.eval_o(|_, cx| cx.done_o(DateTime::now()))
+}
+
+// ------ To Date --------------------------------------------------------------
+fn to_date_num_help() -> HelpMessage {
+    HelpMessage {
+        desc: "convert a unix timestamp (seconds since 1970-01-01 UTC) into a date".into(),
+        examples: vec![HelpExample {
+            desc: "one billion seconds",
+            code: "\\ 1e9 | to-date",
+        }],
+        ..HelpMessage::new("to-date")
+    }
+}
+
+fn to_date_num_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Num)?;
+    blk.assert_output(Ty::Date);
+
+    let blktag = blk.blk_tag().clone();
+    blk.eval_o(move |v, cx| {
+        let secs = Number::try_from(v)?.as_f64();
+        OffsetDateTime::from_unix_timestamp_nanos((secs * 1e9) as i128)
+            .map(DateTime)
+            .map_err(|e| {
+                Error::eval(
+                    &blktag,
+                    format!("`{}` is not a valid timestamp: {}", secs, e),
+                    String::from("invalid timestamp"),
+                    None,
+                )
+            })
+            .and_then(|x| cx.done_o(x))
+    })
+}
+
+fn to_date_str_help() -> HelpMessage {
+    HelpMessage {
+        desc: "parse a string into a date
+the format uses `strftime` specifiers, ie `%d/%m/%Y %H:%M`
+without a format, RFC 3339 and `yyyy-mm-dd [hh:mm[:ss]]` dates are recognised
+dates without an offset are UTC, dates without a time are at midnight"
+            .into(),
+        params: vec![HelpParameter::Optional("fmt".into())],
+        examples: vec![
+            HelpExample {
+                desc: "parse an ISO 8601 date",
+                code: "\\ '2021-03-04' | to-date",
+            },
+            HelpExample {
+                desc: "parse a date with a format",
+                code: "\\ '04/03/2021 13:45' | to-date '%d/%m/%Y %H:%M'",
+            },
+        ],
+        ..HelpMessage::new("to-date")
+    }
+}
+
+fn to_date_str_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Str)?;
+    blk.assert_output(Ty::Date);
+
+    let fmt = fmt_arg(&mut blk)?;
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |v, cx| {
+        let s = Str::try_from(v)?;
+        let dt = match &fmt {
+            Some(fmt) => fmt.parse_date(&s).map_err(|e| {
+                Error::eval(
+                    &blktag,
+                    format!("could not parse `{}` as a date: {}", s, e),
+                    String::from("invalid date"),
+                    None,
+                )
+            }),
+            None => parse_date(&s).ok_or_else(|| {
+                Error::eval(
+                    &blktag,
+                    format!("`{}` is not a recognised date", s),
+                    String::from("invalid date"),
+                    String::from("specify the format, ie `to-date '%d/%m/%Y'`"),
+                )
+            }),
+        }?;
+
+        cx.done_o(dt)
+    })
+}
+
+// ------ Components -----------------------------------------------------------
+fn component(mut blk: Block, f: fn(&DateTime) -> i64) -> Result<Step> {
+    blk.assert_input(&Ty::Date)?;
+    blk.assert_output(Ty::Num);
+    blk.eval_o(move |v, cx| cx.done_o(Number::from(f(&DateTime::try_from(v)?))))
+}
+
+fn day_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the day of the month, from 1 to 31".into(),
+        ..HelpMessage::new("day")
+    }
+}
+
+fn day_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.day().into())
+}
+
+fn hour_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the hour of the day, from 0 to 23".into(),
+        ..HelpMessage::new("hour")
+    }
+}
+
+fn hour_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.hour().into())
+}
+
+fn minute_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the minute of the hour, from 0 to 59".into(),
+        ..HelpMessage::new("minute")
+    }
+}
+
+fn minute_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.minute().into())
+}
+
+fn month_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the month of the year, from 1 (January) to 12 (December)".into(),
+        ..HelpMessage::new("month")
+    }
+}
+
+fn month_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| u8::from(dt.month()).into())
+}
+
+fn second_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the second of the minute, from 0 to 59".into(),
+        ..HelpMessage::new("second")
+    }
+}
+
+fn second_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.second().into())
+}
+
+fn weekday_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the day of the week, from 1 (Monday) to 7 (Sunday)
+use `to-str '%A'` for the name of the day"
+            .into(),
+        ..HelpMessage::new("weekday")
+    }
+}
+
+fn weekday_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.weekday().number_from_monday().into())
+}
+
+fn year_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "the calendar year".into(),
+        examples: vec![HelpExample {
+            desc: "group files by the year they were modified",
+            code: "ls | grp-by { get --Date modified | year }",
+        }],
+        ..HelpMessage::new("year")
+    }
+}
+
+fn year_date_intrinsic(blk: Block) -> Result<Step> {
+    component(blk, |dt| dt.year().into())
+}
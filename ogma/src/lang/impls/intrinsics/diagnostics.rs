@@ -4,6 +4,7 @@ use std::time::Instant;
 pub fn add_intrinsics(impls: &mut Implementations) {
     add! { impls,
         (benchmark, Diagnostics)
+        ("cache-stats", cache_stats, Diagnostics)
         (typify, Diagnostics)
     };
 }
@@ -11,7 +12,9 @@ pub fn add_intrinsics(impls: &mut Implementations) {
 // ------ Benchmark ------------------------------------------------------------
 fn benchmark_help() -> HelpMessage {
     HelpMessage {
-        desc: "time the expression evaluation\npipes <input> to <expr>".into(),
+        desc: "time the expression evaluation\npipes <input> to <expr>
+returns a table with the `duration` of the evaluation"
+            .into(),
         params: vec![HelpParameter::Required("expr".into())],
         examples: vec![
             HelpExample {
@@ -37,17 +40,39 @@ fn benchmark_intrinsic(mut blk: Block) -> Result<Step> {
 }
 
 fn benchmark_table(start: Instant, end: Instant) -> Table {
-    let d = end - start;
-    let (ms, us) = (d.subsec_millis(), d.subsec_micros());
-    let us = us - (ms * 1000);
+    let d = Duration::from(end - start);
+    ::table::Table::from(vec![vec![o("duration")], vec![Entry::Obj(Value::Dur(d))]]).into()
+}
+
+// ------ Cache Stats ---------------------------------------------------------
+fn cache_stats_help() -> HelpMessage {
+    HelpMessage {
+        desc: "output statistics of the file cache
+the cache holds parsed files, evicting the least recently used
+files once the memory limit is exceeded"
+            .into(),
+        examples: vec![HelpExample {
+            desc: "show the number of cached files and their approximate size",
+            code: "cache-stats",
+        }],
+        ..HelpMessage::new("cache-stats")
+    }
+}
+
+fn cache_stats_intrinsic(blk: Block) -> Result<Step> {
+    blk.eval_o(|_, cx| cx.done_o(cache_stats_table(rt::cache_stats())))
+}
+
+fn cache_stats_table(stats: rt::CacheStats) -> Table {
     ::table::Table::from(vec![
+        vec![o("entries"), o("bytes"), o("limit"), o("hits"), o("misses")],
         vec![
-            o("duration"),
-            o("seconds"),
-            o("milliseconds"),
-            o("microseconds"),
+            n(stats.entries as u64),
+            n(stats.bytes as u64),
+            n(stats.limit as u64),
+            n(stats.hits),
+            n(stats.misses),
         ],
-        vec![o(format!("{:?}", d)), n(d.as_secs()), n(ms), n(us)],
     ])
     .into()
 }
@@ -0,0 +1,473 @@
+//! Columnar file reading and writing: Apache Parquet and Arrow IPC.
+//!
+//! Numeric, boolean, string, decimal, and date columns map onto [`Value`]s, other column types
+//! are read as their string representation. Only the projected columns (`--cols`) are read from
+//! the file.
+//!
+//! Requires the `columnar` feature, without it reading or writing a columnar file is an error.
+use super::*;
+#[cfg(feature = "columnar")]
+use ::arrow::{
+    array::{
+        Array, ArrayRef, AsArray, BooleanArray, Decimal128Array, Float64Array, Int64Array,
+        StringArray, TimestampMicrosecondArray,
+    },
+    compute::cast,
+    datatypes::{
+        DataType, Decimal128Type, Field, Float64Type, Int64Type, Schema, SchemaRef, TimeUnit,
+        UInt64Type,
+    },
+    ipc::{reader::FileReader, writer::FileWriter},
+    record_batch::RecordBatch,
+    util::display::array_value_to_string,
+};
+#[cfg(feature = "columnar")]
+use ::parquet::arrow::{
+    arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter, ProjectionMask,
+};
+#[cfg(feature = "columnar")]
+use ::time::OffsetDateTime;
+#[cfg(feature = "columnar")]
+use std::fmt;
+
+/// The number of table rows written per record batch.
+#[cfg(feature = "columnar")]
+const BATCH_ROWS: usize = 1 << 16;
+
+/// The columnar formats.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Parquet,
+    Ipc,
+}
+
+impl Format {
+    fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "parquet" | "pq" => Some(Format::Parquet),
+            "arrow" | "ipc" | "feather" => Some(Format::Ipc),
+            _ => None,
+        }
+    }
+}
+
+/// Returns if the path has a Parquet or Arrow IPC extension.
+pub fn is_columnar(path: &Path) -> bool {
+    Format::from_path(path).is_some()
+}
+
+#[cfg(not(feature = "columnar"))]
+fn disabled() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "parquet and arrow files are not supported, enable the `columnar` feature",
+    )
+}
+
+/// Read the file into a table, reading only the columns in `cols` (in that order) if specified.
+#[cfg(not(feature = "columnar"))]
+pub fn read(_path: &Path, _cols: Option<&[Str]>) -> io::Result<Table> {
+    Err(disabled())
+}
+
+/// Write a table as Apache Parquet.
+#[cfg(not(feature = "columnar"))]
+pub fn write_parquet(_: &mut dyn Write, _: Value, _: &writers::WriteOpts) -> io::Result<()> {
+    Err(disabled())
+}
+
+/// Write a table as an Arrow IPC file.
+#[cfg(not(feature = "columnar"))]
+pub fn write_ipc(_: &mut dyn Write, _: Value, _: &writers::WriteOpts) -> io::Result<()> {
+    Err(disabled())
+}
+
+#[cfg(feature = "columnar")]
+fn err<E: fmt::Display>(e: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e.to_string())
+}
+
+/// Get the index of each column in `cols`, or all the columns if `None`.
+#[cfg(feature = "columnar")]
+fn projection(schema: &Schema, cols: Option<&[Str]>) -> io::Result<Vec<usize>> {
+    match cols {
+        Some(cols) => cols
+            .iter()
+            .map(|c| {
+                schema.index_of(c).map_err(|_| {
+                    io::Error::new(io::ErrorKind::NotFound, format!("column `{}` not found", c))
+                })
+            })
+            .collect(),
+        None => Ok((0..schema.fields().len()).collect()),
+    }
+}
+
+/// Read the file into a table, reading only the columns in `cols` (in that order) if specified.
+#[cfg(feature = "columnar")]
+pub fn read(path: &Path, cols: Option<&[Str]>) -> io::Result<Table> {
+    let file = std::fs::File::open(path)?;
+    let format = Format::from_path(path).expect("only columnar files are read");
+
+    let (schema, batches): (SchemaRef, Vec<RecordBatch>) = match format {
+        Format::Parquet => {
+            let builder = ParquetRecordBatchReaderBuilder::try_new(file).map_err(err)?;
+            let idxs = projection(builder.schema(), cols)?;
+            let schema = builder.schema().project(&idxs).map_err(err)?;
+            let mask = ProjectionMask::roots(builder.parquet_schema(), idxs);
+            let batches = builder
+                .with_projection(mask)
+                .build()
+                .map_err(err)?
+                .collect::<std::result::Result<_, _>>()
+                .map_err(err)?;
+            (Arc::new(schema), batches)
+        }
+        Format::Ipc => {
+            // the schema is read from the footer first to build the projection,
+            // the same handle is then re-read (the reader seeks) with the projection applied
+            let mut file = io::BufReader::new(file);
+            let schema = FileReader::try_new(&mut file, None).map_err(err)?.schema();
+            let idxs = projection(&schema, cols)?;
+            let reader = FileReader::try_new(file, Some(idxs)).map_err(err)?;
+            let schema = reader.schema();
+            let batches = reader.collect::<std::result::Result<_, _>>().map_err(err)?;
+            (schema, batches)
+        }
+    };
+
+    // projections keep the file's column order, so the columns are looked up by name
+    let names = match cols {
+        Some(cols) => cols.iter().map(|c| c.to_string()).collect::<Vec<_>>(),
+        None => schema.fields().iter().map(|f| f.name().clone()).collect(),
+    };
+
+    let mut rows = vec![names.iter().map(|n| o(n.as_str())).collect::<Vec<_>>()];
+    for batch in batches {
+        let columns = names
+            .iter()
+            .map(|n| {
+                let i = batch.schema().index_of(n).map_err(err)?;
+                column(batch.column(i))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+
+        for i in 0..batch.num_rows() {
+            rows.push(columns.iter().map(|c| c[i].clone()).collect());
+        }
+    }
+
+    Ok(Table::from(::table::Table::from(rows)))
+}
+
+/// Convert an arrow column into table entries.
+#[cfg(feature = "columnar")]
+fn column(col: &ArrayRef) -> io::Result<Vec<Entry<Value>>> {
+    use DataType::*;
+
+    fn entries<T, F: Fn(T) -> Entry<Value>>(
+        iter: impl Iterator<Item = Option<T>>,
+        f: F,
+    ) -> Vec<Entry<Value>> {
+        iter.map(|x| x.map(&f).unwrap_or(Entry::Nil)).collect()
+    }
+
+    fn try_entries<T, F: Fn(T) -> io::Result<Entry<Value>>>(
+        iter: impl Iterator<Item = Option<T>>,
+        f: F,
+    ) -> io::Result<Vec<Entry<Value>>> {
+        iter.map(|x| x.map(&f).unwrap_or(Ok(Entry::Nil))).collect()
+    }
+
+    let x = match col.data_type() {
+        Null => vec![Entry::Nil; col.len()],
+        Boolean => entries(col.as_boolean().iter(), |b| Entry::Obj(Value::Bool(b))),
+        Int8 | Int16 | Int32 | Int64 | UInt8 | UInt16 | UInt32 => {
+            let col = cast(col, &Int64).map_err(err)?;
+            entries(col.as_primitive::<Int64Type>().iter(), n)
+        }
+        UInt64 => entries(col.as_primitive::<UInt64Type>().iter(), n),
+        // decimals which fit are kept exact, otherwise they are read as floats below
+        Decimal128(_, scale) if (0..=28).contains(scale) => {
+            let scale = *scale as u32;
+            let decs = try_entries(col.as_primitive::<Decimal128Type>().iter(), |m| {
+                Decimal::try_from_i128_with_scale(m, scale)
+                    .map(|d| Entry::Obj(Value::Dec(d)))
+                    .map_err(err)
+            });
+            match decs {
+                Ok(x) => x,
+                Err(_) => floats(col)?,
+            }
+        }
+        Float16 | Float32 | Float64 | Decimal128(..) | Decimal256(..) => floats(col)?,
+        Date32 | Date64 | Timestamp(..) => {
+            // the number of nanoseconds in each unit
+            let nanos: i128 = match col.data_type() {
+                Date32 => 86_400_000_000_000,
+                Date64 | Timestamp(TimeUnit::Millisecond, _) => 1_000_000,
+                Timestamp(TimeUnit::Second, _) => 1_000_000_000,
+                Timestamp(TimeUnit::Microsecond, _) => 1_000,
+                _ => 1,
+            };
+            let col = cast(col, &Int64).map_err(err)?;
+            try_entries(col.as_primitive::<Int64Type>().iter(), |x| {
+                OffsetDateTime::from_unix_timestamp_nanos(x as i128 * nanos)
+                    .map(|d| Entry::Obj(Value::Date(DateTime(d))))
+                    .map_err(err)
+            })?
+        }
+        Utf8 => entries(col.as_string::<i32>().iter(), o),
+        LargeUtf8 => entries(col.as_string::<i64>().iter(), o),
+        _ => (0..col.len())
+            .map(|i| {
+                if col.is_null(i) {
+                    Ok(Entry::Nil)
+                } else {
+                    array_value_to_string(col, i).map(o).map_err(err)
+                }
+            })
+            .collect::<io::Result<_>>()?,
+    };
+
+    Ok(x)
+}
+
+#[cfg(feature = "columnar")]
+fn floats(col: &ArrayRef) -> io::Result<Vec<Entry<Value>>> {
+    let col = cast(col, &DataType::Float64).map_err(err)?;
+    Ok(col
+        .as_primitive::<Float64Type>()
+        .iter()
+        .map(|x| x.map(n).unwrap_or(Entry::Nil))
+        .collect())
+}
+
+/// Get the number as an `i64` if it is integral and fits, without going through a float.
+#[cfg(feature = "columnar")]
+fn int64(n: &Number) -> Option<i64> {
+    n.as_i128().ok().and_then(|x| i64::try_from(x).ok())
+}
+
+/// Infer the schema of the table: integral numbers, numbers, booleans, decimals, dates, or
+/// strings. All the rows are checked.
+#[cfg(feature = "columnar")]
+fn schema(table: &Table) -> io::Result<Schema> {
+    let fmtr = &mut numfmt::Formatter::new();
+    let header = table
+        .row(0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "cannot save an empty table"))?
+        .map(|e| print::fmt_cell(e, fmtr).to_string())
+        .collect::<Vec<_>>();
+
+    let fields = header
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let values = table
+                .rows()
+                .skip(1)
+                .map(move |mut r| r.nth(i).map(Value::from).unwrap_or(Value::Nil));
+            Field::new(name, infer(values), true)
+        })
+        .collect::<Vec<_>>();
+
+    Ok(Schema::new(fields))
+}
+
+/// Infer the column type from the values.
+#[cfg(feature = "columnar")]
+fn infer<I: Iterator<Item = Value>>(values: I) -> DataType {
+    use DataType::*;
+
+    let mut ty = None;
+    for v in values {
+        let x = match v {
+            Value::Nil => continue,
+            Value::Num(n) if int64(&n).is_some() => Int64,
+            Value::Num(_) => Float64,
+            Value::Bool(_) => Boolean,
+            Value::Dec(d) => Decimal128(38, d.scale() as i8),
+            Value::Date(_) => Timestamp(TimeUnit::Microsecond, Some("UTC".into())),
+            _ => Utf8,
+        };
+        ty = Some(match (ty, x) {
+            (None, x) => x,
+            (Some(a), b) if a == b => a,
+            (Some(Int64), Float64) | (Some(Float64), Int64) => Float64,
+            (Some(Decimal128(p, a)), Decimal128(_, b)) => Decimal128(p, a.max(b)),
+            _ => Utf8,
+        });
+    }
+
+    ty.unwrap_or(Utf8)
+}
+
+/// Convert `len` table rows starting at `start` (excluding the header) into a record batch.
+#[cfg(feature = "columnar")]
+fn to_batch(
+    table: &Table,
+    schema: &SchemaRef,
+    start: usize,
+    len: usize,
+) -> io::Result<RecordBatch> {
+    let fmtr = &mut numfmt::Formatter::new();
+
+    let mut cols: Vec<ArrayRef> = Vec::with_capacity(schema.fields().len());
+    for (i, field) in schema.fields().iter().enumerate() {
+        let values = || {
+            table
+                .rows()
+                .skip(1 + start)
+                .take(len)
+                .map(move |mut r| r.nth(i).map(Value::from).unwrap_or(Value::Nil))
+        };
+
+        let col: ArrayRef = match field.data_type() {
+            DataType::Int64 => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Num(n) => int64(&n),
+                        _ => None,
+                    })
+                    .collect::<Int64Array>(),
+            ),
+            DataType::Float64 => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Num(n) => Some(n.as_f64()),
+                        _ => None,
+                    })
+                    .collect::<Float64Array>(),
+            ),
+            DataType::Boolean => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Bool(b) => Some(b),
+                        _ => None,
+                    })
+                    .collect::<BooleanArray>(),
+            ),
+            DataType::Decimal128(p, s) => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Dec(mut d) => {
+                            d.rescale(*s as u32);
+                            Some(d.mantissa())
+                        }
+                        _ => None,
+                    })
+                    .collect::<Decimal128Array>()
+                    .with_precision_and_scale(*p, *s)
+                    .map_err(err)?,
+            ),
+            DataType::Timestamp(_, tz) => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Date(d) => Some((d.0.unix_timestamp_nanos() / 1_000) as i64),
+                        _ => None,
+                    })
+                    .collect::<TimestampMicrosecondArray>()
+                    .with_timezone_opt(tz.clone()),
+            ),
+            _ => Arc::new(
+                values()
+                    .map(|v| match v {
+                        Value::Nil => None,
+                        v => Some(print::fmt_cell(&Entry::from(v), fmtr).to_string()),
+                    })
+                    .collect::<StringArray>(),
+            ),
+        };
+
+        cols.push(col);
+    }
+
+    RecordBatch::try_new(schema.clone(), cols).map_err(err)
+}
+
+/// Infer the schema of a table value and convert it into record batches of [`BATCH_ROWS`].
+#[cfg(feature = "columnar")]
+fn to_batches(
+    value: Value,
+) -> io::Result<(SchemaRef, impl Iterator<Item = io::Result<RecordBatch>>)> {
+    let table = match value {
+        Value::Tab(t) => t,
+        x => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "only tables can be saved as parquet or arrow, found `{}`",
+                    x.ty()
+                ),
+            ))
+        }
+    };
+
+    let schema = Arc::new(schema(&table)?);
+    let rows = table.rows_len().saturating_sub(1);
+    let s = schema.clone();
+    // an empty table is still written as a single (empty) batch
+    let batches = (0..rows.max(1))
+        .step_by(BATCH_ROWS)
+        .map(move |start| to_batch(&table, &s, start, BATCH_ROWS.min(rows - start)));
+
+    Ok((schema, batches))
+}
+
+/// A buffer shared with the parquet writer, which requires a `Send` writer.
+///
+/// The buffer is drained into the output file after each row group is flushed, so only a single
+/// row group is held in memory.
+#[cfg(feature = "columnar")]
+#[derive(Default, Clone)]
+struct SharedBuf(Arc<crate::Mutex<Vec<u8>>>);
+
+#[cfg(feature = "columnar")]
+impl SharedBuf {
+    fn drain_into(&self, file: &mut dyn Write) -> io::Result<()> {
+        let mut buf = self.0.lock();
+        file.write_all(&buf)?;
+        buf.clear();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "columnar")]
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Write a table as Apache Parquet.
+#[cfg(feature = "columnar")]
+pub fn write_parquet(file: &mut dyn Write, value: Value, _: &writers::WriteOpts) -> io::Result<()> {
+    let (schema, batches) = to_batches(value)?;
+    let buf = SharedBuf::default();
+    let mut wtr = ArrowWriter::try_new(buf.clone(), schema, None).map_err(err)?;
+    for batch in batches {
+        wtr.write(&batch?).map_err(err)?;
+        wtr.flush().map_err(err)?;
+        buf.drain_into(file)?;
+    }
+    wtr.close().map_err(err)?;
+    buf.drain_into(file)
+}
+
+/// Write a table as an Arrow IPC file.
+#[cfg(feature = "columnar")]
+pub fn write_ipc(file: &mut dyn Write, value: Value, _: &writers::WriteOpts) -> io::Result<()> {
+    let (schema, batches) = to_batches(value)?;
+    let mut wtr = FileWriter::try_new(file, &schema).map_err(err)?;
+    for batch in batches {
+        wtr.write(&batch?).map_err(err)?;
+    }
+    wtr.finish().map_err(err)
+}
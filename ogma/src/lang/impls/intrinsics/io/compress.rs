@@ -0,0 +1,123 @@
+//! Transparent compression of files.
+//!
+//! Compression is detected by the file extension (`.gz`, `.zst`), or when reading, by the
+//! leading magic bytes.
+use super::*;
+use std::path::Path;
+
+/// A supported compression format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+impl Compression {
+    /// Detect compression from the path's extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_lowercase();
+        match ext.as_str() {
+            "gz" | "gzip" => Some(Compression::Gzip),
+            "zst" | "zstd" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Detect compression from the leading magic bytes.
+    pub fn sniff(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Decompress the `bytes`.
+    pub fn decompress(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        use std::io::Read;
+
+        let mut buf = Vec::new();
+        match self {
+            Compression::Gzip => {
+                ::flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut buf)?;
+            }
+            Compression::Zstd => {
+                ::zstd::stream::read::Decoder::new(bytes)?.read_to_end(&mut buf)?;
+            }
+        }
+        Ok(buf)
+    }
+
+    /// Compress everything written by `f` into `wtr`.
+    pub fn write<W, F>(self, wtr: W, f: F) -> io::Result<()>
+    where
+        W: Write,
+        F: FnOnce(&mut dyn Write) -> io::Result<()>,
+    {
+        match self {
+            Compression::Gzip => {
+                let mut e = ::flate2::write::GzEncoder::new(wtr, ::flate2::Compression::default());
+                f(&mut e)?;
+                e.finish()?.flush()
+            }
+            Compression::Zstd => {
+                let mut e = ::zstd::stream::write::Encoder::new(wtr, 0)?;
+                f(&mut e)?;
+                e.finish()?.flush()
+            }
+        }
+    }
+}
+
+/// Read the file, decompressing if compression is detected.
+pub fn read_bytes(path: &Path) -> io::Result<Vec<u8>> {
+    let bytes = std::fs::read(path)?;
+    match Compression::from_path(path).or_else(|| Compression::sniff(&bytes)) {
+        Some(c) => c.decompress(&bytes),
+        None => Ok(bytes),
+    }
+}
+
+/// Strips a compression extension off the path, such that `foo.csv.gz` becomes `foo.csv`.
+pub fn strip_ext(path: &Path) -> &Path {
+    match Compression::from_path(path) {
+        Some(_) => path.file_stem().map(Path::new).unwrap_or(path),
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detection() {
+        let f = |p| Compression::from_path(Path::new(p));
+        assert_eq!(f("foo.csv.gz"), Some(Compression::Gzip));
+        assert_eq!(f("foo.csv.GZ"), Some(Compression::Gzip));
+        assert_eq!(f("foo.csv.zst"), Some(Compression::Zstd));
+        assert_eq!(f("foo.csv"), None);
+        assert_eq!(f("foo"), None);
+
+        assert_eq!(strip_ext(Path::new("foo.csv.gz")), Path::new("foo.csv"));
+        assert_eq!(strip_ext(Path::new("foo.tsv.zst")), Path::new("foo.tsv"));
+        assert_eq!(strip_ext(Path::new("foo.csv")), Path::new("foo.csv"));
+    }
+
+    #[test]
+    fn round_trip() {
+        for c in [Compression::Gzip, Compression::Zstd] {
+            let mut buf = Vec::new();
+            c.write(&mut buf, |w| write!(w, "a,b\n1,2")).unwrap();
+            assert_eq!(Compression::sniff(&buf), Some(c));
+            assert_eq!(c.decompress(&buf).unwrap(), b"a,b\n1,2");
+        }
+
+        assert_eq!(Compression::sniff(b"a,b\n1,2"), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! External process execution: `exec`.
+//!
+//! Running processes is disabled by default, and must be enabled through
+//! [`Definitions::set_allow_exec`] (the `--allow-exec` flag when using the binary).
+use super::*;
+use std::process::{Command, Stdio};
+
+pub fn add_intrinsics(impls: &mut Implementations) {
+    add! { impls,
+        (exec, Io)
+    };
+}
+
+fn exec_help() -> HelpMessage {
+    HelpMessage {
+        desc: "run an external command, returning its standard output as a string
+a string input is piped to the command's stdin, a table input is piped as csv
+the command runs in the current working directory
+a non-zero exit status is an error, which includes the command's stderr
+flags for the command need to be quoted, ie `exec ls '-l'`
+process execution is disabled by default, enable with `ogma --allow-exec`"
+            .into(),
+        params: vec![
+            HelpParameter::Required("command".into()),
+            HelpParameter::Optional("args..".into()),
+        ],
+        flags: vec![("table", "parse the standard output as a csv table")],
+        examples: vec![
+            HelpExample {
+                desc: "list the git status",
+                code: "exec git status '--short'",
+            },
+            HelpExample {
+                desc: "pipe a table through an external filter",
+                code: "open file.csv | exec --table grep 'foo'",
+            },
+        ],
+        ..HelpMessage::new("exec")
+    }
+}
+
+fn exec_intrinsic(mut blk: Block) -> Result<Step> {
+    if !blk.defs().allow_exec() {
+        return Err(Error::exec_disabled(blk.op_tag()));
+    }
+
+    let stdin = match blk.in_ty() {
+        Ty::Nil => false,
+        Ty::Str | Ty::Tab => true,
+        x => return Err(Error::wrong_op_input_type(x, blk.op_tag())),
+    };
+
+    let table = blk.get_flag("table").is_some();
+    blk.assert_output(if table { Ty::Tab } else { Ty::Str });
+
+    if blk.args_len() == 0 {
+        return Err(Error::insufficient_args(blk.blk_tag(), 0, None));
+    }
+    let mut args = Vec::with_capacity(blk.args_len());
+    for _ in 0..blk.args_len() {
+        args.push(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        );
+    }
+
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |val, cx| {
+        let args = args
+            .iter()
+            .map(|arg| arg.resolve(|| val.clone(), &cx).and_then(Str::try_from))
+            .collect::<Result<Vec<_>>>()?;
+        let input = if stdin { Some(val) } else { None };
+
+        let stdout = run(&args, input, &cx).map_err(|e| Error::io(&blktag, e))?;
+
+        if table {
+            readers::read_table(Path::new("stdout.csv"), &stdout, &Default::default())
+                .map_err(|e| Error::io(&blktag, e))
+                .and_then(|t| cx.done_o(t))
+        } else {
+            cx.done_o(Str::from(stdout))
+        }
+    })
+}
+
+/// Spawns the command (the first of `args`), feeding `input` to stdin, and waits for it to
+/// finish. Returns stdout.
+fn run(args: &[Str], input: Option<Value>, cx: &Context) -> io::Result<String> {
+    let (cmd, args) = args.split_first().expect("at least one argument");
+
+    let mut child = Command::new(cmd.as_str())
+        .args(args.iter().map(|x| x.as_str()))
+        .current_dir(cx.root.join(cx.wd))
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", e, cmd)))?;
+
+    // stdin is written on another thread so a command filling its stdout pipe does not deadlock
+    let writer = match (input, child.stdin.take()) {
+        (Some(value), Some(mut pipe)) => {
+            let mut buf = Vec::new();
+            match value {
+                Value::Str(s) => buf.extend_from_slice(s.as_bytes()),
+                value => writers::write_value(
+                    Path::new("stdin.csv"),
+                    &mut buf,
+                    value,
+                    &Default::default(),
+                )?,
+            }
+            Some(std::thread::spawn(move || pipe.write_all(&buf)))
+        }
+        _ => None,
+    };
+
+    let output = child.wait_with_output()?;
+    if let Some(writer) = writer {
+        match writer.join() {
+            // the command may not read all of stdin, which is fine
+            Ok(Err(e)) if e.kind() != io::ErrorKind::BrokenPipe => return Err(e),
+            _ => (),
+        }
+    }
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            format!(
+                "`{}` failed with {}: {}",
+                cmd,
+                output.status,
+                stderr.trim_end()
+            ),
+        ));
+    }
+
+    String::from_utf8(output.stdout).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
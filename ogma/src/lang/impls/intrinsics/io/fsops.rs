@@ -0,0 +1,458 @@
+//! Filesystem mutation commands: `mkdir`, `rm`, `cp`, and `mv`.
+//!
+//! Each command accepts a single path, or a table of paths for bulk operations, and outputs a
+//! table of the actions taken. Paths are resolved through the [`rt::Sandbox`].
+use super::*;
+
+pub fn add_intrinsics(impls: &mut Implementations) {
+    add! { impls,
+        ("cp", Table, cp_table, Io)
+        (cp, Io)
+
+        ("mkdir", Table, mkdir_table, Io)
+        (mkdir, Io)
+
+        ("mv", Table, mv_table, Io)
+        (mv, Io)
+
+        ("rm", Table, rm_table, Io)
+        (rm, Io)
+    };
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Cp,
+    Mkdir,
+    Mv,
+    Rm,
+}
+
+/// The resolved source, and destination, of an action.
+type Resolved = (PathBuf, Option<PathBuf>);
+
+impl Op {
+    fn name(self) -> &'static str {
+        match self {
+            Op::Cp => "cp",
+            Op::Mkdir => "mkdir",
+            Op::Mv => "mv",
+            Op::Rm => "rm",
+        }
+    }
+
+    fn has_dest(self) -> bool {
+        matches!(self, Op::Cp | Op::Mv)
+    }
+
+    /// Resolve the paths and apply the operation. If `dry_run`, the paths are resolved and
+    /// checked, but nothing is changed.
+    fn apply(
+        self,
+        from: &str,
+        to: Option<&str>,
+        dry_run: bool,
+        cx: &Context,
+    ) -> io::Result<Resolved> {
+        let sandbox = cx.sandbox;
+        let (root, wd) = (cx.root, cx.wd);
+
+        // if the destination is an existing directory, the source is placed within it
+        let dest = |src: &Path| {
+            let to = to.unwrap_or_default();
+            sandbox
+                .write(root, wd, Path::new(to))
+                .map(|d| match src.file_name() {
+                    Some(name) if d.is_dir() => d.join(name),
+                    _ => d,
+                })
+        };
+
+        match self {
+            Op::Cp => {
+                let src = sandbox.read(root, wd, Path::new(from))?;
+                if src.is_dir() {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("cannot copy a directory: {}", from),
+                    ));
+                }
+                let dst = dest(&src)?;
+                if !dry_run {
+                    mkdirs(&dst).and_then(|_| std::fs::copy(&src, &dst))?;
+                }
+                Ok((src, Some(dst)))
+            }
+            Op::Mkdir => {
+                let p = sandbox.write(root, wd, Path::new(from))?;
+                if !dry_run {
+                    std::fs::create_dir_all(&p)?;
+                }
+                Ok((p, None))
+            }
+            Op::Mv => {
+                let src = sandbox.modify(root, wd, Path::new(from))?;
+                src.symlink_metadata()
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", e, from)))?;
+                let dst = dest(&src)?;
+                if !dry_run {
+                    mkdirs(&dst).and_then(|_| std::fs::rename(&src, &dst))?;
+                }
+                Ok((src, Some(dst)))
+            }
+            Op::Rm => {
+                let p = sandbox.modify(root, wd, Path::new(from))?;
+                let meta = p
+                    .symlink_metadata()
+                    .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", e, from)))?;
+                if !dry_run {
+                    if meta.is_dir() {
+                        std::fs::remove_dir(&p)?;
+                    } else {
+                        std::fs::remove_file(&p)?;
+                    }
+                }
+                Ok((p, None))
+            }
+        }
+    }
+}
+
+/// The flag shared by the commands.
+const DRY_RUN: (&str, &str) = (
+    "dry-run",
+    "resolve and check the paths without making changes",
+);
+
+/// The outcome of an action, as a table row.
+struct Outcome {
+    path: String,
+    to: Option<String>,
+    result: String,
+}
+
+impl Outcome {
+    fn new(from: &str, to: Option<&str>, resolved: io::Result<Resolved>, dry_run: bool) -> Self {
+        match resolved {
+            Ok((path, to)) => Outcome {
+                path: path.display().to_string(),
+                to: to.map(|x| x.display().to_string()),
+                result: if dry_run { "dry-run" } else { "ok" }.to_string(),
+            },
+            Err(e) => Outcome {
+                path: from.to_string(),
+                to: to.map(ToString::to_string),
+                result: e.to_string(),
+            },
+        }
+    }
+}
+
+fn outcomes_table<I: IntoIterator<Item = Outcome>>(op: Op, outcomes: I) -> Table {
+    let header = vec![o("action"), o("path"), o("to"), o("result")];
+    let rows = outcomes.into_iter().map(|x| {
+        vec![
+            o(op.name()),
+            o(x.path),
+            x.to.map(o).unwrap_or(Entry::Nil),
+            o(x.result),
+        ]
+    });
+
+    ::table::Table::from(once(header).chain(rows).collect::<Vec<_>>()).into()
+}
+
+/// Operates on a single path (and destination), errors are returned.
+fn path_intrinsic(op: Op, mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Tab); // always outputs a table
+
+    let dry_run = blk.get_flag(DRY_RUN.0).is_some();
+    let from = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let to = if op.has_dest() {
+        Some(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        )
+    } else {
+        None
+    };
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |val, cx| {
+        let from: Str = from.resolve(|| val.clone(), &cx)?.try_into()?;
+        let to: Option<Str> = to
+            .as_ref()
+            .map(|to| to.resolve(|| val.clone(), &cx).and_then(Str::try_from))
+            .transpose()?;
+        let to = to.as_deref();
+
+        let resolved = op
+            .apply(&from, to, dry_run, &cx)
+            .map_err(|e| Error::io(&blktag, e))?;
+        let outcome = Outcome::new(&from, to, Ok(resolved), dry_run);
+        cx.done_o(outcomes_table(op, once(outcome)))
+    })
+}
+
+/// Operates on each path in a table column, errors are reported in the result column.
+fn table_intrinsic(op: Op, mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Tab)?;
+    blk.assert_output(Ty::Tab);
+
+    let dry_run = blk.get_flag(DRY_RUN.0).is_some();
+    let to = if op.has_dest() {
+        Some(
+            blk.next_arg()?
+                .supplied(Ty::Nil)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        )
+    } else {
+        None
+    };
+    let col = if blk.args_len() > 0 {
+        Some(
+            blk.next_arg()?
+                .supplied(Ty::Nil)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        )
+    } else {
+        None
+    };
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |table, cx| {
+        let table = Table::try_from(table)?;
+        let (colname, tag) = match &col {
+            Some(col) => (Str::try_from(col.resolve(|| Value::Nil, &cx)?)?, &col.tag),
+            None => (Str::new("path"), &blktag),
+        };
+        let idx = TableRow::col_idx(&table, &colname, tag)?;
+        let to: Option<Str> = to
+            .as_ref()
+            .map(|to| to.resolve(|| Value::Nil, &cx).and_then(Str::try_from))
+            .transpose()?;
+        let to = to.as_deref();
+
+        let outcomes = table
+            .rows()
+            .skip(1)
+            .map(|mut row| match row.nth(idx) {
+                Some(Entry::Obj(Value::Str(from))) => {
+                    Outcome::new(from, to, op.apply(from, to, dry_run, &cx), dry_run)
+                }
+                x => Outcome {
+                    path: x
+                        .map(|x| print::fmt_cell(x, &mut numfmt::Formatter::new()).to_string())
+                        .unwrap_or_default(),
+                    to: to.map(ToString::to_string),
+                    result: "expecting a string path".to_string(),
+                },
+            })
+            .collect::<Vec<_>>();
+
+        cx.done_o(outcomes_table(op, outcomes))
+    })
+}
+
+// ------ Cp -------------------------------------------------------------------
+fn cp_help() -> HelpMessage {
+    HelpMessage {
+        desc: "copy a file
+if `to` is an existing directory, the file is copied into it
+outputs a table of the actions taken"
+            .into(),
+        params: vec![
+            HelpParameter::Required("from".into()),
+            HelpParameter::Required("to".into()),
+        ],
+        flags: vec![DRY_RUN],
+        examples: vec![
+            HelpExample {
+                desc: "copy a file",
+                code: "cp file.csv backup.csv",
+            },
+            HelpExample {
+                desc: "copy a file into a directory",
+                code: "cp file.csv backups",
+            },
+        ],
+        ..HelpMessage::new("cp")
+    }
+}
+
+fn cp_intrinsic(blk: Block) -> Result<Step> {
+    path_intrinsic(Op::Cp, blk)
+}
+
+fn cp_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "copy each file listed in a column of the table
+the column defaults to `path`, errors are noted in the result column"
+            .into(),
+        params: vec![
+            HelpParameter::Required("to".into()),
+            HelpParameter::Optional("col".into()),
+        ],
+        flags: vec![DRY_RUN],
+        examples: vec![HelpExample {
+            desc: "copy all csv files into a directory",
+            code: "ls --glob '*.csv' | cp backups",
+        }],
+        ..HelpMessage::new("cp")
+    }
+}
+
+fn cp_table_intrinsic(blk: Block) -> Result<Step> {
+    table_intrinsic(Op::Cp, blk)
+}
+
+// ------ Mkdir ----------------------------------------------------------------
+fn mkdir_help() -> HelpMessage {
+    HelpMessage {
+        desc: "create a directory, and any missing parent directories
+outputs a table of the actions taken"
+            .into(),
+        params: vec![HelpParameter::Required("path".into())],
+        flags: vec![DRY_RUN],
+        examples: vec![HelpExample {
+            desc: "create a directory",
+            code: "mkdir path/to/dir",
+        }],
+        ..HelpMessage::new("mkdir")
+    }
+}
+
+fn mkdir_intrinsic(blk: Block) -> Result<Step> {
+    path_intrinsic(Op::Mkdir, blk)
+}
+
+fn mkdir_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "create each directory listed in a column of the table
+the column defaults to `path`, errors are noted in the result column"
+            .into(),
+        params: vec![HelpParameter::Optional("col".into())],
+        flags: vec![DRY_RUN],
+        examples: vec![HelpExample {
+            desc: "create the directories listed in a file",
+            code: "open dirs.csv | mkdir dir",
+        }],
+        ..HelpMessage::new("mkdir")
+    }
+}
+
+fn mkdir_table_intrinsic(blk: Block) -> Result<Step> {
+    table_intrinsic(Op::Mkdir, blk)
+}
+
+// ------ Mv -------------------------------------------------------------------
+fn mv_help() -> HelpMessage {
+    HelpMessage {
+        desc: "move (rename) a file or directory
+if `to` is an existing directory, the item is moved into it
+outputs a table of the actions taken"
+            .into(),
+        params: vec![
+            HelpParameter::Required("from".into()),
+            HelpParameter::Required("to".into()),
+        ],
+        flags: vec![DRY_RUN],
+        examples: vec![
+            HelpExample {
+                desc: "rename a file",
+                code: "mv file.csv renamed.csv",
+            },
+            HelpExample {
+                desc: "preview moving a file into a directory",
+                code: "mv --dry-run file.csv archive",
+            },
+        ],
+        ..HelpMessage::new("mv")
+    }
+}
+
+fn mv_intrinsic(blk: Block) -> Result<Step> {
+    path_intrinsic(Op::Mv, blk)
+}
+
+fn mv_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "move each item listed in a column of the table
+the column defaults to `path`, errors are noted in the result column"
+            .into(),
+        params: vec![
+            HelpParameter::Required("to".into()),
+            HelpParameter::Optional("col".into()),
+        ],
+        flags: vec![DRY_RUN],
+        examples: vec![HelpExample {
+            desc: "move all log files into an archive directory",
+            code: "ls --glob '*.log' | mv archive",
+        }],
+        ..HelpMessage::new("mv")
+    }
+}
+
+fn mv_table_intrinsic(blk: Block) -> Result<Step> {
+    table_intrinsic(Op::Mv, blk)
+}
+
+// ------ Rm -------------------------------------------------------------------
+fn rm_help() -> HelpMessage {
+    HelpMessage {
+        desc: "remove a file or empty directory
+symlinks are removed, not their targets
+outputs a table of the actions taken"
+            .into(),
+        params: vec![HelpParameter::Required("path".into())],
+        flags: vec![DRY_RUN],
+        examples: vec![
+            HelpExample {
+                desc: "remove a file",
+                code: "rm file.csv",
+            },
+            HelpExample {
+                desc: "check a file can be removed",
+                code: "rm --dry-run file.csv",
+            },
+        ],
+        ..HelpMessage::new("rm")
+    }
+}
+
+fn rm_intrinsic(blk: Block) -> Result<Step> {
+    path_intrinsic(Op::Rm, blk)
+}
+
+fn rm_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "remove each item listed in a column of the table
+the column defaults to `path`, errors are noted in the result column"
+            .into(),
+        params: vec![HelpParameter::Optional("col".into())],
+        flags: vec![DRY_RUN],
+        examples: vec![
+            HelpExample {
+                desc: "preview removing all temporary files",
+                code: "ls --glob '*.tmp' | rm --dry-run",
+            },
+            HelpExample {
+                desc: "remove files in the working directory listed by name",
+                code: "ls | filter ext --Str = tmp | rm name",
+            },
+        ],
+        ..HelpMessage::new("rm")
+    }
+}
+
+fn rm_table_intrinsic(blk: Block) -> Result<Step> {
+    table_intrinsic(Op::Rm, blk)
+}
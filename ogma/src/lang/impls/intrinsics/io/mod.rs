@@ -0,0 +1,1041 @@
+use super::*;
+use rt::fscache::{Stamp, FSCACHE};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+mod columnar;
+mod compress;
+mod exec;
+mod fsops;
+mod readers;
+mod sqlite;
+mod text;
+mod workbook;
+mod writers;
+
+pub fn add_intrinsics(impls: &mut Implementations) {
+    add! { impls,
+        ("ls", Table, ls_table, Io)
+        (ls, Io)
+
+        (open, Io)
+        (save, Io)
+    };
+
+    exec::add_intrinsics(impls);
+    fsops::add_intrinsics(impls);
+    sqlite::add_intrinsics(impls);
+    workbook::add_intrinsics(impls);
+}
+
+// ------ Public API -----------------------------------------------------------
+/// Parse `contents` as a table, using the format of the file extension `ext` (eg `csv`, `json`).
+pub fn parse_table(contents: &str, ext: &str) -> io::Result<Table> {
+    let path = Path::new("input").with_extension(ext);
+    readers::read_table(&path, contents, &Default::default())
+}
+
+/// Write `value` using the format of the file extension `ext` (eg `csv`, `json`).
+pub fn write_as(ext: &str, wtr: &mut dyn Write, value: Value) -> io::Result<()> {
+    let path = Path::new("output").with_extension(ext);
+    writers::write_value(&path, wtr, value, &Default::default())
+}
+
+// ------ Helpers --------------------------------------------------------------
+/// Resolves `path`, relative to the working directory, for reading.
+/// The path is checked against the [`rt::Sandbox`] policy, which by default disallows users to
+/// walk around outside the workspace folders.
+fn read_path(path: &str, cx: &Context) -> io::Result<PathBuf> {
+    cx.sandbox.read(cx.root, cx.wd, Path::new(path))
+}
+
+/// Resolves `path`, relative to the working directory, for writing.
+/// The path is checked against the [`rt::Sandbox`] policy.
+fn write_path(path: &str, cx: &Context) -> io::Result<PathBuf> {
+    cx.sandbox.write(cx.root, cx.wd, Path::new(path))
+}
+
+// ------ List -----------------------------------------------------------------
+fn ls_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "list out the headers of the table".into(),
+        examples: vec![HelpExample {
+            desc: "list headers in table",
+            code: "open table.csv | ls",
+        }],
+        ..HelpMessage::new("ls")
+    }
+}
+
+fn ls_table_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Type::Tab)?;
+    blk.assert_output(Type::Tab); // always outputs a table
+
+    let blk_tag = blk.blk_tag().clone();
+    blk.eval_o(move |input, cx| {
+        Table::try_from(input)
+            .and_then(|t| {
+                t.row(0)
+                    .ok_or_else(|| Error::empty_table("", &blk_tag))
+                    .map(|r| once(o("header")).chain(r.cloned()).map(|x| vec![x]))
+                    .map(|x| x.collect::<Vec<_>>())
+            })
+            .map(::table::Table::from)
+            .map(Table::from)
+            .and_then(|x| cx.done_o(x))
+    })
+}
+
+fn ls_help() -> HelpMessage {
+    HelpMessage {
+        desc: "list out the filesystem contents in the current working dir
+recursive listings add a path column, relative to the working dir
+symlinked directories are followed once, and never outside the root"
+            .into(),
+        params: vec![HelpParameter::Optional("path".into())],
+        flags: vec![
+            ("recurse", "list the contents of sub-directories"),
+            (
+                "depth <n>",
+                "list recursively to a depth of n directories, 1 is the directory itself",
+            ),
+            (
+                "glob <pattern>",
+                "list recursively, keeping items matching the pattern. patterns without a `/` match the item name",
+            ),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "list the current working directory items",
+                code: "ls",
+            },
+            HelpExample {
+                desc: "list directory items in `path`",
+                code: "ls path/to",
+            },
+            HelpExample {
+                desc: "list all items under `path`",
+                code: "ls --recurse path/to",
+            },
+            HelpExample {
+                desc: "list csv files at most two directories deep",
+                code: "ls --depth 2 --glob '*.csv'",
+            },
+        ],
+        ..HelpMessage::new("ls")
+    }
+}
+
+fn ls_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Type::Tab); // always outputs a table
+
+    let blk_tag = blk.blk_tag().clone();
+    let mut arg_flags = ["depth", "glob"]
+        .iter()
+        .filter_map(|&f| blk.get_flag(f))
+        .collect::<Vec<_>>();
+    let recurse = blk.get_flag("recurse").is_some();
+    let mut args = concrete_args(&mut blk, Some(Type::Nil))?;
+    arg_flags.sort_by_key(|f| std::cmp::Reverse(f.start));
+    let walk = walk_flags(&mut args, arg_flags, recurse)?;
+    let path = match args.len() {
+        0 => None,
+        _ => Some(file_arg(args, &blk_tag)?),
+    };
+
+    blk.eval_o(move |_, cx| {
+        let path: Option<Str> = path
+            .as_ref()
+            .map(|p| p.resolve(|| Value::Nil, &cx).and_then(Str::try_from))
+            .transpose()?;
+        let path = path.as_deref().unwrap_or("");
+        let dir = read_path(path, &cx).map_err(|e| Error::io(&blk_tag, e))?;
+        match &walk {
+            Some(walk) => {
+                walk_dir_table(&dir, Path::new(path), walk, &cx).map_err(|e| Error::io(&blk_tag, e))
+            }
+            None => make_dir_table(dir, &blk_tag),
+        }
+        .and_then(|x| cx.done_o(x))
+    })
+}
+
+/// The single file argument left once the flag values are taken, which must return a string.
+fn file_arg(mut args: Vec<eng::Argument>, blktag: &Tag) -> Result<eng::Argument> {
+    let arg = match args.len() {
+        0 => return Err(Error::insufficient_args(blktag, 0, None)),
+        1 => args.remove(0),
+        _ => return Err(Error::unused_args(args[1..].iter().map(|a| &a.tag))),
+    };
+    match arg.out_ty() {
+        Ty::Str => Ok(arg),
+        ty => Err(Error::unexp_arg_output_ty(&Ty::Str, ty, &arg.tag)),
+    }
+}
+
+/// Options for listing directories recursively.
+struct Walk {
+    /// The maximum depth to list, `1` lists the directory itself.
+    depth: Option<usize>,
+    glob: Option<::glob::Pattern>,
+}
+
+/// Take the values of the recursive listing flags (`--depth` and `--glob`) from `args`, returning
+/// `None` if the listing is not recursive.
+///
+/// The flags are expected in reverse order of position.
+fn walk_flags(
+    args: &mut Vec<eng::Argument>,
+    flags: Vec<Tag>,
+    recurse: bool,
+) -> Result<Option<Walk>> {
+    let (mut depth, mut glob) = (None, None);
+    for flag in flags {
+        if flag.str() == "depth" {
+            let arg = take_flag_args(args, &flag, Some(1), "depth 2")?.remove(0);
+            let d = arg.extract_literal::<Number>()?;
+            let x = d.as_f64();
+            let x = (x >= 1.0 && x.fract() == 0.0).then(|| x as usize);
+            depth = Some(x.ok_or_else(|| {
+                Error::invalid_flag_value(
+                    &arg.tag,
+                    format!("depth must be a positive integer, found `{}`", d),
+                    "use `--depth 1` to list only the directory".to_string(),
+                )
+            })?);
+        } else {
+            let arg = take_flag_args(args, &flag, Some(1), "glob '*.csv'")?.remove(0);
+            let g = arg.extract_literal::<Str>()?;
+            glob = Some(::glob::Pattern::new(g).map_err(|e| {
+                Error::invalid_flag_value(
+                    &arg.tag,
+                    format!("invalid glob pattern `{}`: {}", g, e),
+                    "patterns use `*`, `?`, and `[...]`, for example `*.csv`".to_string(),
+                )
+            })?);
+        }
+    }
+
+    Ok((recurse || depth.is_some() || glob.is_some()).then(|| Walk { depth, glob }))
+}
+
+fn make_dir_table<P: AsRef<std::path::Path>>(dir: P, blk_tag: &Tag) -> Result<Table> {
+    let mut table = InnerTable::from(vec![vec![
+        o("name"),
+        o("type"),
+        o("size"),
+        o("ext"),
+        o("modified"),
+    ]]);
+    table.header = true;
+
+    for entry in dir
+        .as_ref()
+        .read_dir()
+        .map_err(|e| Error::io(blk_tag, e))?
+        .filter_map(|x| x.ok())
+    {
+        table.add_row(dir_entry_row(&entry));
+    }
+
+    table.sort(0, cmp_names);
+
+    Ok(table.into())
+}
+
+/// Orders the name (or path) cells of a directory table.
+fn cmp_names(x: &Entry<Value>, y: &Entry<Value>) -> std::cmp::Ordering {
+    match (x, y) {
+        (Entry::Obj(Value::Str(x)), Entry::Obj(Value::Str(y))) => x.cmp(y),
+        _ => std::cmp::Ordering::Equal,
+    }
+}
+
+/// The name, type, size, ext, and modified cells of a directory entry.
+fn dir_entry_row(entry: &std::fs::DirEntry) -> Vec<Entry<Value>> {
+    use Entry::*;
+
+    let row = once(o(entry.file_name().to_str().unwrap_or("")));
+    if let Ok(meta) = entry.metadata() {
+        row.chain(once(if meta.is_dir() {
+            o("dir")
+        } else if meta.is_file() {
+            o("file")
+        } else {
+            Nil
+        }))
+        .chain(once(Num(meta.len().into())))
+        .chain(once(
+            entry
+                .path()
+                .extension()
+                .and_then(|x| x.to_str())
+                .map(o)
+                .unwrap_or(Nil),
+        ))
+        .chain(once(
+            meta.modified()
+                .map(|t| Obj(Value::Date(DateTime::from(t))))
+                .unwrap_or(Nil),
+        ))
+        .collect()
+    } else {
+        row.collect()
+    }
+}
+
+/// List the contents of `dir` recursively, with a leading path column of each item's path
+/// prefixed with `display`.
+///
+/// Directories (including symlinked ones) are only descended into if the [`rt::Sandbox`] allows
+/// it, and each directory is only listed once, which guards against symlink loops.
+/// Sub-directories which can not be read are skipped.
+fn walk_dir_table(dir: &Path, display: &Path, walk: &Walk, cx: &Context) -> io::Result<Table> {
+    let mut table = InnerTable::from(vec![vec![
+        o("path"),
+        o("name"),
+        o("type"),
+        o("size"),
+        o("ext"),
+        o("modified"),
+    ]]);
+    table.header = true;
+
+    let sandbox = cx.sandbox;
+    let root = cx.root.canonicalize()?;
+    let mut visited = HashSet::default();
+    visited.insert(dir.canonicalize()?);
+
+    // match patterns without a separator against the item name
+    let match_path = walk
+        .glob
+        .as_ref()
+        .map(|g| g.as_str().contains('/'))
+        .unwrap_or_default();
+
+    let mut dirs = vec![(dir.to_path_buf(), PathBuf::new(), 1)];
+    let mut first = true;
+    while let Some((dir, rel, depth)) = dirs.pop() {
+        let rd = match dir.read_dir() {
+            Ok(x) => x,
+            Err(e) if first => return Err(e),
+            Err(_) => continue,
+        };
+        first = false;
+
+        for entry in rd.filter_map(|x| x.ok()) {
+            let rel = rel.join(entry.file_name());
+            let path = entry.path();
+
+            let keep = walk
+                .glob
+                .as_ref()
+                .map(|g| {
+                    if match_path {
+                        let opts = ::glob::MatchOptions {
+                            require_literal_separator: true,
+                            ..::glob::MatchOptions::new()
+                        };
+                        g.matches_path_with(&rel, opts)
+                    } else {
+                        g.matches(&entry.file_name().to_string_lossy())
+                    }
+                })
+                .unwrap_or(true);
+            if keep {
+                let p = display.join(&rel);
+                table.add_row(once(o(p.to_string_lossy().as_ref())).chain(dir_entry_row(&entry)));
+            }
+
+            let is_link = entry
+                .file_type()
+                .map(|x| x.is_symlink())
+                .unwrap_or_default();
+            let descend = walk.depth.map(|d| depth < d).unwrap_or(true)
+                && (sandbox.follows_symlinks() || !is_link)
+                && path.is_dir(); // follows symlinks
+            if descend {
+                if let Ok(canon) = path.canonicalize() {
+                    if sandbox.allows(&root, &canon) && visited.insert(canon) {
+                        dirs.push((path, rel, depth + 1));
+                    }
+                }
+            }
+        }
+    }
+
+    table.sort(0, cmp_names);
+
+    Ok(table.into())
+}
+
+// ------ Open -----------------------------------------------------------------
+fn open_help() -> HelpMessage {
+    HelpMessage {
+        desc: "open something
+Table (default): parse file as a table
+String: reads file as string
+tables are parsed using the file extension: csv, tsv, psv, json, ndjson
+gzip (.gz) and zstd (.zst) compressed files are decompressed first
+a glob pattern opens each matching file, stacking the tables by aligning the headers
+json files are arrays of objects, the object keys become the table headers
+SQLite databases (.sqlite, .sqlite3, .db) read the only table, unless `--table` is specified
+workbooks (.xlsx, .xlsm, .xlsb, .xls, .ods) read the first sheet, unless `--sheet` is specified
+parquet (.parquet, .pq) and arrow ipc (.arrow, .ipc, .feather) files keep the column types
+fixed-width files are parsed with `--fixed`, using the first line as the header unless the columns are named
+unrecognised extensions are parsed as comma separated values"
+            .into(),
+        params: vec![HelpParameter::Required("file".into())],
+        flags: vec![
+            (
+                "<type>",
+                "open file as type. defaults to Table if not specified",
+            ),
+            (
+                "delim <char>",
+                "parse the table using the delimiter, use `\\t` for tabs",
+            ),
+            (
+                "encoding <name>",
+                "decode the file using the encoding, defaults to utf-8. utf-8 and utf-16 byte order marks are detected",
+            ),
+            (
+                "source-col <name>",
+                "add a column with the file each row was read from",
+            ),
+            (
+                "table <name>",
+                "read this table from a SQLite database, required if there is more than one table",
+            ),
+            (
+                "sheet <name|index>",
+                "read this sheet of a workbook, an integer is the zero-based sheet index if no sheet has that name",
+            ),
+            (
+                "range '<A1:F200>'",
+                "read only the cells in this range of a workbook sheet, quoted since it contains a `:`",
+            ),
+            (
+                "cols <col> ..",
+                "read only these columns of a parquet or arrow file, the file comes before the flag",
+            ),
+            (
+                "fixed '<[name:]start:end> ..'",
+                "parse fixed-width columns, character offsets are zero-based and the end is exclusive. the columns are separated by spaces",
+            ),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "open a csv as a table",
+                code: "open file.csv",
+            },
+            HelpExample {
+                desc: "open a file along a path",
+                code: "open 'path/to a/file.csv'",
+            },
+            HelpExample {
+                desc: "open a tab separated file as a table",
+                code: "open file.tsv",
+            },
+            HelpExample {
+                desc: "open a semi-colon separated file as a table",
+                code: "open --delim ';' file.txt",
+            },
+            HelpExample {
+                desc: "open newline delimited json as a table",
+                code: "open logs.ndjson",
+            },
+            HelpExample {
+                desc: "open a gzip compressed csv as a table",
+                code: "open file.csv.gz",
+            },
+            HelpExample {
+                desc: "open a windows-1252 encoded csv",
+                code: "open --encoding windows-1252 file.csv",
+            },
+            HelpExample {
+                desc: "open and stack all csv files in a directory, noting the source file",
+                code: "open --source-col file 'sales/*.csv'",
+            },
+            HelpExample {
+                desc: "open a table from a SQLite database",
+                code: "open --table sales db.sqlite",
+            },
+            HelpExample {
+                desc: "open a range of cells in a workbook sheet",
+                code: "open --sheet Q1 --range 'A3:F200' finance.xlsx",
+            },
+            HelpExample {
+                desc: "open two columns of a parquet file",
+                code: "open diamonds.parquet --cols price carat",
+            },
+            HelpExample {
+                desc: "open a fixed-width file, with the last column running to the end of the line",
+                code: "open --fixed '0:10 10:25 25:' statement.txt",
+            },
+            HelpExample {
+                desc: "open a fixed-width file without a header line",
+                code: "open --fixed 'date:0:10 desc:10:40 amount:40:' statement.txt",
+            },
+            HelpExample {
+                desc: "open a file as a string",
+                code: "open --Str foo.txt",
+            },
+        ],
+        ..HelpMessage::new("open")
+    }
+}
+
+fn open_intrinsic(mut blk: Block) -> Result<Step> {
+    let blktag = blk.blk_tag().clone();
+    // value flags need to be removed before fetching the type flag
+    let mut arg_flags = [
+        "delim",
+        "cols",
+        "encoding",
+        "source-col",
+        "table",
+        "sheet",
+        "range",
+        "fixed",
+    ]
+    .iter()
+    .filter_map(|&f| blk.get_flag(f))
+    .collect::<Vec<_>>();
+    let mut args = concrete_args(&mut blk, None)?;
+    // `--cols` takes every argument after it, so the last flag takes its arguments first
+    arg_flags.sort_by_key(|f| std::cmp::Reverse(f.start));
+    let (mut delim, mut cols, mut encoding) = (None, None, None);
+    let (mut source_col, mut db_table) = (None, None);
+    let (mut sheet, mut range, mut fixed) = (None, None, None);
+    for flag in arg_flags {
+        match flag.str() {
+            "delim" => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "delim ';'")?.remove(0);
+                let d = arg.extract_literal::<Str>()?;
+                let d = readers::parse_delim(d).ok_or_else(|| {
+                    Error::invalid_flag_value(
+                        &arg.tag,
+                        format!("delimiter must be a single character, found `{}`", d),
+                        "use `\\t` or `tab` for a tab character".to_string(),
+                    )
+                })?;
+                delim = Some((flag, d));
+            }
+            "cols" => {
+                let c = take_flag_args(&mut args, &flag, None, "cols price carat")?
+                    .iter()
+                    .map(|a| a.extract_literal::<Str>().map(Clone::clone))
+                    .collect::<Result<Vec<_>>>()?;
+                cols = Some((flag, c));
+            }
+            "encoding" => {
+                let arg =
+                    take_flag_args(&mut args, &flag, Some(1), "encoding windows-1252")?.remove(0);
+                encoding = Some(parse_encoding(&arg)?);
+            }
+            "source-col" => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "source-col file")?.remove(0);
+                let c = arg.extract_literal::<Str>()?.clone();
+                source_col = Some((flag, c));
+            }
+            "table" => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "table sales")?.remove(0);
+                let t = arg.extract_literal::<Str>()?.clone();
+                db_table = Some((flag, t));
+            }
+            "sheet" => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "sheet Q1")?.remove(0);
+                // a sheet index is a number, which is matched as written
+                let s = match arg.out_ty() {
+                    Ty::Num => arg.extract_literal::<Number>().map(|_| arg.tag.str())?,
+                    _ => arg.extract_literal::<Str>()?.as_str(),
+                };
+                sheet = Some((flag, workbook::Sheet::parse(s)));
+            }
+            "range" => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "range 'A1:F200'")?.remove(0);
+                let r = arg.extract_literal::<Str>()?;
+                let r = workbook::CellRange::parse(r).ok_or_else(|| {
+                    Error::invalid_flag_value(
+                        &arg.tag,
+                        format!("invalid cell range `{}`", r),
+                        "specify the range as the top-left and bottom-right cells, ie `--range 'A1:F200'`"
+                            .to_string(),
+                    )
+                })?;
+                range = Some((flag, r));
+            }
+            _ => {
+                let arg = take_flag_args(&mut args, &flag, Some(1), "fixed '0:10 10:'")?.remove(0);
+                let f = arg.extract_literal::<Str>()?;
+                let f = readers::FixedWidth::parse(f).map_err(|e| {
+                    Error::invalid_flag_value(
+                        &arg.tag,
+                        format!("invalid fixed-width columns `{}`: {}", f, e),
+                        "specify the columns as `[name:]start:end`, separated by spaces and quoted, ie `--fixed '0:10 10:'`"
+                            .to_string(),
+                    )
+                })?;
+                fixed = Some((flag, f));
+            }
+        }
+    }
+    let arg = file_arg(args, &blktag)?;
+    if let (Some(_), Some((flag, _))) = (&delim, &fixed) {
+        return Err(Error::invalid_flag_value(
+            flag,
+            "`--fixed` cannot be used with `--delim`".to_string(),
+            "fixed-width files are not delimited, remove one of the flags".to_string(),
+        ));
+    }
+    // TODO make this output inferred / default to Table?
+    let as_ty = type_flag(&mut blk)?.unwrap_or(Ty::Tab);
+
+    match as_ty {
+        Ty::Tab => {
+            let opts = readers::ReadOpts {
+                delim: delim.map(|(_, d)| d),
+                encoding,
+                sheet: sheet.map(|(_, s)| s),
+                range: range.map(|(_, r)| r),
+                cols: cols.map(|(_, c)| c),
+                fixed: fixed.map(|(_, f)| f),
+                table: db_table.map(|(_, t)| t),
+            };
+            let key = opts.cache_key();
+            let source_col = source_col.map(|(_, c)| c);
+
+            blk.eval_o(move |val, cx| {
+                let p: Str = arg.resolve(|| val, &cx)?.try_into()?;
+                let paths = expand_filepath(&p, &cx).map_err(|e| Error::io(&blktag, e))?;
+                let table = if paths.len() == 1 && source_col.is_none() {
+                    open_table(&paths[0], &opts, &key).map_err(|e| Error::io(&blktag, e))?
+                } else {
+                    let tables = paths
+                        .iter()
+                        .map(|path| open_table(path, &opts, &key).map(|t| (path, t)))
+                        .collect::<io::Result<Vec<_>>>()
+                        .map_err(|e| Error::io(&blktag, e))?;
+                    stack_tables(tables, source_col.as_deref())
+                };
+
+                cx.done_o(table)
+            })
+        }
+        Ty::Str
+            if delim.is_some()
+                || source_col.is_some()
+                || db_table.is_some()
+                || sheet.is_some()
+                || range.is_some()
+                || cols.is_some()
+                || fixed.is_some() =>
+        {
+            Err(Error::unused_flags(
+                delim
+                    .iter()
+                    .map(|(f, _)| f)
+                    .chain(source_col.iter().map(|(f, _)| f))
+                    .chain(db_table.iter().map(|(f, _)| f))
+                    .chain(sheet.iter().map(|(f, _)| f))
+                    .chain(range.iter().map(|(f, _)| f))
+                    .chain(cols.iter().map(|(f, _)| f))
+                    .chain(fixed.iter().map(|(f, _)| f)),
+            ))
+        }
+        Ty::Str => {
+            let opts = readers::ReadOpts {
+                encoding,
+                ..Default::default()
+            };
+            let key = opts.cache_key();
+
+            blk.eval_o(move |val, cx| {
+                let p: Str = arg.resolve(|| val, &cx)?.try_into()?;
+                let path = read_path(&p, &cx).map_err(|e| Error::io(&blktag, e))?;
+                let s = match FSCACHE.get::<Str>(&path, &key) {
+                    Some(s) => s,
+                    None => {
+                        let stamp = Stamp::of(&path).ok();
+                        let s: Str = read_file(&path, opts.encoding)
+                            .map_err(|e| Error::io(&blktag, e))?
+                            .into();
+
+                        FSCACHE.insert(&path, &key, stamp, s.clone());
+                        s
+                    }
+                };
+                cx.done_o(s)
+            })
+        }
+        x => Err(Error {
+            help_msg: None,
+            ..Error::wrong_op_input_type(&x, blk.op_tag())
+        }),
+    }
+}
+
+/// Expands `path` into the files to open.
+/// If `path` is not an existing file and contains glob characters, it is treated as a glob
+/// pattern, with each match checked against the sandbox in the same way as [`read_path`].
+fn expand_filepath(path: &str, cx: &Context) -> io::Result<Vec<std::path::PathBuf>> {
+    let wd = cx.root.join(cx.wd);
+    if !path.contains(&['*', '?', '['] as &[_]) || wd.join(path).exists() {
+        return read_path(path, cx).map(|x| vec![x]);
+    }
+
+    // the working directory is literal, only the supplied path is a pattern
+    let pattern = Path::new(&::glob::Pattern::escape(&wd.to_string_lossy())).join(path);
+    let sandbox = cx.sandbox;
+    let paths = ::glob::glob(&pattern.to_string_lossy())
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", e, path)))?
+        .filter_map(|x| x.ok())
+        .filter(|x| x.is_file())
+        .map(|x| {
+            // matches are prefixed with the root
+            let x = x.strip_prefix(cx.root).unwrap_or(&x);
+            sandbox.read(cx.root, Path::new(""), x)
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+
+    if paths.is_empty() {
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("no files match the pattern: {}", path),
+        ))
+    } else {
+        Ok(paths)
+    }
+}
+
+/// Open a file as a table, using the [`FSCACHE`] if available.
+fn open_table(path: &std::path::Path, opts: &readers::ReadOpts, key: &str) -> io::Result<Table> {
+    match FSCACHE.get::<Table>(path, key) {
+        Some(table) => Ok(table),
+        None => {
+            let stamp = Stamp::of(path).ok();
+            let table = if opts.table.is_some() || sqlite::is_db(path) {
+                sqlite::read_table(path, opts.table.as_deref())?
+            } else if columnar::is_columnar(path) {
+                columnar::read(path, opts.cols.as_deref())?
+            } else if opts.cols.is_some() {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "`--cols` is only supported for parquet and arrow files: {}",
+                        path.display()
+                    ),
+                ));
+            } else if workbook::is_workbook(path) {
+                workbook::read(path, opts.sheet.as_ref(), opts.range)?
+            } else {
+                let s = read_file(path, opts.encoding)?;
+                readers::read_table(path, &s, opts)?
+            };
+            FSCACHE.insert(path, key, stamp, table.clone());
+            Ok(table)
+        }
+    }
+}
+
+/// Stack the rows of tables, aligning the columns on the header names.
+/// Columns not in a table are left empty.
+/// If `source_col` is specified, a leading column is added with the path of the row's file.
+fn stack_tables<P: AsRef<std::path::Path>>(
+    tables: Vec<(P, Table)>,
+    source_col: Option<&str>,
+) -> Table {
+    let mut header: Vec<Entry<Value>> = source_col.map(o).into_iter().collect();
+    let mut rows = Vec::new();
+
+    for (path, table) in tables {
+        let mut trows = table.rows();
+        let hdrs = match trows.next() {
+            Some(x) => x,
+            None => continue,
+        };
+
+        // map each column of this table into a column of the stacked table
+        let mut idxs: Vec<usize> = Vec::with_capacity(table.cols_len());
+        for h in hdrs {
+            let i = header
+                .iter()
+                .enumerate()
+                .skip(source_col.is_some() as usize)
+                .position(|(i, x)| x == h && !idxs.contains(&i))
+                .map(|i| i + source_col.is_some() as usize);
+            idxs.push(i.unwrap_or_else(|| {
+                header.push(h.clone());
+                header.len() - 1
+            }));
+        }
+
+        let src = path.as_ref().display().to_string();
+        for row in trows {
+            let mut r = vec![Entry::Nil; header.len()];
+            if source_col.is_some() {
+                r[0] = o(src.as_str());
+            }
+            for (&i, e) in idxs.iter().zip(row) {
+                r[i] = e.clone();
+            }
+            rows.push(r);
+        }
+    }
+
+    let cols = header.len();
+    let rows = once(header).chain(rows.into_iter().map(|mut r| {
+        r.resize(cols, Entry::Nil);
+        r
+    }));
+
+    Table::from(::table::Table::from(rows.collect::<Vec<_>>()))
+}
+
+/// Read a file to a String, decoding using `encoding` (defaulting to UTF-8).
+/// Compressed files are decompressed first.
+fn read_file(path: impl AsRef<std::path::Path>, encoding: Option<&str>) -> io::Result<String> {
+    text::decode(&compress::read_bytes(path.as_ref())?, encoding)
+}
+
+/// Parses the value of the `--encoding <name>` flag.
+fn parse_encoding(arg: &eng::Argument) -> Result<&'static str> {
+    let e = arg.extract_literal::<Str>()?;
+    text::lookup(e).ok_or_else(|| {
+        Error::invalid_flag_value(
+            &arg.tag,
+            format!("unknown encoding `{}`", e),
+            "try `utf-8`, `utf-16le`, `utf-16be`, or `windows-1252`".to_string(),
+        )
+    })
+}
+
+// ------ Save -----------------------------------------------------------------
+fn save_help() -> HelpMessage {
+    HelpMessage {
+        desc: "save the input to a file
+table input is saved as RFC 4180 comma separated values
+files with a json or ndjson extension are saved as json, tables become objects keyed by header
+tables can be saved as parquet (.parquet, .pq) or arrow ipc (.arrow, .ipc, .feather) files
+files with a .gz or .zst extension are compressed
+use `--table` to save a table into a SQLite database, replacing any existing table
+the database columns are typed using the table's values"
+            .into(),
+        params: vec![HelpParameter::Required("file".into())],
+        flags: vec![
+            ("crlf", "use CRLF (\\r\\n) line endings"),
+            (
+                "encoding <name>",
+                "encode the file using the encoding, defaults to utf-8",
+            ),
+            ("table <name>", "save the table into a SQLite database"),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "save table as a csv",
+                code: "open file1.csv | save file2.csv",
+            },
+            HelpExample {
+                desc: "save table as newline delimited json",
+                code: "open file1.csv | save file2.ndjson",
+            },
+            HelpExample {
+                desc: "save table as a csv with CRLF line endings",
+                code: "open file1.csv | save --crlf file2.csv",
+            },
+            HelpExample {
+                desc: "save table as a zstd compressed csv",
+                code: "open file1.csv | save file2.csv.zst",
+            },
+            HelpExample {
+                desc: "save table as a utf-16 encoded csv",
+                code: "open file1.csv | save --encoding utf-16le file2.csv",
+            },
+            HelpExample {
+                desc: "save table into a SQLite database",
+                code: "open file1.csv | save --table file1 db.sqlite",
+            },
+            HelpExample {
+                desc: "save text as a string",
+                code: "\\ 'Hello, world!' | save hello-world.txt",
+            },
+        ],
+        ..HelpMessage::new("save")
+    }
+}
+
+fn save_intrinsic(mut blk: Block) -> Result<Step> {
+    let ty = blk.in_ty().clone();
+    blk.assert_output(ty.clone()); // output is the same as input
+    if ty == Ty::TabRow {
+        return Err(Error::wrong_op_input_type(&ty, blk.op_tag()));
+    }
+
+    let crlf = blk.get_flag("crlf").is_some();
+    let mut arg_flags = ["encoding", "table"]
+        .iter()
+        .filter_map(|&f| blk.get_flag(f))
+        .collect::<Vec<_>>();
+    if ty != Ty::Tab && arg_flags.iter().any(|f| f.str() == "table") {
+        return Err(Error::wrong_op_input_type(&ty, blk.op_tag()));
+    }
+    let blktag = blk.blk_tag().clone();
+    let mut args = concrete_args(&mut blk, None)?;
+    arg_flags.sort_by_key(|f| std::cmp::Reverse(f.start));
+    let (mut encoding, mut db_table) = (None, None);
+    for flag in arg_flags {
+        if flag.str() == "encoding" {
+            let arg = take_flag_args(&mut args, &flag, Some(1), "encoding utf-16le")?.remove(0);
+            encoding = Some(parse_encoding(&arg)?);
+        } else {
+            let arg = take_flag_args(&mut args, &flag, Some(1), "table sales")?.remove(0);
+            db_table = Some(arg.extract_literal::<Str>()?.clone());
+        }
+    }
+    let opts = writers::WriteOpts { crlf, encoding };
+    let filepath = file_arg(args, &blktag)?;
+    blk.eval(ty, move |val, cx| {
+        let p: Str = filepath.resolve(|| val.clone(), &cx)?.try_into()?;
+        let p = write_path(&p, &cx).map_err(|e| Error::io(&blktag, e))?;
+        if let Some(name) = &db_table {
+            let table = Table::try_from(val.clone())?;
+            mkdirs(&p)
+                .and_then(|_| sqlite::write_table(&p, name, &table))
+                .map_err(|e| Error::io(&blktag, e))?;
+            return cx.done(val);
+        }
+        mkdirs(&p)
+            .and_then(|_| std::fs::File::create(&p))
+            .map(std::io::BufWriter::new)
+            .and_then(|mut file| match compress::Compression::from_path(&p) {
+                Some(c) => c.write(file, |w| writers::write_value(&p, w, val.clone(), &opts)),
+                None => writers::write_value(&p, &mut file, val.clone(), &opts),
+            })
+            .map_err(|e| Error::io(&blktag, e))?;
+        cx.done(val)
+    })
+}
+
+fn mkdirs<P: AsRef<std::path::Path>>(p: P) -> io::Result<()> {
+    let p = p.as_ref();
+    if let Some(p) = p.parent() {
+        if !p.exists() {
+            std::fs::create_dir_all(p)?;
+        }
+    }
+    Ok(())
+}
+
+/// Write the value, tables are written as [RFC 4180](https://www.rfc-editor.org/rfc/rfc4180)
+/// comma separated values.
+fn write_file<W: Write>(file: &mut W, value: Value, opts: &writers::WriteOpts) -> io::Result<()> {
+    use ::numfmt::*;
+    fn fmt_cell<WW: Write>(
+        wtr: &mut WW,
+        entry: &Entry<Value>,
+        fmtr: &mut Formatter,
+    ) -> io::Result<()> {
+        match entry {
+            Entry::Obj(Value::Nil) | Entry::Nil => Ok(()), // don't write anything
+            e => {
+                let s = print::fmt_cell(e, fmtr);
+                // fields containing commas, quotes, or line breaks are quoted, and any quotes
+                // are doubled.
+                if s.contains(&[',', '"', '\r', '\n'] as &[_]) {
+                    write!(wtr, "\"{}\"", s.replace('"', "\"\""))
+                } else {
+                    write!(wtr, "{}", s)
+                }
+            }
+        }
+    }
+
+    let fmtr = &mut Formatter::new();
+    match value {
+        Value::Tab(table) => {
+            let mut add_newline = false;
+            for row in table.rows() {
+                if add_newline {
+                    write!(file, "{}", opts.newline())?;
+                }
+                add_newline |= true;
+                let mut add_comma = false;
+                for entry in row {
+                    if add_comma {
+                        write!(file, ",")?;
+                    }
+                    add_comma |= true;
+                    fmt_cell(file, entry, fmtr)?;
+                }
+            }
+        }
+        x => {
+            let x = print::fmt_cell(&Entry::from(x), fmtr);
+            write!(file, "{}", x)?;
+        }
+    }
+
+    file.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_file_testing() {
+        let f = |v| {
+            let mut wtr = Vec::new();
+            write_file(&mut wtr, v, &Default::default()).unwrap();
+            String::from_utf8(wtr).unwrap()
+        };
+        let s = |s| Entry::Obj(Value::Str(Str::new(s)));
+        let n = |n: f64| Entry::Num(n.into());
+
+        let t = |v| Value::Tab(Table::from(::table::Table::from(v)));
+
+        assert_eq!(&f(Value::Bool(true)), "true");
+        assert_eq!(&f(Value::Num((3.14).into())), "3.14");
+        assert_eq!(&f(Value::Str("Hello, world!".into())), "Hello, world!");
+        assert_eq!(
+            &f(t(vec![
+                vec![n(1.5e6), n(2.5)],
+                vec![s("foo"), Entry::Nil, s("foo\nbar")],
+                vec![n(3.6e9), s("foo,bar"), s("foo \"zog\" bar")]
+            ])),
+            r#"1500000.0,2.5,
+foo,,"foo
+bar"
+3600000000.0,"foo,bar","foo ""zog"" bar""#
+        );
+    }
+
+    #[test]
+    fn write_file_crlf() {
+        let f = |v| {
+            let mut wtr = Vec::new();
+            let opts = writers::WriteOpts {
+                crlf: true,
+                ..Default::default()
+            };
+            write_file(&mut wtr, v, &opts).unwrap();
+            String::from_utf8(wtr).unwrap()
+        };
+        let s = |s| Entry::Obj(Value::Str(Str::new(s)));
+
+        let t = |v| Value::Tab(Table::from(::table::Table::from(v)));
+
+        assert_eq!(&f(Value::Str("foo\nbar".into())), "foo\nbar");
+        assert_eq!(
+            &f(t(vec![
+                vec![s("a"), s("b")],
+                vec![s("foo\r\nbar"), s("\"")],
+                vec![s(""), s("zog")],
+            ])),
+            "a,b\r\n\"foo\r\nbar\",\"\"\"\"\r\n,zog"
+        );
+    }
+}
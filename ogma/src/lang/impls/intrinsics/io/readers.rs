@@ -0,0 +1,484 @@
+//! Readers which parse file contents into a [`Table`].
+//!
+//! Readers are keyed on the (lowercase) file extension, if no reader matches the extension then
+//! the contents are parsed as comma separated values.
+//! Compression extensions are skipped, so `foo.tsv.gz` uses the `tsv` reader.
+use super::*;
+use ::libs::serde_json;
+use std::{fmt, path::Path};
+
+/// Options which alter how a file is read into a [`Table`].
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ReadOpts {
+    /// Parse as delimiter separated values using this delimiter, regardless of extension.
+    pub delim: Option<char>,
+    /// Decode the file using this encoding (see [`text::lookup`]), defaults to UTF-8.
+    pub encoding: Option<&'static str>,
+    /// The sheet to read from a workbook, defaults to the first sheet.
+    pub sheet: Option<workbook::Sheet>,
+    /// The cells to read from a workbook sheet, defaults to the used cells.
+    pub range: Option<workbook::CellRange>,
+    /// Only read these columns (in this order) from a columnar file.
+    pub cols: Option<Vec<Str>>,
+    /// Parse as fixed-width columns, regardless of extension.
+    pub fixed: Option<FixedWidth>,
+    /// The table to read from a SQLite database, defaults to the only table.
+    pub table: Option<Str>,
+}
+
+/// Parses the contents of a file into a [`Table`].
+pub type ReadFn = fn(&str, &ReadOpts) -> io::Result<Table>;
+
+::lazy_static::lazy_static! {
+    /// The table readers, keyed on file extension.
+    static ref READERS: HashMap<&'static str, ReadFn> = {
+        let mut map: HashMap<_, ReadFn> = HashMap::default();
+        map.insert("csv", dsv::<','>);
+        map.insert("tsv", dsv::<'\t'>);
+        map.insert("tab", dsv::<'\t'>);
+        map.insert("psv", dsv::<'|'>);
+        map.insert("json", json);
+        map.insert("ndjson", ndjson);
+        map.insert("jsonl", ndjson);
+        map
+    };
+}
+
+impl ReadOpts {
+    /// A key which describes these options, used to discriminate entries in the [`FSCACHE`].
+    pub fn cache_key(&self) -> String {
+        let delim = self.delim.map(|d| format!("delim={}", d));
+        let enc = self.encoding.map(|e| format!("encoding={}", e));
+        let sheet = self.sheet.as_ref().map(|s| format!("sheet={}", s));
+        let range = self.range.map(|r| format!("range={}", r));
+        let cols = self.cols.as_ref().map(|c| format!("cols={}", c.join(",")));
+        let fixed = self.fixed.as_ref().map(|f| format!("fixed={}", f));
+        let table = self.table.as_ref().map(|t| format!("table={}", t));
+        delim
+            .into_iter()
+            .chain(fixed)
+            .chain(enc)
+            .chain(sheet)
+            .chain(range)
+            .chain(cols)
+            .chain(table)
+            .collect::<Vec<_>>()
+            .join(";")
+    }
+}
+
+/// Read the `contents` into a table, choosing the reader using the extension of `path`.
+pub fn read_table(path: &Path, contents: &str, opts: &ReadOpts) -> io::Result<Table> {
+    match (&opts.fixed, opts.delim) {
+        (Some(fixed), _) => Ok(parse_fixed(fixed, contents)),
+        (None, Some(delim)) => Ok(parse_dsv(delim, contents)),
+        (None, None) => reader(path)(contents, opts),
+    }
+}
+
+/// Get the reader registered against the `path`'s extension.
+/// Defaults to comma separated values.
+fn reader(path: &Path) -> ReadFn {
+    compress::strip_ext(path)
+        .extension()
+        .and_then(|x| x.to_str())
+        .and_then(|x| READERS.get(x.to_lowercase().as_str()))
+        .copied()
+        .unwrap_or(dsv::<','> as ReadFn)
+}
+
+fn dsv<const D: char>(contents: &str, _: &ReadOpts) -> io::Result<Table> {
+    Ok(parse_dsv(D, contents))
+}
+
+fn parse_dsv(delim: char, contents: &str) -> Table {
+    Table::from(::table::parse_dsv(delim, contents).map_obj(|s| Value::Str(Str::new(s))))
+}
+
+/// A fixed-width column: an optional name, and a character range, which is open ended if there
+/// is no `end`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedCol {
+    pub name: Option<Str>,
+    pub start: usize,
+    pub end: Option<usize>,
+}
+
+/// The columns of a fixed-width file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedWidth(pub Vec<FixedCol>);
+
+impl FixedWidth {
+    /// Parse a whitespace separated list of columns, each of the form `[name:]start:[end]`.
+    /// Either every column is named, or none are (the first line is then the header).
+    pub fn parse(spec: &str) -> std::result::Result<Self, String> {
+        let cols = spec
+            .split_whitespace()
+            .map(|col| {
+                let mut parts = col.rsplitn(3, ':');
+                let (end, start, name) = (parts.next(), parts.next(), parts.next());
+                let start = start
+                    .and_then(|s| s.parse::<usize>().ok())
+                    .ok_or_else(|| format!("invalid column `{}`", col))?;
+                let end = match end {
+                    Some("") => None,
+                    Some(e) => Some(
+                        e.parse::<usize>()
+                            .ok()
+                            .filter(|&e| e > start)
+                            .ok_or_else(|| format!("invalid column `{}`", col))?,
+                    ),
+                    None => None,
+                };
+                let name = name.map(Str::new);
+                Ok(FixedCol { name, start, end })
+            })
+            .collect::<std::result::Result<Vec<_>, String>>()?;
+
+        if cols.is_empty() {
+            return Err("no columns specified".to_string());
+        }
+        let named = cols.iter().filter(|c| c.name.is_some()).count();
+        if named != 0 && named != cols.len() {
+            return Err("either name every column, or none of them".to_string());
+        }
+
+        Ok(FixedWidth(cols))
+    }
+
+    fn named(&self) -> bool {
+        self.0.iter().any(|c| c.name.is_some())
+    }
+}
+
+impl fmt::Display for FixedWidth {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cols = self
+            .0
+            .iter()
+            .map(|c| {
+                let name = c
+                    .name
+                    .as_ref()
+                    .map(|n| format!("{}:", n))
+                    .unwrap_or_default();
+                let end = c.end.map(|e| e.to_string()).unwrap_or_default();
+                format!("{}{}:{}", name, c.start, end)
+            })
+            .collect::<Vec<_>>();
+        write!(f, "{}", cols.join(" "))
+    }
+}
+
+/// Parse fixed-width columns, cells are trimmed.
+/// The cells are re-delimited and parsed with [`parse_dsv`] so values are detected the same way
+/// as delimited files.
+fn parse_fixed(fixed: &FixedWidth, contents: &str) -> Table {
+    const DELIM: char = '\u{1f}'; // ascii unit separator
+
+    // cells with quotes are quoted
+    let quote = |s: &str| {
+        if s.contains(&['"', DELIM] as &[_]) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    };
+    let cell = |chars: &[char], col: &FixedCol| {
+        let end = col.end.unwrap_or(chars.len()).min(chars.len());
+        let s = chars
+            .get(col.start..end)
+            .map(|x| x.iter().collect::<String>())
+            .unwrap_or_default();
+        quote(s.trim())
+    };
+
+    let mut dsv = String::with_capacity(contents.len());
+    if fixed.named() {
+        let names = fixed
+            .0
+            .iter()
+            .map(|c| quote(c.name.as_deref().unwrap_or_default()));
+        dsv.push_str(&names.collect::<Vec<_>>().join(&DELIM.to_string()));
+        dsv.push('\n');
+    }
+
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        let chars = line.chars().collect::<Vec<_>>();
+        let cells = fixed.0.iter().map(|c| cell(&chars, c));
+        dsv.push_str(&cells.collect::<Vec<_>>().join(&DELIM.to_string()));
+        dsv.push('\n');
+    }
+
+    parse_dsv(DELIM, &dsv)
+}
+
+/// Parse a JSON array of objects (or a single object) into a table.
+fn json(contents: &str, _: &ReadOpts) -> io::Result<Table> {
+    use serde_json::Value as J;
+
+    match serde_json::from_str(contents).map_err(invalid_data)? {
+        J::Array(xs) => json_rows(xs),
+        x @ J::Object(_) => json_rows(vec![x]),
+        _ => Err(invalid_data("expecting a JSON array of objects")),
+    }
+}
+
+/// Parse newline delimited JSON, where each non-empty line is an object.
+fn ndjson(contents: &str, _: &ReadOpts) -> io::Result<Table> {
+    contents
+        .lines()
+        .filter(|x| !x.trim().is_empty())
+        .map(|x| serde_json::from_str(x).map_err(invalid_data))
+        .collect::<io::Result<Vec<_>>>()
+        .and_then(json_rows)
+}
+
+/// Flatten JSON objects into table rows.
+/// The header is the union of the object keys, in the order they are first encountered.
+fn json_rows(objs: Vec<serde_json::Value>) -> io::Result<Table> {
+    use serde_json::Value as J;
+
+    let mut header: Vec<String> = Vec::new();
+    let mut rows = Vec::with_capacity(objs.len());
+
+    for obj in objs {
+        let obj = match obj {
+            J::Object(x) => x,
+            _ => return Err(invalid_data("expecting each JSON value to be an object")),
+        };
+
+        let mut row = vec![Entry::Nil; header.len()];
+        for (k, v) in obj {
+            let i = match header.iter().position(|x| x == &k) {
+                Some(i) => i,
+                None => {
+                    header.push(k);
+                    row.push(Entry::Nil);
+                    header.len() - 1
+                }
+            };
+            row[i] = json_entry(v)?;
+        }
+        rows.push(row);
+    }
+
+    let cols = header.len();
+    let header = header.into_iter().map(o).collect();
+    let rows = once(header).chain(rows.into_iter().map(|mut row| {
+        row.resize(cols, Entry::Nil);
+        row
+    }));
+
+    Ok(Table::from(::table::Table::from(rows.collect::<Vec<_>>())))
+}
+
+/// Convert a JSON value into a table entry.
+/// Objects and arrays of objects become nested tables, other arrays are kept as JSON strings.
+fn json_entry(v: serde_json::Value) -> io::Result<Entry<Value>> {
+    use serde_json::Value as J;
+
+    Ok(match v {
+        J::Null => Entry::Nil,
+        J::Bool(x) => Entry::Obj(Value::Bool(x)),
+        J::Number(x) => Entry::Num(json_num(&x)),
+        J::String(x) => o(x),
+        x @ J::Object(_) => Entry::Obj(Value::Tab(json_rows(vec![x])?)),
+        J::Array(xs) if !xs.is_empty() && xs.iter().all(J::is_object) => {
+            Entry::Obj(Value::Tab(json_rows(xs)?))
+        }
+        x @ J::Array(_) => o(x.to_string()),
+    })
+}
+
+fn json_num(n: &serde_json::Number) -> Number {
+    n.as_i64()
+        .map(Number::from)
+        .or_else(|| n.as_u64().map(Number::from))
+        .unwrap_or_else(|| Number::from(n.as_f64().unwrap_or(std::f64::NAN)))
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Parse a delimiter flag value.
+/// The value can be a single character, or `\t`/`tab` for a tab character.
+pub fn parse_delim(s: &str) -> Option<char> {
+    match s {
+        "\\t" | "tab" => Some('\t'),
+        s => {
+            let mut chars = s.chars();
+            chars.next().filter(|_| chars.next().is_none())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reader_by_extension() {
+        let opts = &ReadOpts::default();
+        let f = |p: &str, s: &str| read_table(Path::new(p), s, opts).unwrap().rows_len();
+
+        assert_eq!(f("foo.csv", "a,b\n1,2"), 2);
+        assert_eq!(f("foo.tsv", "a\tb\n1\t2"), 2);
+        assert_eq!(f("foo.TSV", "a\tb\n1\t2"), 2);
+        assert_eq!(f("foo.psv", "a|b\n1|2"), 2);
+        assert_eq!(f("foo", "a,b\n1,2"), 2);
+
+        let t = read_table(Path::new("foo.tsv"), "a\tb\n1\t2", opts).unwrap();
+        assert_eq!(t.cols_len(), 2);
+        let t = read_table(Path::new("foo.csv"), "a\tb\n1\t2", opts).unwrap();
+        assert_eq!(t.cols_len(), 1);
+
+        // compression extensions are ignored
+        let t = read_table(Path::new("foo.tsv.gz"), "a\tb\n1\t2", opts).unwrap();
+        assert_eq!(t.cols_len(), 2);
+    }
+
+    #[test]
+    fn delim_overrides_extension() {
+        let opts = &ReadOpts {
+            delim: Some(';'),
+            ..Default::default()
+        };
+        let t = read_table(Path::new("foo.tsv"), "a;b\n1;2", opts).unwrap();
+        assert_eq!(t.cols_len(), 2);
+    }
+
+    #[test]
+    fn cache_keys() {
+        let f = |delim, encoding| {
+            ReadOpts {
+                delim,
+                encoding,
+                ..Default::default()
+            }
+            .cache_key()
+        };
+        assert_eq!(&f(None, None), "");
+        assert_eq!(&f(Some(';'), None), "delim=;");
+        assert_eq!(&f(None, Some("utf-16le")), "encoding=utf-16le");
+        assert_eq!(&f(Some(';'), Some("utf-16le")), "delim=;;encoding=utf-16le");
+
+        let opts = ReadOpts {
+            sheet: Some(workbook::Sheet::parse("Q1")),
+            range: workbook::CellRange::parse("A1:F200"),
+            ..Default::default()
+        };
+        assert_eq!(&opts.cache_key(), "sheet=Q1;range=A1:F200");
+    }
+
+    #[test]
+    fn json_reader() {
+        let opts = &ReadOpts::default();
+        let t = read_table(
+            Path::new("foo.json"),
+            r#"[{"a": 1, "b": "x"}, {"b": null, "c": true, "a": 2.5}]"#,
+            opts,
+        )
+        .unwrap();
+        assert_eq!(t.rows_len(), 3);
+        assert_eq!(t.cols_len(), 3);
+        let row = |i| t.row(i).unwrap().cloned().collect::<Vec<_>>();
+        assert_eq!(row(0), vec![o("a"), o("b"), o("c")]);
+        assert_eq!(row(1), vec![n(1), o("x"), Entry::Nil]);
+        assert_eq!(
+            row(2),
+            vec![n(2.5), Entry::Nil, Entry::Obj(Value::Bool(true))]
+        );
+
+        // single object is a single row
+        let t = read_table(Path::new("foo.json"), r#"{"a": 1, "b": 2}"#, opts).unwrap();
+        assert_eq!(t.rows_len(), 2);
+
+        // nested values
+        let t = read_table(
+            Path::new("foo.json"),
+            r#"[{"a": [1, 2], "b": {"c": 3}, "d": [{"e": 1}, {"e": 2}]}]"#,
+            opts,
+        )
+        .unwrap();
+        let row = t.row(1).unwrap().cloned().collect::<Vec<_>>();
+        assert_eq!(row[0], o("[1,2]"));
+        assert!(matches!(&row[1], Entry::Obj(Value::Tab(t)) if t.rows_len() == 2));
+        assert!(matches!(&row[2], Entry::Obj(Value::Tab(t)) if t.rows_len() == 3));
+
+        assert!(read_table(Path::new("foo.json"), "[1, 2]", opts).is_err());
+        assert!(read_table(Path::new("foo.json"), "{", opts).is_err());
+    }
+
+    #[test]
+    fn ndjson_reader() {
+        let opts = &ReadOpts::default();
+        let f = |p: &str, s: &str| read_table(Path::new(p), s, opts);
+
+        let t = f("foo.ndjson", "{\"a\": 1}\n\n{\"a\": 2, \"b\": \"x\"}\n").unwrap();
+        assert_eq!(t.rows_len(), 3);
+        assert_eq!(t.cols_len(), 2);
+        let t = f("foo.jsonl", "{\"a\": 1}\n{\"a\": 2}").unwrap();
+        assert_eq!(t.rows_len(), 3);
+
+        assert!(f("foo.ndjson", "{\"a\": 1}\n[1]").is_err());
+    }
+
+    #[test]
+    fn fixed_width() {
+        let spec = |s| FixedWidth::parse(s);
+        assert_eq!(spec("0:10 10:").unwrap().to_string(), "0:10 10:");
+        assert_eq!(
+            spec(" id:0:4   name:4: ").unwrap().to_string(),
+            "id:0:4 name:4:"
+        );
+        assert!(spec("0:10 x").is_err());
+        assert!(spec("0:10,10:").is_err());
+        assert!(spec("5:2").is_err());
+        assert!(spec("id:0:4 4:").is_err());
+        assert!(spec("  ").is_err());
+
+        let contents = "id  name      amount
+1   alice       10.5
+2   bob, jr    -3
+
+";
+        let opts = &ReadOpts {
+            fixed: Some(spec("0:4 4:14 14:").unwrap()),
+            ..Default::default()
+        };
+        let t = read_table(Path::new("foo.csv"), contents, opts).unwrap();
+        let exp = ::table::Table::from(vec![
+            vec![o("id"), o("name"), o("amount")],
+            vec![n(1), o("alice"), n(10.5)],
+            vec![n(2), o("bob, jr"), n(-3)],
+        ]);
+        assert_eq!(t, Table::from(exp));
+
+        // named columns use every line as data
+        let opts = &ReadOpts {
+            fixed: Some(spec("a:0:4 b:4:14").unwrap()),
+            ..Default::default()
+        };
+        let t = read_table(Path::new("foo.txt"), contents, opts).unwrap();
+        assert_eq!(t.rows_len(), 4);
+        assert_eq!(
+            t.row(0).unwrap().cloned().collect::<Vec<_>>(),
+            vec![o("a"), o("b")]
+        );
+    }
+
+    #[test]
+    fn parse_delim_test() {
+        assert_eq!(parse_delim(";"), Some(';'));
+        assert_eq!(parse_delim("|"), Some('|'));
+        assert_eq!(parse_delim("\\t"), Some('\t'));
+        assert_eq!(parse_delim("tab"), Some('\t'));
+        assert_eq!(parse_delim(""), None);
+        assert_eq!(parse_delim(";;"), None);
+    }
+}
@@ -0,0 +1,303 @@
+//! SQLite database reading and writing.
+//!
+//! Databases are opened with `open [--table <name>]` or queried with `sql-query`, and tables are
+//! written with `save --table <name>`. Paths are resolved through the [`rt::Sandbox`].
+//!
+//! Requires the `sqlite` feature, without it reading or writing a database is an error.
+use super::*;
+#[cfg(feature = "sqlite")]
+use ::rusqlite::{
+    limits::Limit,
+    params_from_iter,
+    types::{Value as Sql, ValueRef},
+    Connection, OpenFlags,
+};
+
+pub fn add_intrinsics(_impls: &mut Implementations) {
+    #[cfg(feature = "sqlite")]
+    add! { _impls,
+        ("sql-query", sql_query, Io)
+    };
+}
+
+/// Returns if the path has a SQLite database extension.
+pub fn is_db(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| matches!(x.to_lowercase().as_str(), "sqlite" | "sqlite3" | "db"))
+        .unwrap_or_default()
+}
+
+#[cfg(not(feature = "sqlite"))]
+fn disabled(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "SQLite databases are not supported, enable the `sqlite` feature: {}",
+            path.display()
+        ),
+    )
+}
+
+/// Read a whole table out of the database.
+#[cfg(not(feature = "sqlite"))]
+pub fn read_table(path: &Path, _table: Option<&str>) -> io::Result<Table> {
+    Err(disabled(path))
+}
+
+/// Write the table into the database.
+#[cfg(not(feature = "sqlite"))]
+pub fn write_table(path: &Path, _name: &str, _table: &Table) -> io::Result<()> {
+    Err(disabled(path))
+}
+
+#[cfg(feature = "sqlite")]
+fn sql_err(e: ::rusqlite::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, e)
+}
+
+/// Quote an identifier, doubling any quotes.
+#[cfg(feature = "sqlite")]
+fn ident(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Open the database read-only.
+///
+/// Attaching other databases is disabled (which also disables `VACUUM INTO`), so a query can not
+/// reach files outside the sandbox. URI filenames are not enabled.
+#[cfg(feature = "sqlite")]
+fn open_read_only(path: &Path) -> io::Result<Connection> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    let conn = Connection::open_with_flags(path, flags).map_err(sql_err)?;
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    Ok(conn)
+}
+
+/// Read a whole table out of the database.
+/// If `table` is not specified, the database must contain a single table.
+#[cfg(feature = "sqlite")]
+pub fn read_table(path: &Path, table: Option<&str>) -> io::Result<Table> {
+    let conn = open_read_only(path)?;
+    let table = match table {
+        Some(t) => t.to_string(),
+        None => {
+            let mut names = table_names(&conn)?;
+            match names.len() {
+                1 => names.remove(0),
+                0 => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("the database has no tables: {}", path.display()),
+                    ))
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!(
+                            "specify the database table with `--table <name>`, one of: {}",
+                            names.join(", ")
+                        ),
+                    ))
+                }
+            }
+        }
+    };
+    query_conn(&conn, &format!("SELECT * FROM {}", ident(&table)))
+}
+
+/// The names of the (non-internal) tables in the database, sorted.
+#[cfg(feature = "sqlite")]
+fn table_names(conn: &Connection) -> io::Result<Vec<String>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )
+        .map_err(sql_err)?;
+    let names = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .and_then(|rows| rows.collect())
+        .map_err(sql_err)?;
+    Ok(names)
+}
+
+/// Run a query against the database, the column names become the table headers.
+/// The database is opened read-only.
+#[cfg(feature = "sqlite")]
+pub fn query(path: &Path, sql: &str) -> io::Result<Table> {
+    open_read_only(path).and_then(|conn| query_conn(&conn, sql))
+}
+
+#[cfg(feature = "sqlite")]
+fn query_conn(conn: &Connection, sql: &str) -> io::Result<Table> {
+    let mut stmt = conn.prepare(sql).map_err(sql_err)?;
+    let cols = stmt.column_count();
+
+    let mut table = vec![stmt.column_names().into_iter().map(o).collect::<Vec<_>>()];
+
+    let mut rows = stmt.query([]).map_err(sql_err)?;
+    while let Some(row) = rows.next().map_err(sql_err)? {
+        let row = (0..cols)
+            .map(|i| row.get_ref(i).map(entry))
+            .collect::<::rusqlite::Result<Vec<_>>>()
+            .map_err(sql_err)?;
+        table.push(row);
+    }
+
+    Ok(Table::from(::table::Table::from(table)))
+}
+
+#[cfg(feature = "sqlite")]
+fn entry(v: ValueRef) -> Entry<Value> {
+    match v {
+        ValueRef::Null => Entry::Nil,
+        ValueRef::Integer(i) => n(i),
+        ValueRef::Real(f) => n(f),
+        ValueRef::Text(s) => o(String::from_utf8_lossy(s).as_ref()),
+        ValueRef::Blob(b) => o(format!("<blob {} bytes>", b.len())),
+    }
+}
+
+/// The SQL column type, inferred from the column's values.
+#[cfg(feature = "sqlite")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColTy {
+    Integer,
+    Real,
+    Text,
+}
+
+#[cfg(feature = "sqlite")]
+impl ColTy {
+    fn infer<'a, I: Iterator<Item = &'a Entry<Value>>>(entries: I) -> Self {
+        let mut ty = None;
+        for e in entries {
+            let x = match Value::from(e) {
+                Value::Nil => continue,
+                Value::Bool(_) => ColTy::Integer,
+                Value::Num(n) if n.as_f64().fract() == 0.0 => ColTy::Integer,
+                Value::Num(_) => ColTy::Real,
+                _ => ColTy::Text,
+            };
+            ty = Some(match (ty, x) {
+                (None, x) => x,
+                (Some(a), b) if a == b => a,
+                (Some(ColTy::Integer), ColTy::Real) | (Some(ColTy::Real), ColTy::Integer) => {
+                    ColTy::Real
+                }
+                _ => ColTy::Text,
+            });
+        }
+
+        ty.unwrap_or(ColTy::Text)
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ColTy::Integer => "INTEGER",
+            ColTy::Real => "REAL",
+            ColTy::Text => "TEXT",
+        }
+    }
+
+    fn value(self, e: &Entry<Value>, fmtr: &mut numfmt::Formatter) -> Sql {
+        match (self, Value::from(e)) {
+            (_, Value::Nil) => Sql::Null,
+            (ColTy::Integer, Value::Bool(b)) => Sql::Integer(b as i64),
+            (ColTy::Integer, Value::Num(n)) => Sql::Integer(n.as_f64() as i64),
+            (ColTy::Real, Value::Num(n)) => Sql::Real(n.as_f64()),
+            _ => Sql::Text(print::fmt_cell(e, fmtr).to_string()),
+        }
+    }
+}
+
+/// Write the table into the database, replacing any existing table of the same name.
+/// The first row is used as the column names, and the column types are inferred from the values.
+#[cfg(feature = "sqlite")]
+pub fn write_table(path: &Path, name: &str, table: &Table) -> io::Result<()> {
+    let fmtr = &mut numfmt::Formatter::new();
+    let header = table
+        .row(0)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "cannot save an empty table"))?
+        .map(|e| ident(&print::fmt_cell(e, fmtr)))
+        .collect::<Vec<_>>();
+    let tys = (0..table.cols_len())
+        .map(|i| ColTy::infer(table.rows().skip(1).filter_map(|mut r| r.nth(i))))
+        .collect::<Vec<_>>();
+
+    let name = ident(name);
+    let cols = header
+        .iter()
+        .zip(&tys)
+        .map(|(h, ty)| format!("{} {}", h, ty.name()))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let params = vec!["?"; header.len()].join(", ");
+
+    let mut conn = Connection::open(path).map_err(sql_err)?;
+    let tx = conn.transaction().map_err(sql_err)?;
+    tx.execute(&format!("DROP TABLE IF EXISTS {}", name), [])
+        .and_then(|_| tx.execute(&format!("CREATE TABLE {} ({})", name, cols), []))
+        .map_err(sql_err)?;
+    {
+        let mut stmt = tx
+            .prepare(&format!("INSERT INTO {} VALUES ({})", name, params))
+            .map_err(sql_err)?;
+        for row in table.rows().skip(1) {
+            let values = row.zip(&tys).map(|(e, ty)| ty.value(e, fmtr));
+            stmt.execute(params_from_iter(values)).map_err(sql_err)?;
+        }
+    }
+    tx.commit().map_err(sql_err)
+}
+
+// ------ Sql Query ------------------------------------------------------------
+#[cfg(feature = "sqlite")]
+fn sql_query_help() -> HelpMessage {
+    HelpMessage {
+        desc: "run a SQL query against a SQLite database, returning the results as a table
+the database is opened read-only, the column names become the headers"
+            .into(),
+        params: vec![
+            HelpParameter::Required("database".into()),
+            HelpParameter::Required("query".into()),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "query a database",
+                code: "sql-query db.sqlite 'SELECT name, size FROM files WHERE size > 100'",
+            },
+            HelpExample {
+                desc: "list the tables and indices in a database",
+                code: "sql-query db.sqlite 'SELECT name, type FROM sqlite_master'",
+            },
+        ],
+        ..HelpMessage::new("sql-query")
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn sql_query_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Tab);
+
+    let db = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let sql = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |val, cx| {
+        let db: Str = db.resolve(|| val.clone(), &cx)?.try_into()?;
+        let sql: Str = sql.resolve(|| val, &cx)?.try_into()?;
+        read_path(&db, &cx)
+            .and_then(|p| query(&p, &sql))
+            .map_err(|e| Error::io(&blktag, e))
+            .and_then(|t| cx.done_o(t))
+    })
+}
@@ -0,0 +1,83 @@
+//! Text encodings for reading and writing files.
+//!
+//! Encodings are named using [WHATWG labels](https://encoding.spec.whatwg.org/#names-and-labels),
+//! such as `utf-8`, `utf-16le`, or `windows-1252`.
+use super::*;
+use ::encoding::{all::UTF_8, DecoderTrap, EncoderTrap, Encoding, EncodingRef};
+
+/// Look up an encoding by its label, returning the canonical name of the encoding.
+pub fn lookup(label: &str) -> Option<&'static str> {
+    ::encoding::label::encoding_from_whatwg_label(label).map(name)
+}
+
+fn name(enc: EncodingRef) -> &'static str {
+    enc.whatwg_name().unwrap_or_else(|| enc.name())
+}
+
+/// Get the encoding of a canonical name, defaulting to UTF-8.
+fn encoding(name: Option<&str>) -> EncodingRef {
+    name.and_then(::encoding::label::encoding_from_whatwg_label)
+        .unwrap_or(UTF_8)
+}
+
+/// Decode the bytes into a string.
+///
+/// A UTF-8 or UTF-16 byte order mark takes precedence over the given encoding, which defaults
+/// to UTF-8.
+pub fn decode(bytes: &[u8], encoding_name: Option<&str>) -> io::Result<String> {
+    ::encoding::decode(bytes, DecoderTrap::Strict, encoding(encoding_name))
+        .0
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Encode the string using the encoding, which defaults to UTF-8.
+///
+/// UTF-16 output is prefixed with a byte order mark so that it can be detected when read back.
+pub fn encode(s: &str, encoding_name: Option<&str>) -> io::Result<Vec<u8>> {
+    let enc = encoding(encoding_name);
+    let bom: &[u8] = match name(enc) {
+        "utf-16le" => &[0xFF, 0xFE],
+        "utf-16be" => &[0xFE, 0xFF],
+        _ => &[],
+    };
+
+    let mut bytes = bom.to_vec();
+    enc.encode_to(s, EncoderTrap::Strict, &mut bytes)
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} cannot be encoded as {}", e, name(enc)),
+            )
+        })?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_test() {
+        assert_eq!(lookup("utf-8"), Some("utf-8"));
+        assert_eq!(lookup("UTF8"), Some("utf-8"));
+        assert_eq!(lookup("latin1"), Some("windows-1252"));
+        assert_eq!(lookup("utf-16"), Some("utf-16le"));
+        assert_eq!(lookup("foo"), None);
+    }
+
+    #[test]
+    fn round_trips() {
+        for enc in ["utf-8", "utf-16le", "utf-16be", "windows-1252"] {
+            let bytes = encode("Zoë, €5", Some(enc)).unwrap();
+            assert_eq!(&decode(&bytes, Some(enc)).unwrap(), "Zoë, €5");
+            // bom detection
+            if enc.starts_with("utf-16") {
+                assert_eq!(&decode(&bytes, None).unwrap(), "Zoë, €5");
+            }
+        }
+
+        assert_eq!(encode("Zoë", Some("windows-1252")).unwrap(), b"Zo\xEB");
+        assert!(decode(b"Zo\xEB", None).is_err());
+        assert!(encode("日本", Some("windows-1252")).is_err());
+    }
+}
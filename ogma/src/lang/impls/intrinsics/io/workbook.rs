@@ -0,0 +1,256 @@
+//! Spreadsheet workbook reading (xlsx, xlsm, xlsb, xls, ods).
+//!
+//! Workbooks are opened with `open`, optionally selecting a sheet (`--sheet`) and a cell range
+//! (`--range`). The sheet names are listed with the `sheets` command.
+//!
+//! Requires the `workbook` feature, without it reading a workbook is an error.
+use super::*;
+#[cfg(feature = "workbook")]
+use ::calamine::{open_workbook_auto, Data, Reader};
+use std::fmt;
+
+pub fn add_intrinsics(_impls: &mut Implementations) {
+    #[cfg(feature = "workbook")]
+    add! { _impls,
+        (sheets, Io)
+    };
+}
+
+/// Returns if the path has a workbook extension.
+pub fn is_workbook(path: &Path) -> bool {
+    path.extension()
+        .and_then(|x| x.to_str())
+        .map(|x| {
+            matches!(
+                x.to_lowercase().as_str(),
+                "xlsx" | "xlsm" | "xlsb" | "xls" | "ods"
+            )
+        })
+        .unwrap_or_default()
+}
+
+/// A sheet within a workbook, either by name or by (zero-based) index.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sheet(Str);
+
+impl Sheet {
+    /// A sheet is resolved by name when the workbook is read, an integer is only used as an index
+    /// if no sheet has that name.
+    pub fn parse(s: &str) -> Self {
+        Sheet(Str::new(s))
+    }
+
+    /// Find the sheet in `names`, matching the name first, then an integer as an index.
+    pub fn find<'a>(&self, names: &'a [String]) -> Option<&'a String> {
+        names
+            .iter()
+            .find(|x| x.as_str() == self.0.as_str())
+            .or_else(|| self.0.parse::<usize>().ok().and_then(|i| names.get(i)))
+    }
+}
+
+impl fmt::Display for Sheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// An inclusive range of cells, as zero-based `(row, col)` positions.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CellRange {
+    pub start: (u32, u32),
+    pub end: (u32, u32),
+}
+
+impl CellRange {
+    /// Parse a range in the A1 reference style, such as `A1:F200`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let (start, end) = s.split_once(':')?;
+        let (start, end) = (cell_ref(start)?, cell_ref(end)?);
+        (start.0 <= end.0 && start.1 <= end.1).then(|| CellRange { start, end })
+    }
+}
+
+impl fmt::Display for CellRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cell = |(row, col): (u32, u32)| {
+            let mut letters = Vec::new();
+            let mut c = col + 1;
+            while c > 0 {
+                letters.push((b'A' + ((c - 1) % 26) as u8) as char);
+                c = (c - 1) / 26;
+            }
+            letters.iter().rev().collect::<String>() + &(row + 1).to_string()
+        };
+        write!(f, "{}:{}", cell(self.start), cell(self.end))
+    }
+}
+
+/// Parse a cell reference such as `B12` into a zero-based `(row, col)`.
+fn cell_ref(s: &str) -> Option<(u32, u32)> {
+    let split = s.find(|c: char| !c.is_ascii_alphabetic())?;
+    let (letters, digits) = s.split_at(split);
+    if letters.is_empty() || letters.len() > 3 {
+        return None;
+    }
+    let col = letters
+        .bytes()
+        .map(|b| (b.to_ascii_uppercase() - b'A') as u32 + 1)
+        .fold(0, |acc, x| acc * 26 + x);
+    let row = digits.parse::<u32>().ok().filter(|&r| r > 0)?;
+    Some((row - 1, col - 1))
+}
+
+#[cfg(not(feature = "workbook"))]
+fn disabled(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "workbooks are not supported, enable the `workbook` feature: {}",
+            path.display()
+        ),
+    )
+}
+
+/// Read a sheet of the workbook into a table.
+#[cfg(not(feature = "workbook"))]
+pub fn read(path: &Path, _sheet: Option<&Sheet>, _range: Option<CellRange>) -> io::Result<Table> {
+    Err(disabled(path))
+}
+
+#[cfg(feature = "workbook")]
+fn wb_err<E: fmt::Display>(e: E, path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("{}: {}", e, path.display()),
+    )
+}
+
+/// List the names of the sheets in the workbook.
+#[cfg(feature = "workbook")]
+pub fn sheet_names(path: &Path) -> io::Result<Vec<String>> {
+    open_workbook_auto(path)
+        .map(|wb| wb.sheet_names())
+        .map_err(|e| wb_err(e, path))
+}
+
+/// Read a sheet of the workbook into a table, defaulting to the first sheet.
+/// If `range` is specified, only the cells within it are read, otherwise the used cells are read.
+#[cfg(feature = "workbook")]
+pub fn read(path: &Path, sheet: Option<&Sheet>, range: Option<CellRange>) -> io::Result<Table> {
+    let mut wb = open_workbook_auto(path).map_err(|e| wb_err(e, path))?;
+    let names = wb.sheet_names();
+    let name = match sheet {
+        None => names.first(),
+        Some(sheet) => sheet.find(&names),
+    }
+    .ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            format!(
+                "sheet `{}` not found, the workbook has sheets: {}: {}",
+                sheet.map(ToString::to_string).unwrap_or_default(),
+                names.join(", "),
+                path.display()
+            ),
+        )
+    })?;
+
+    let data = wb.worksheet_range(name).map_err(|e| wb_err(e, path))?;
+    let data = match range {
+        Some(r) => data.range(r.start, r.end),
+        None => data,
+    };
+
+    let rows = data
+        .rows()
+        .map(|r| r.iter().map(entry).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    Ok(Table::from(::table::Table::from(rows)))
+}
+
+#[cfg(feature = "workbook")]
+fn entry(cell: &Data) -> Entry<Value> {
+    match cell {
+        Data::Empty => Entry::Nil,
+        Data::Int(i) => n(*i),
+        Data::Float(f) => Entry::Num(num(*f)),
+        Data::Bool(b) => Entry::Obj(Value::Bool(*b)),
+        Data::String(s) | Data::DateTimeIso(s) | Data::DurationIso(s) => o(s.as_str()),
+        Data::DateTime(x) => Entry::Num(num(x.as_f64())),
+        Data::Error(e) => o(e.to_string()),
+    }
+}
+
+/// Spreadsheets store numbers as floats, integral values are stored as integers.
+#[cfg(feature = "workbook")]
+fn num(f: f64) -> Number {
+    if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+        Number::from(f as i64)
+    } else {
+        Number::from(f)
+    }
+}
+
+// ------ Sheets ---------------------------------------------------------------
+#[cfg(feature = "workbook")]
+fn sheets_help() -> HelpMessage {
+    HelpMessage {
+        desc: "list the sheets in a workbook (xlsx, xlsm, xlsb, xls, ods)
+the name or index can be used with `open --sheet`"
+            .into(),
+        params: vec![HelpParameter::Required("file".into())],
+        examples: vec![HelpExample {
+            desc: "list the sheets in a workbook",
+            code: "sheets finance.xlsx",
+        }],
+        ..HelpMessage::new("sheets")
+    }
+}
+
+#[cfg(feature = "workbook")]
+fn sheets_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Tab);
+
+    let file = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |val, cx| {
+        let file: Str = file.resolve(|| val, &cx)?.try_into()?;
+        let names = read_path(&file, &cx)
+            .and_then(|p| sheet_names(&p))
+            .map_err(|e| Error::io(&blktag, e))?;
+
+        let header = vec![o("index"), o("sheet")];
+        let rows = names
+            .into_iter()
+            .enumerate()
+            .map(|(i, name)| vec![n(i as u64), o(name)]);
+        let table = ::table::Table::from(once(header).chain(rows).collect::<Vec<_>>());
+
+        cx.done_o(Table::from(table))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sheet_names_before_indices() {
+        let names = ["Q1", "2020", "0"].map(String::from);
+        let find = |s| Sheet::parse(s).find(&names).map(String::as_str);
+
+        assert_eq!(find("Q1"), Some("Q1"));
+        assert_eq!(find("2020"), Some("2020"));
+        // a sheet named `0` is preferred over the first sheet
+        assert_eq!(find("0"), Some("0"));
+        assert_eq!(find("1"), Some("2020"));
+        assert_eq!(find("3"), None);
+        assert_eq!(find("Q2"), None);
+    }
+}
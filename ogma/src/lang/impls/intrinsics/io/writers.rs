@@ -0,0 +1,339 @@
+//! Writers which serialise a [`Value`] to a file.
+//!
+//! Writers are keyed on the (lowercase) file extension, if no writer matches the extension then
+//! the value is written using [`write_file`] (tables as comma separated values).
+use super::*;
+use ::libs::serde_json::{self, Value as J};
+use std::path::Path;
+
+/// Options which alter how a value is written.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WriteOpts {
+    /// Use CRLF line endings rather than LF.
+    pub crlf: bool,
+    /// Encode the output using this encoding (see [`text::lookup`]), defaults to UTF-8.
+    pub encoding: Option<&'static str>,
+}
+
+/// Writes a value out.
+pub type WriteFn = fn(&mut dyn Write, Value, &WriteOpts) -> io::Result<()>;
+
+::lazy_static::lazy_static! {
+    /// The value writers, keyed on file extension.
+    static ref WRITERS: HashMap<&'static str, WriteFn> = {
+        let mut map: HashMap<_, WriteFn> = HashMap::default();
+        map.insert("json", json);
+        map.insert("ndjson", ndjson);
+        map.insert("jsonl", ndjson);
+        map.insert("md", md);
+        map.insert("markdown", md);
+        map.insert("html", html);
+        map.insert("htm", html);
+        map.insert("parquet", columnar::write_parquet);
+        map.insert("pq", columnar::write_parquet);
+        map.insert("arrow", columnar::write_ipc);
+        map.insert("ipc", columnar::write_ipc);
+        map.insert("feather", columnar::write_ipc);
+        map
+    };
+}
+
+impl WriteOpts {
+    /// The line ending to use.
+    pub fn newline(&self) -> &'static str {
+        if self.crlf {
+            "\r\n"
+        } else {
+            "\n"
+        }
+    }
+}
+
+/// Write the `value`, choosing the writer using the extension of `path`.
+pub fn write_value(
+    path: &Path,
+    file: &mut dyn Write,
+    value: Value,
+    opts: &WriteOpts,
+) -> io::Result<()> {
+    let write = writer(path);
+    match opts.encoding.filter(|&e| e != "utf-8") {
+        Some(enc) => {
+            // writers output UTF-8, which is then transcoded
+            let mut buf = Vec::new();
+            write(&mut buf, value, opts)?;
+            let s = String::from_utf8(buf)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+            file.write_all(&text::encode(&s, Some(enc))?)?;
+        }
+        None => write(file, value, opts)?,
+    }
+    file.flush()
+}
+
+/// Get the writer registered against the `path`'s extension.
+/// Defaults to [`write_file`].
+fn writer(path: &Path) -> WriteFn {
+    compress::strip_ext(path)
+        .extension()
+        .and_then(|x| x.to_str())
+        .and_then(|x| WRITERS.get(x.to_lowercase().as_str()))
+        .copied()
+        .unwrap_or(dsv as WriteFn)
+}
+
+fn dsv(mut file: &mut dyn Write, value: Value, opts: &WriteOpts) -> io::Result<()> {
+    write_file(&mut file, value, opts)
+}
+
+/// Tables are written as an array of objects, keyed on the header row.
+fn json(file: &mut dyn Write, value: Value, _: &WriteOpts) -> io::Result<()> {
+    let fmtr = &mut numfmt::Formatter::new();
+    let json = match value {
+        Value::Tab(table) => J::Array(json_objs(&table, fmtr).collect()),
+        x => json_entry(&Entry::from(x), fmtr),
+    };
+    serde_json::to_writer(file, &json).map_err(io::Error::from)
+}
+
+/// Tables are written as an object per line, keyed on the header row.
+fn ndjson(file: &mut dyn Write, value: Value, opts: &WriteOpts) -> io::Result<()> {
+    let fmtr = &mut numfmt::Formatter::new();
+    match value {
+        Value::Tab(table) => {
+            for obj in json_objs(&table, fmtr) {
+                serde_json::to_writer(&mut *file, &obj)?;
+                write!(file, "{}", opts.newline())?;
+            }
+            Ok(())
+        }
+        x => {
+            serde_json::to_writer(&mut *file, &json_entry(&Entry::from(x), fmtr))?;
+            write!(file, "{}", opts.newline())
+        }
+    }
+}
+
+/// Tables are written as a Markdown pipe table, other values are written as text.
+fn md(file: &mut dyn Write, value: Value, opts: &WriteOpts) -> io::Result<()> {
+    match value {
+        Value::Tab(table) => print::write_md_table(&table, file),
+        x => dsv(file, x, opts),
+    }
+}
+
+/// Tables are written as a standalone HTML document, other values are written as text.
+fn html(file: &mut dyn Write, value: Value, opts: &WriteOpts) -> io::Result<()> {
+    match value {
+        Value::Tab(table) => {
+            writeln!(file, "<!DOCTYPE html>")?;
+            writeln!(file, "<html>")?;
+            writeln!(file, "<head><meta charset=\"utf-8\"></head>")?;
+            writeln!(file, "<body>")?;
+            print::write_html_table(&table, file)?;
+            writeln!(file, "</body>")?;
+            writeln!(file, "</html>")
+        }
+        x => dsv(file, x, opts),
+    }
+}
+
+/// Map each table row (after the header) into a JSON object.
+///
+/// Repeated header names are suffixed (`a`, `a_1`, ...) so no column is lost.
+fn json_objs<'a>(
+    table: &'a Table,
+    fmtr: &'a mut numfmt::Formatter,
+) -> impl Iterator<Item = J> + 'a {
+    let header = table
+        .row(0)
+        .map(|r| {
+            let names = r.map(|e| print::fmt_cell(e, fmtr).to_string()).collect();
+            json_keys(names)
+        })
+        .unwrap_or_else(Vec::new);
+
+    table.rows().skip(1).map(move |row| {
+        J::Object(
+            header
+                .iter()
+                .cloned()
+                .zip(row.map(|e| json_entry(e, fmtr)))
+                .collect(),
+        )
+    })
+}
+
+/// Deduplicate header names, suffixing repeats with the first free `_<n>`.
+fn json_keys(names: Vec<String>) -> Vec<String> {
+    let mut seen: HashSet<String> = names.iter().cloned().collect();
+    let mut used = HashSet::default();
+    names
+        .into_iter()
+        .map(|name| {
+            if used.insert(name.clone()) {
+                return name;
+            }
+            let key = (1..)
+                .map(|i| format!("{}_{}", name, i))
+                .find(|k| !seen.contains(k))
+                .expect("infinite iterator");
+            seen.insert(key.clone());
+            used.insert(key.clone());
+            key
+        })
+        .collect()
+}
+
+fn json_entry(entry: &Entry<Value>, fmtr: &mut numfmt::Formatter) -> J {
+    match entry {
+        Entry::Nil | Entry::Obj(Value::Nil) => J::Null,
+        Entry::Num(n) | Entry::Obj(Value::Num(n)) => json_num(n),
+        Entry::Obj(Value::Bool(x)) => J::Bool(*x),
+        Entry::Obj(Value::Str(x)) => J::String(x.to_string()),
+        Entry::Obj(Value::Tab(x)) => J::Array(json_objs(x, fmtr).collect()),
+        e => J::String(print::fmt_cell(e, fmtr).to_string()),
+    }
+}
+
+/// Whole numbers are written as integers, non-finite numbers are written as `null`.
+fn json_num(n: &Number) -> J {
+    let f = n.as_f64();
+    if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+        J::from(f as i64)
+    } else {
+        serde_json::Number::from_f64(f)
+            .map(J::Number)
+            .unwrap_or(J::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(p: &str, v: Value) -> String {
+        let mut wtr = Vec::new();
+        write_value(Path::new(p), &mut wtr, v, &Default::default()).unwrap();
+        String::from_utf8(wtr).unwrap()
+    }
+
+    fn table() -> Value {
+        Value::Tab(Table::from(::table::Table::from(vec![
+            vec![o("a"), o("b"), o("c")],
+            vec![n(1), o("x"), Entry::Nil],
+            vec![n(2.5), Entry::Nil, Entry::Obj(Value::Bool(true))],
+        ])))
+    }
+
+    #[test]
+    fn writer_by_extension() {
+        assert!(write("foo.csv", table()).starts_with("a,b,c\n"));
+        assert_eq!(&write("foo", table()), &write("foo.csv", table()));
+        assert_eq!(
+            &write("foo.json", table()),
+            r#"[{"a":1,"b":"x","c":null},{"a":2.5,"b":null,"c":true}]"#
+        );
+        assert_eq!(
+            &write("foo.JSON", table()),
+            r#"[{"a":1,"b":"x","c":null},{"a":2.5,"b":null,"c":true}]"#
+        );
+        assert_eq!(
+            &write("foo.ndjson", table()),
+            "{\"a\":1,\"b\":\"x\",\"c\":null}\n{\"a\":2.5,\"b\":null,\"c\":true}\n"
+        );
+        assert_eq!(&write("foo.jsonl", table()), &write("foo.ndjson", table()));
+    }
+
+    #[test]
+    fn md_and_html() {
+        let s = write("foo.md", table());
+        assert!(s.starts_with("| a | b | c |\n| --- | --- | --- |\n"));
+        assert!(s.ends_with("\n| 2.5 |  | true |\n"));
+        assert_eq!(&write("foo.markdown", table()), &write("foo.md", table()));
+        assert_eq!(&write("foo.md", Value::Str("hello".into())), "hello");
+
+        let s = write("foo.html", table());
+        assert!(s.starts_with("<!DOCTYPE html>\n<html>\n"));
+        assert!(s.contains("<tr><th>a</th><th>b</th><th>c</th></tr>\n"));
+        assert!(s.contains("<tr><td>2.5</td><td></td><td>true</td></tr>\n"));
+        assert!(s.ends_with("</table>\n</body>\n</html>\n"));
+        assert_eq!(&write("foo.htm", table()), &s);
+    }
+
+    #[test]
+    fn encoded_output() {
+        let f = |encoding| {
+            let mut wtr = Vec::new();
+            let opts = WriteOpts {
+                encoding,
+                ..Default::default()
+            };
+            write_value(
+                Path::new("foo.txt"),
+                &mut wtr,
+                Value::Str("Zoë".into()),
+                &opts,
+            )
+            .unwrap();
+            wtr
+        };
+
+        assert_eq!(f(None), "Zoë".as_bytes());
+        assert_eq!(f(Some("utf-8")), "Zoë".as_bytes());
+        assert_eq!(f(Some("windows-1252")), b"Zo\xEB");
+        assert_eq!(f(Some("utf-16le")), b"\xFF\xFEZ\0o\0\xEB\0");
+    }
+
+    #[test]
+    fn json_non_tables() {
+        assert_eq!(&write("foo.json", Value::Bool(true)), "true");
+        assert_eq!(&write("foo.json", Value::Num(3.14.into())), "3.14");
+        assert_eq!(&write("foo.json", Value::Num(std::f64::NAN.into())), "null");
+        assert_eq!(&write("foo.json", Value::Str("a\"b".into())), r#""a\"b""#);
+        assert_eq!(&write("foo.ndjson", Value::Nil), "null\n");
+    }
+
+    #[test]
+    fn json_nested_tables() {
+        let t = Table::from(::table::Table::from(vec![
+            vec![o("a"), o("b")],
+            vec![Entry::Obj(table()), n(-1)],
+        ]));
+        assert_eq!(
+            &write("foo.json", Value::Tab(t)),
+            r#"[{"a":[{"a":1,"b":"x","c":null},{"a":2.5,"b":null,"c":true}],"b":-1}]"#
+        );
+    }
+
+    #[test]
+    fn json_duplicate_headers() {
+        let t = Table::from(::table::Table::from(vec![
+            vec![o("a"), o("a"), o("a_1"), o("a")],
+            vec![n(1), n(2), n(3), n(4)],
+        ]));
+        assert_eq!(
+            &write("foo.json", Value::Tab(t)),
+            r#"[{"a":1,"a_2":2,"a_1":3,"a_3":4}]"#
+        );
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let round_trip = |v: Value| {
+            let s = write("foo.json", v);
+            Value::Tab(readers::read_table(Path::new("foo.json"), &s, &Default::default()).unwrap())
+        };
+
+        assert_eq!(round_trip(table()), table());
+
+        // bool only column and a nested table
+        let b = |x| Entry::Obj(Value::Bool(x));
+        let t = Value::Tab(Table::from(::table::Table::from(vec![
+            vec![o("a"), o("b"), o("c")],
+            vec![b(true), Entry::Obj(table()), n(1)],
+            vec![b(false), Entry::Obj(table()), n(-2.5)],
+        ])));
+        assert_eq!(round_trip(t.clone()), t);
+    }
+}
@@ -2,6 +2,7 @@ use crate::prelude::*;
 use ::kserd::Number;
 use ::libs::{divvy::Str, rayon::prelude::*};
 use ::paste::paste;
+use ::rust_decimal::Decimal;
 use ::table::Entry;
 use ast::{Location, Tag};
 use eng::{AnonTypes, Block, Context, Step};
@@ -10,6 +11,7 @@ use std::{
     convert::{TryFrom, TryInto},
     iter::*,
 };
+use types::Map; // disambiguate from `std::iter::Map`
 use Type as Ty;
 
 macro_rules! add {
@@ -42,15 +44,19 @@ macro_rules! add {
 
 mod arithmetic;
 mod cmp;
+mod datetime;
 mod diagnostics;
 mod io;
 mod logic;
 mod morphism;
 mod pipeline;
 
+pub use io::{parse_table, write_as};
+
 pub fn add_intrinsics(impls: &mut Implementations) {
     arithmetic::add_intrinsics(impls);
     cmp::add_intrinsics(impls);
+    datetime::add_intrinsics(impls);
     diagnostics::add_intrinsics(impls);
     io::add_intrinsics(impls);
     logic::add_intrinsics(impls);
@@ -196,19 +202,88 @@ fn n<N: Into<Number>>(n: N) -> Entry<Value> {
 fn type_flag(blk: &mut Block) -> Result<Option<Type>> {
     blk.get_flag(None)
         .map(|ty| {
-            let x = if ty.str().starts_with("U_") {
-                Tuple::parse_name(ty.str(), blk.defs().types())
-            } else {
-                None
-            };
-            match x {
-                Some(x) => Ok(x),
-                None => blk.defs().types().get_using_tag(&ty).map(|x| x.clone()),
-            }
+            Type::parse_name(ty.str(), blk.defs().types()).ok_or_else(|| Error::type_not_found(&ty))
         })
         .transpose()
 }
 
+/// Remove the arguments that follow `flag`, for flags such as `--delim <char>` whose values are
+/// supplied as arguments.
+///
+/// `count` limits the number of arguments taken, otherwise every argument after the flag is
+/// taken. At least one argument is expected, `usage` is used in the error otherwise.
+fn take_flag_args(
+    args: &mut Vec<eng::Argument>,
+    flag: &Tag,
+    count: Option<usize>,
+    usage: &str,
+) -> Result<Vec<eng::Argument>> {
+    let i = args
+        .iter()
+        .position(|a| a.tag.start > flag.start)
+        .ok_or_else(|| {
+            Error::invalid_flag_value(
+                flag,
+                format!("`--{}` expects a value", flag.str()),
+                format!("supply the value after the flag, ie `--{}`", usage),
+            )
+        })?;
+    let end = count.map(|n| (i + n).min(args.len())).unwrap_or(args.len());
+    Ok(args.drain(i..end).collect())
+}
+
+/// Concrete the remaining arguments, which are supplied `in_ty`.
+///
+/// Arguments with a known output type keep it, so numeric flag values such as the `2` in
+/// `--depth 2` can be read, the others are expected to return strings.
+/// Flag values are then removed with [`take_flag_args`], leaving the command's own arguments.
+fn concrete_args(blk: &mut Block, in_ty: Option<Type>) -> Result<Vec<eng::Argument>> {
+    let mut args = Vec::with_capacity(blk.args_len());
+    while blk.args_len() > 0 {
+        let arg = blk.next_arg()?.supplied(in_ty.clone())?;
+        let arg = match arg.return_ty() {
+            Some(_) => arg,
+            None => arg.returns(Ty::Str)?,
+        };
+        args.push(arg.concrete()?);
+    }
+    Ok(args)
+}
+
+/// Used to get a type from a flag value argument such as the `Str` in `--key Str`.
+fn type_flag_arg(arg: &eng::Argument, blk: &Block, usage: &str) -> Result<Type> {
+    let name = arg.extract_literal::<Str>()?;
+    Type::parse_name(name, blk.defs().types()).ok_or_else(|| {
+        Error::invalid_flag_value(
+            &arg.tag,
+            format!("type `{}` not defined", name),
+            format!("use a defined type, for example `--{}`", usage),
+        )
+    })
+}
+
+/// Get the element type of a `List` input, erroring if the block's input is not a list.
+///
+/// Lists are generic over their element type, so list commands are keyed on `List<T>` and take
+/// the element type from the block's input type with this.
+fn list_elem_ty(blk: &Block) -> Result<Type> {
+    match blk.in_ty() {
+        Ty::List(ty) => Ok(Type::clone(ty)),
+        x => Err(Error::wrong_op_input_type(x, blk.op_tag())),
+    }
+}
+
+/// Get the key and value types of a `Map` input, erroring if the block's input is not a map.
+///
+/// Like lists, maps are generic over their key and value types, so map commands are keyed on
+/// `Map<K,V>` and take the key and value types from the block's input type with this.
+fn map_tys(blk: &Block) -> Result<(Type, Type)> {
+    match blk.in_ty() {
+        Ty::Map(k, v) => Ok((Type::clone(k), Type::clone(v))),
+        x => Err(Error::wrong_op_input_type(x, blk.op_tag())),
+    }
+}
+
 /// Iterator over buf in a parallel fashion, invoking the callback `f` on each item of `buf`.
 ///
 /// This function is meant for iterating over table rows and updating-in-place `T`. The pattern to
@@ -1,5 +1,5 @@
 use super::*;
-use std::{cell::RefCell, cmp, collections::BTreeMap, mem, rc::Rc};
+use std::{borrow::Cow, cell::RefCell, cmp, collections::BTreeMap, mem, rc::Rc};
 
 pub fn add_intrinsics(impls: &mut Implementations) {
     add! { impls,
@@ -11,12 +11,15 @@ pub fn add_intrinsics(impls: &mut Implementations) {
 
     ("filter", Str, filter_str, Morphism)
     ("filter", Table, filter_table, Morphism)
+    ("filter", List, filter_list, Morphism)
 
     ("fold", Table, fold_table, Morphism)
+    ("fold", List, fold_list, Morphism)
     ("fold-while", Table, fold_while_table, Morphism)
     ("grp", Table, grp_table, Morphism)
     ("grp-by", Table, grpby_table, Morphism)
     ("map", Table, map_table, Morphism)
+    ("map", List, map_list, Morphism)
     ("pick", Table, pick_table, Morphism)
     ("ren", Table, ren_table, Morphism)
     ("ren-with", Table, ren_with_table, Morphism)
@@ -28,8 +31,11 @@ pub fn add_intrinsics(impls: &mut Implementations) {
     ("skip", Table, skip_table, Morphism)
 
     ("sort", Table, sort_table, Morphism)
+    ("sort", List, sort_list, Morphism)
     ("sort-by", Table, sortby_table, Morphism)
 
+    ("split", Str, split_str, Morphism)
+
     ("take", Str, take_str, Morphism)
     ("take", Table, take_table, Morphism)
     };
@@ -519,6 +525,45 @@ fn filter_table_columns(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn filter_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "filter a list based on if an item matches a predicate".into(),
+        params: vec![HelpParameter::Required("<predicate>".into())],
+        examples: vec![HelpExample {
+            desc: "keep the numbers greater than one",
+            code: "List 1 2 3 | filter > 1",
+        }],
+        ..HelpMessage::new("filter")
+    }
+}
+
+fn filter_list_intrinsic(mut blk: Block) -> Result<Step> {
+    let ty = list_elem_ty(&blk)?;
+    let oty = blk.in_ty().clone();
+    blk.assert_output(oty.clone());
+
+    let predicate = blk
+        .next_arg()?
+        .supplied(ty.clone())?
+        .returns(Ty::Bool)?
+        .concrete()?;
+
+    blk.eval(oty, move |input, cx| {
+        let list = List::try_from(input)?;
+        let mut items = Vec::with_capacity(list.len());
+        for x in list.iter() {
+            if predicate
+                .resolve(|| x.clone(), &cx)
+                .and_then(bool::try_from)?
+            {
+                items.push(x.clone());
+            }
+        }
+
+        cx.done(List::new(ty.clone(), items))
+    })
+}
+
 // ------ Fold -----------------------------------------------------------------
 fn fold_table_help() -> HelpMessage {
     HelpMessage {
@@ -578,6 +623,62 @@ fn fold_table_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn fold_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "fold (reduce) a list into single value
+fold takes a seed value and an accumulator expression
+the variable $item is available to query the list item"
+            .into(),
+        params: vec![
+            HelpParameter::Required("seed".into()),
+            HelpParameter::Required("accumulator".into()),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "sum a list of numbers",
+                code: "List 1 2 3 | fold 0 + $item",
+            },
+            HelpExample {
+                desc: "count the characters in each word",
+                code: "\\ 'the quick brown fox' | split | fold 0 + {\\ $item | len}",
+            },
+        ],
+        ..HelpMessage::new("fold")
+    }
+}
+
+fn fold_list_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_adds_vars(true);
+    let ty = list_elem_ty(&blk)?;
+
+    let seed = blk
+        .next_arg()?
+        .decouple_op_seal()
+        .supplied(Type::Nil)?
+        .concrete()?;
+    let out_ty = seed.out_ty().clone();
+    blk.assert_output(out_ty.clone());
+
+    let item_var = blk.inject_manual_var_next_arg("item", ty)?;
+    blk.assert_vars_added();
+    let acc_expr = blk
+        .next_arg()?
+        .supplied(out_ty.clone())? // accumulator supplies seed type
+        .returns(out_ty.clone())? // and must return seed type!
+        .concrete()?;
+
+    blk.eval(out_ty, move |list, mut cx| {
+        let list = List::try_from(list)?;
+        let mut x = seed.resolve(|| Value::Nil, &cx)?;
+        for item in list.iter() {
+            item_var.set_data(&mut cx.env, item.clone());
+            x = acc_expr.resolve(|| x, &cx)?;
+        }
+
+        cx.done(x)
+    })
+}
+
 // ------ Fold-While -----------------------------------------------------------
 fn fold_while_table_help() -> HelpMessage {
     HelpMessage {
@@ -950,6 +1051,47 @@ impl MapTable {
     }
 }
 
+fn map_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "replace each item in a list with the result of an expression
+the input into the expression is the item
+the expression can return a different type, changing the type of the list"
+            .into(),
+        params: vec![HelpParameter::Required("value".into())],
+        examples: vec![
+            HelpExample {
+                desc: "double each number",
+                code: "List 1 2 3 | map * 2",
+            },
+            HelpExample {
+                desc: "the length of each word. type: List<Num>",
+                code: "\\ 'the quick brown fox' | split | map len",
+            },
+        ],
+        ..HelpMessage::new("map")
+    }
+}
+
+fn map_list_intrinsic(mut blk: Block) -> Result<Step> {
+    let ty = list_elem_ty(&blk)?;
+
+    let transformation = blk.next_arg()?.supplied(ty)?.concrete()?;
+    let ty = transformation.out_ty().clone();
+    let oty = Type::List(Arc::new(ty.clone()));
+
+    blk.insert_anon_type_into_compiler(oty.clone());
+    blk.assert_output(oty.clone());
+
+    blk.eval(oty, move |input, cx| {
+        let items = List::try_from(input)?
+            .iter()
+            .map(|x| transformation.resolve(|| x.clone(), &cx))
+            .collect::<Result<Vec<_>>>()?;
+
+        cx.done(List::new(ty.clone(), items))
+    })
+}
+
 // ------ Pick -----------------------------------------------------------------
 fn pick_table_help() -> HelpMessage {
     HelpMessage {
@@ -1366,46 +1508,109 @@ fn sort_table_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
-/// Ogma data types are compared on name. OgmaData with the same types are considered 'equal'.
+fn sort_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "sort a list lowest to highest in a canonical fashion
+this sorts different value types, but NOT user-defined types"
+            .into(),
+        examples: vec![HelpExample {
+            desc: "sort words alphabetically",
+            code: "\\ 'the quick brown fox' | split | sort",
+        }],
+        ..HelpMessage::new("sort")
+    }
+}
+
+fn sort_list_intrinsic(mut blk: Block) -> Result<Step> {
+    list_elem_ty(&blk)?;
+    let oty = blk.in_ty().clone();
+    blk.assert_output(oty.clone());
+
+    blk.eval(oty, |input, cx| {
+        let mut list = List::try_from(input)?;
+        list.make_mut().sort_by(cmp_values);
+        cx.done(list)
+    })
+}
+
+/// Compares table entries, see [`cmp_values`].
 fn cmp_table_entries(a: &Entry<Value>, b: &Entry<Value>) -> std::cmp::Ordering {
-    // overall order of variants: Bool, Num, Str, OgmaData, Nil, Table, TableRow
-    let (ai, bi) = (entry_discriminant(a), entry_discriminant(b));
+    fn value(e: &Entry<Value>) -> Cow<Value> {
+        match e {
+            Entry::Nil => Cow::Owned(Value::Nil),
+            Entry::Num(n) => Cow::Owned(Value::Num(*n)),
+            Entry::Obj(v) => Cow::Borrowed(v),
+        }
+    }
+
+    cmp_values(&value(a), &value(b))
+}
+
+/// Ogma data types are compared on name. OgmaData with the same types are considered 'equal'.
+/// Lists are compared item by item, maps are considered 'equal'.
+/// Numbers and decimals are compared by value.
+fn cmp_values(a: &Value, b: &Value) -> std::cmp::Ordering {
+    // overall order of variants: Bool, Num/Dec, Str, Date, Dur, List, Map, OgmaData, Nil, Table,
+    // TableRow
+    let (ai, bi) = (value_discriminant(a), value_discriminant(b));
     if ai != bi {
         return ai.cmp(&bi);
     }
 
     use std::cmp::Ordering::*;
-    use Entry as E;
     use Value as V;
-    use E::Obj as O;
 
     // we know a and b types variants will match now
     match (a, b) {
-        (E::Nil, _) | (O(V::Nil), _) => Equal,
-        (E::Num(lhs), E::Num(rhs))
-        | (O(V::Num(lhs)), E::Num(rhs))
-        | (E::Num(lhs), O(V::Num(rhs)))
-        | (O(V::Num(lhs)), O(V::Num(rhs))) => lhs.cmp(rhs),
-        (O(V::Bool(lhs)), O(V::Bool(rhs))) => lhs.cmp(rhs),
-        (O(V::Str(lhs)), O(V::Str(rhs))) => lhs.cmp(rhs),
-        (O(V::Tab(_)), _) => Equal,
-        (O(V::TabRow(_)), _) => Equal,
-        (O(V::Ogma(lhs)), O(V::Ogma(rhs))) => lhs.ty().name().str().cmp(rhs.ty().name().str()),
+        (V::Nil, _) => Equal,
+        (V::Num(lhs), V::Num(rhs)) => lhs.cmp(rhs),
+        (V::Bool(lhs), V::Bool(rhs)) => lhs.cmp(rhs),
+        (V::Dec(lhs), V::Dec(rhs)) => lhs.cmp(rhs),
+        (V::Num(lhs), V::Dec(rhs)) => cmp_num_dec(lhs, rhs),
+        (V::Dec(lhs), V::Num(rhs)) => cmp_num_dec(rhs, lhs).reverse(),
+        (V::Str(lhs), V::Str(rhs)) => lhs.cmp(rhs),
+        (V::Date(lhs), V::Date(rhs)) => lhs.cmp(rhs),
+        (V::Dur(lhs), V::Dur(rhs)) => lhs.cmp(rhs),
+        (V::List(lhs), V::List(rhs)) => lhs
+            .iter()
+            .zip(rhs.iter())
+            .map(|(a, b)| cmp_values(a, b))
+            .find(|x| x != &Equal)
+            .unwrap_or_else(|| lhs.len().cmp(&rhs.len())),
+        (V::Map(_), _) => Equal,
+        (V::Tab(_), _) => Equal,
+        (V::TabRow(_), _) => Equal,
+        (V::Ogma(lhs), V::Ogma(rhs)) => lhs.ty().name().str().cmp(rhs.ty().name().str()),
         _ => unreachable!("should not reach here given all equal cases are done"),
     }
 }
 
-/// Assigns a number to each variant of Entry<Value> combo.
-/// `Bool = 0, Num = 1, Str = 2, OgmaData = 3, Nil = 4, Table = 5, TableRow = 6`
-fn entry_discriminant(e: &Entry<Value>) -> u8 {
-    match e {
-        Entry::Obj(Value::Bool(_)) => 0,
-        Entry::Num(_) | Entry::Obj(Value::Num(_)) => 1,
-        Entry::Obj(Value::Str(_)) => 2,
-        Entry::Obj(Value::Ogma(_)) => 3,
-        Entry::Nil | Entry::Obj(Value::Nil) => 4,
-        Entry::Obj(Value::Tab(_)) => 5,
-        Entry::Obj(Value::TabRow(_)) => 6,
+/// Compare a number to a decimal, using the number's shortest representation as a decimal.
+/// Numbers outside the decimal range (or non-finite) are ordered by sign.
+fn cmp_num_dec(n: &Number, d: &Decimal) -> std::cmp::Ordering {
+    match arithmetic::num_to_dec(n) {
+        Ok(n) => n.cmp(d),
+        Err(_) if n.as_f64() > 0.0 => std::cmp::Ordering::Greater,
+        Err(_) => std::cmp::Ordering::Less,
+    }
+}
+
+/// Assigns a number to each variant of Value.
+/// `Bool = 0, Num/Dec = 1, Str = 2, Date = 3, Dur = 4, List = 5, Map = 6, OgmaData = 7, Nil = 8,
+/// Table = 9, TableRow = 10`
+fn value_discriminant(v: &Value) -> u8 {
+    match v {
+        Value::Bool(_) => 0,
+        Value::Num(_) | Value::Dec(_) => 1,
+        Value::Str(_) => 2,
+        Value::Date(_) => 3,
+        Value::Dur(_) => 4,
+        Value::List(_) => 5,
+        Value::Map(_) => 6,
+        Value::Ogma(_) => 7,
+        Value::Nil => 8,
+        Value::Tab(_) => 9,
+        Value::TabRow(_) => 10,
     }
 }
 
@@ -1504,6 +1709,64 @@ fn reorder_table(mut table: Table, order: SortIdx) -> Table {
     ::table::Table::from(x).into()
 }
 
+// ------ Split ----------------------------------------------------------------
+fn split_str_help() -> HelpMessage {
+    HelpMessage {
+        desc: "split a string into a list of strings
+the string is split on whitespace if no separator is specified"
+            .into(),
+        params: vec![HelpParameter::Optional("separator".into())],
+        examples: vec![
+            HelpExample {
+                desc: "split a sentence into words",
+                code: "\\ 'the quick brown fox' | split",
+            },
+            HelpExample {
+                desc: "split comma separated values",
+                code: "\\ 'a,b,,c' | split ','",
+            },
+        ],
+        ..HelpMessage::new("split")
+    }
+}
+
+fn split_str_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Str)?;
+    let oty = Type::List(Arc::new(Ty::Str));
+    blk.insert_anon_type_into_compiler(oty.clone());
+    blk.assert_output(oty.clone());
+
+    let sep = match blk.args_len() {
+        0 => None,
+        _ => Some(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        ),
+    };
+
+    blk.eval(oty, move |input, cx| {
+        let sep: Option<Str> = match &sep {
+            Some(sep) => Some(sep.resolve(|| input.clone(), &cx)?.try_into()?),
+            None => None,
+        };
+        let string = Str::try_from(input)?;
+        let items = match &sep {
+            Some(sep) => string
+                .split(sep.as_str())
+                .map(|x| Value::Str(x.into()))
+                .collect(),
+            None => string
+                .split_whitespace()
+                .map(|x| Value::Str(x.into()))
+                .collect(),
+        };
+
+        cx.done(List::new(Ty::Str, items))
+    })
+}
+
 // ------ Take -----------------------------------------------------------------
 fn take_str_help() -> HelpMessage {
     HelpMessage {
@@ -3,6 +3,13 @@ use libs::fastrand;
 
 pub fn add_intrinsics(impls: &mut Implementations) {
     add! { impls,
+        (arg, Pipeline)
+
+        ("contains", Map, contains_map, Pipeline)
+
+        (env, Pipeline)
+
+        ("get", Map, get_map, Pipeline)
         ("get", TableRow, get_tabrow, Pipeline)
         (get, Pipeline)
 
@@ -14,28 +21,190 @@ pub fn add_intrinsics(impls: &mut Implementations) {
             ast::DotOperatorBlock::help
         )
         ("\\", in, Pipeline)
+        ("insert", Map, insert_map, Pipeline)
 
         ("len", Str, len_str, Pipeline)
         ("len", Table, len_table, Pipeline)
+        ("len", List, len_list, Pipeline)
 
         (let, Pipeline)
+        ("List", list, Pipeline)
+        ("merge", Map, merge_map, Pipeline)
 
         ("nth", Str, nth_str, Pipeline)
         ("nth", Table, nth_table, Pipeline)
+        ("nth", List, nth_list, Pipeline)
 
         (rand, Pipeline)
         (range, Pipeline)
         ("Table", table, Pipeline)
 
+        ("to-list", Table, to_list_table, Pipeline)
+        ("to-map", Table, to_map_table, Pipeline)
+
         ("to-str", bool, to_str_bool, Pipeline)
         ("to-str", Number, to_str_num, Pipeline)
         ("to-str", Str, to_str_str, Pipeline)
+        ("to-str", DateTime, to_str_date, Pipeline)
+        ("to-str", Table, to_str_table, Pipeline)
         ("to-str", to_str, Pipeline)
 
+        ("to-table", to_table, Pipeline)
+
         ("Tuple", tuple, Pipeline)
     };
 }
 
+// ------ Arg ------------------------------------------------------------------
+fn arg_help() -> HelpMessage {
+    HelpMessage {
+        desc: "return the value of a script argument, supplied with `ogma file.ogma -- name=value`
+the name must be a literal, and is checked before evaluation
+if a default is given, the argument is parsed into the type of the default"
+            .into(),
+        params: vec![
+            HelpParameter::Required("name".into()),
+            HelpParameter::Optional("default".into()),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "open the file supplied with `-- file=data.csv`",
+                code: "open { arg file }",
+            },
+            HelpExample {
+                desc: "take a number of rows, defaulting to 10",
+                code: "open file.csv | take { arg rows 10 }",
+            },
+        ],
+        ..HelpMessage::new("arg")
+    }
+}
+
+fn arg_intrinsic(mut blk: Block) -> Result<Step> {
+    let name = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let tag = name.tag.clone();
+    let name = name.extract_literal::<Str>()?.clone();
+    let value = blk.defs().arg(&name);
+
+    if blk.args_len() == 0 {
+        let value = value
+            .map(Str::new)
+            .ok_or_else(|| Error::script_arg_not_found(&name, &tag))?;
+        return blk.eval_o(move |_, cx| cx.done_o(value.clone()));
+    }
+
+    let default = blk.next_arg()?.supplied(None)?.concrete()?;
+    let ty = default.out_ty().clone();
+    // parse the supplied value now, so it is type checked before evaluation
+    let value = value
+        .map(|v| parse_script_arg(&name, v, &ty, &default.tag))
+        .transpose()?;
+
+    blk.eval(ty, move |input, cx| match &value {
+        Some(v) => cx.done(v.clone()),
+        None => default.resolve(|| input, &cx).and_then(|v| cx.done(v)),
+    })
+}
+
+fn parse_script_arg(name: &str, value: &str, ty: &Type, tag: &Tag) -> Result<Value> {
+    let err = || Error::script_arg_ty(name, value, ty, tag);
+    match ty {
+        Ty::Str => Ok(Value::Str(Str::new(value))),
+        Ty::Num => value.parse::<Number>().map(Value::Num).map_err(|_| err()),
+        Ty::Bool => value.parse::<bool>().map(Value::Bool).map_err(|_| err()),
+        _ => Err(err()),
+    }
+}
+
+// ------ Contains -------------------------------------------------------------
+fn contains_map_help() -> HelpMessage {
+    HelpMessage {
+        desc: "returns if a map contains an entry under the key".into(),
+        params: vec![HelpParameter::Required("key".into())],
+        examples: vec![HelpExample {
+            desc: "check if there is a file named 'README.md'",
+            code: "ls | to-map name size | contains 'README.md'",
+        }],
+        ..HelpMessage::new("contains")
+    }
+}
+
+fn contains_map_intrinsic(mut blk: Block) -> Result<Step> {
+    let (kty, _) = map_tys(&blk)?;
+    blk.assert_output(Ty::Bool);
+
+    let key = blk.next_arg()?.supplied(None)?.returns(kty)?.concrete()?;
+
+    blk.eval_o(move |input, cx| {
+        let k = key.resolve(|| input.clone(), &cx)?;
+        Map::try_from(input)
+            .map(|m| m.contains_key(&k))
+            .and_then(|x| cx.done_o(x))
+    })
+}
+
+// ------ Env ------------------------------------------------------------------
+fn env_help() -> HelpMessage {
+    HelpMessage {
+        desc: "return the value of an environment variable
+errors if the variable is not set and no default is given"
+            .into(),
+        params: vec![
+            HelpParameter::Required("name".into()),
+            HelpParameter::Optional("default".into()),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "get the home directory",
+                code: "env HOME",
+            },
+            HelpExample {
+                desc: "open a file from a variable, with a default",
+                code: "open { env DATA_FILE 'data.csv' }",
+            },
+        ],
+        ..HelpMessage::new("env")
+    }
+}
+
+fn env_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_output(Ty::Str);
+
+    let name = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Str)?
+        .concrete()?;
+    let default = if blk.args_len() > 0 {
+        Some(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        )
+    } else {
+        None
+    };
+
+    blk.eval_o(move |input, cx| {
+        let n = name
+            .resolve(|| input.clone(), &cx)
+            .and_then(Str::try_from)?;
+        match (std::env::var(n.as_str()), &default) {
+            (Ok(v), _) => cx.done_o(Str::from(v)),
+            (Err(_), Some(d)) => d
+                .resolve(|| input, &cx)
+                .and_then(Str::try_from)
+                .and_then(|v| cx.done_o(v)),
+            (Err(_), None) => Err(Error::env_var_not_found(&n, &name.tag)),
+        }
+    })
+}
+
 // ------ Get ------------------------------------------------------------------
 fn get_tabrow_help() -> HelpMessage {
     HelpMessage {
@@ -119,6 +288,63 @@ fn get_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn get_map_help() -> HelpMessage {
+    HelpMessage {
+        desc: "get the value under a key in a map
+a default can be given for missing keys"
+            .into(),
+        params: vec![
+            HelpParameter::Required("key".into()),
+            HelpParameter::Optional("default".into()),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "get the size of the file 'README.md'",
+                code: "ls | to-map name size | get 'README.md'",
+            },
+            HelpExample {
+                desc: "get the size of the file 'foo', using a default of zero",
+                code: "ls | to-map name size | get foo 0",
+            },
+        ],
+        ..HelpMessage::new("get")
+    }
+}
+
+/// Map lookups are constant time, so `get` can be used inside a table's `map`.
+fn get_map_intrinsic(mut blk: Block) -> Result<Step> {
+    let (kty, vty) = map_tys(&blk)?;
+
+    let key = blk.next_arg()?.supplied(None)?.returns(kty)?.concrete()?;
+    let default = match blk.args_len() {
+        0 => None,
+        _ => Some(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(vty.clone())?
+                .concrete()?,
+        ),
+    };
+
+    blk.eval(vty, move |input, cx| {
+        let k = key.resolve(|| input.clone(), &cx)?;
+        let map = Map::try_from(input.clone())?;
+        match (map.get(&k), &default) {
+            (Some(v), _) => cx.done(v.clone()),
+            (None, Some(d)) => d.resolve(|| input, &cx).and_then(|x| cx.done(x)),
+            (None, None) => Err(Error::eval(
+                &key.tag,
+                "key not found in map",
+                format!(
+                    "this resolves to `{}`",
+                    print::fmt_cell(&Entry::from(k.clone()), &mut numfmt::Formatter::default())
+                ),
+                "use a default to handle missing keys, ie `get key default`".to_string(),
+            )),
+        }
+    })
+}
+
 enum TableGetType {
     Default(eng::Argument),
     Flag(Type),
@@ -285,6 +511,41 @@ fn in_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+// ------ Insert ---------------------------------------------------------------
+fn insert_map_help() -> HelpMessage {
+    HelpMessage {
+        desc: "insert an entry into a map
+if the key already exists, the value is replaced"
+            .into(),
+        params: vec![
+            HelpParameter::Required("key".into()),
+            HelpParameter::Required("value".into()),
+        ],
+        examples: vec![HelpExample {
+            desc: "add a file 'foo' with a size of zero",
+            code: "ls | to-map name size | insert foo 0",
+        }],
+        ..HelpMessage::new("insert")
+    }
+}
+
+fn insert_map_intrinsic(mut blk: Block) -> Result<Step> {
+    let (kty, vty) = map_tys(&blk)?;
+    let oty = blk.in_ty().clone();
+    blk.assert_output(oty.clone());
+
+    let key = blk.next_arg()?.supplied(None)?.returns(kty)?.concrete()?;
+    let value = blk.next_arg()?.supplied(None)?.returns(vty)?.concrete()?;
+
+    blk.eval(oty, move |input, cx| {
+        let k = key.resolve(|| input.clone(), &cx)?;
+        let v = value.resolve(|| input.clone(), &cx)?;
+        let mut map = Map::try_from(input)?;
+        map.insert(k, v);
+        cx.done(map)
+    })
+}
+
 // ------ Length ---------------------------------------------------------------
 fn len_str_help() -> HelpMessage {
     HelpMessage {
@@ -348,6 +609,29 @@ fn len_table_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn len_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "return the number of items in a list".into(),
+        examples: vec![HelpExample {
+            desc: "number of words in a sentence",
+            code: "\\ 'the quick brown fox' | split | len",
+        }],
+        ..HelpMessage::new("len")
+    }
+}
+
+fn len_list_intrinsic(mut blk: Block) -> Result<Step> {
+    list_elem_ty(&blk)?;
+    blk.assert_output(Ty::Num);
+
+    blk.eval_o(|l, cx| {
+        List::try_from(l)
+            .map(|l| l.len())
+            .map(Number::from)
+            .and_then(|x| cx.done_o(x))
+    })
+}
+
 // ------ Let ------------------------------------------------------------------
 fn let_help() -> HelpMessage {
     HelpMessage {
@@ -457,6 +741,113 @@ fn let_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+// ------ List ctor ------------------------------------------------------------
+fn list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "construct a list of the result of each expression
+lists are homogeneous, each expression must return the same type
+lists have the type `List<Ty>`, use a type flag to construct an empty list"
+            .into(),
+        params: vec![HelpParameter::Optional("args..".into())],
+        flags: vec![("<type>", "the type of the list items")],
+        examples: vec![
+            HelpExample {
+                desc: "create a list of numbers. type: List<Num>",
+                code: "List 1 2 3",
+            },
+            HelpExample {
+                desc: "create a list of strings. type: List<Str>",
+                code: "List foo bar 'hello world'",
+            },
+            HelpExample {
+                desc: "create an empty list of strings",
+                code: "List --Str",
+            },
+        ],
+        ..HelpMessage::new("List")
+    }
+}
+
+fn list_intrinsic(mut blk: Block) -> Result<Step> {
+    let len = blk.args_len();
+    let mut ty = type_flag(&mut blk)?;
+    if len == 0 && ty.is_none() {
+        return Err(Error::insufficient_args(blk.blk_tag(), 0, None));
+    }
+
+    // the first argument sets the list type if there is no type flag
+    let mut items = Vec::with_capacity(len);
+    for _ in 0..len {
+        let arg = blk.next_arg()?.supplied(None)?;
+        let arg = match &ty {
+            Some(ty) => arg.returns(ty.clone())?,
+            None => arg,
+        }
+        .concrete()?;
+        ty.get_or_insert_with(|| arg.out_ty().clone());
+        items.push(arg);
+    }
+
+    let ty = ty.expect("list type is set by flag or first argument");
+    let oty = Type::List(Arc::new(ty.clone()));
+
+    blk.insert_anon_type_into_compiler(oty.clone());
+    blk.assert_output(oty.clone());
+
+    blk.eval(oty, move |input, cx| {
+        let mut data = Vec::with_capacity(items.len());
+        for arg in &items {
+            data.push(arg.resolve(|| input.clone(), &cx)?);
+        }
+        cx.done(List::new(ty.clone(), data))
+    })
+}
+
+// ------ Merge ----------------------------------------------------------------
+fn merge_map_help() -> HelpMessage {
+    HelpMessage {
+        desc: "merge the entries of one or more maps into the input map
+the maps must have the same type
+entries with the same key are replaced, with the last map taking precedence"
+            .into(),
+        params: vec![HelpParameter::Required("maps..".into())],
+        examples: vec![HelpExample {
+            desc: "merge the file sizes of two directories",
+            code: "ls foo | to-map name size | merge {ls bar | to-map name size}",
+        }],
+        ..HelpMessage::new("merge")
+    }
+}
+
+fn merge_map_intrinsic(mut blk: Block) -> Result<Step> {
+    map_tys(&blk)?;
+    let oty = blk.in_ty().clone();
+    blk.assert_output(oty.clone());
+
+    // there is at least one map to merge
+    let len = blk.args_len().max(1);
+    let mut maps = Vec::with_capacity(len);
+    for _ in 0..len {
+        maps.push(
+            blk.next_arg()?
+                .supplied(None)?
+                .returns(oty.clone())?
+                .concrete()?,
+        );
+    }
+
+    blk.eval(oty, move |input, cx| {
+        let mut map = Map::try_from(input.clone())?;
+        for arg in &maps {
+            let other = arg.resolve(|| input.clone(), &cx).and_then(Map::try_from)?;
+            for (k, v) in other.iter() {
+                map.insert(k.clone(), v.clone());
+            }
+        }
+        cx.done(map)
+    })
+}
+
 // ------ Nth ------------------------------------------------------------------
 fn nth_str_help() -> HelpMessage {
     HelpMessage {
@@ -551,6 +942,45 @@ fn nth_table_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+fn nth_list_help() -> HelpMessage {
+    HelpMessage {
+        desc: "retrieves the nth item of a list".into(),
+        params: vec![HelpParameter::Required("index".into())],
+        examples: vec![HelpExample {
+            desc: "get the second word of a sentence",
+            code: "\\ 'the quick brown fox' | split | nth 1",
+        }],
+        ..HelpMessage::new("nth")
+    }
+}
+
+fn nth_list_intrinsic(mut blk: Block) -> Result<Step> {
+    let ty = list_elem_ty(&blk)?;
+
+    let n = blk
+        .next_arg()?
+        .supplied(None)?
+        .returns(Ty::Num)?
+        .concrete()?;
+    blk.eval(ty, move |list, cx| {
+        let nth = n
+            .resolve(|| list.clone(), &cx)
+            .and_then(|v| cnv_num_to_uint::<usize>(v, &n.tag))?;
+        List::try_from(list)
+            .and_then(|l| {
+                l.get(nth).cloned().ok_or_else(|| {
+                    Error::eval(
+                        &n.tag,
+                        "index is outside list bounds",
+                        format!("this resolves to `{}`", nth),
+                        None,
+                    )
+                })
+            })
+            .and_then(|x| cx.done(x))
+    })
+}
+
 // ------ Rand -----------------------------------------------------------------
 fn rand_help() -> HelpMessage {
     HelpMessage {
@@ -763,6 +1193,174 @@ fn table_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+// ------ To List --------------------------------------------------------------
+fn to_list_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "convert a table column into a list
+the first column is used if no column is specified
+the items are expected to be numbers, use a type flag to specify otherwise"
+            .into(),
+        params: vec![HelpParameter::Optional("col-name".into())],
+        flags: vec![("<type>", "the type of the column entries")],
+        examples: vec![
+            HelpExample {
+                desc: "list the numbers zero to nine",
+                code: "range 0 10 | to-list",
+            },
+            HelpExample {
+                desc: "list the file names in the directory",
+                code: "ls | to-list name --Str",
+            },
+        ],
+        ..HelpMessage::new("to-list")
+    }
+}
+
+fn to_list_table_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Tab)?;
+
+    let colarg = match blk.args_len() {
+        0 => None,
+        _ => Some(
+            blk.next_arg()?
+                .supplied(Ty::Nil)?
+                .returns(Ty::Str)?
+                .concrete()?,
+        ),
+    };
+    let ty = type_flag(&mut blk)?.unwrap_or(Ty::Num);
+    let oty = Type::List(Arc::new(ty.clone()));
+
+    blk.insert_anon_type_into_compiler(oty.clone());
+    blk.assert_output(oty.clone());
+
+    let blktag = blk.blk_tag().clone();
+    blk.eval(oty, move |table, cx| {
+        let table = Table::try_from(table)?;
+        let (colidx, tag) = match &colarg {
+            Some(arg) => {
+                let name: Str = arg.resolve(|| Value::Nil, &cx)?.try_into()?;
+                (TableRow::col_idx(&table, &name, &arg.tag)?, &arg.tag)
+            }
+            None if table.is_empty() => {
+                return Err(Error::eval(&blktag, "table is empty", None, None))
+            }
+            None => (0, &blktag),
+        };
+
+        let colname = print::fmt_cell(
+            TableRow::entry_at(&table, 0, colidx),
+            &mut numfmt::Formatter::default(),
+        );
+        let items = (1..table.rows_len())
+            .map(|idx| {
+                let e = TableRow::entry_at(&table, idx, colidx);
+                TableRow::cnv_value(e, &ty, idx, &colname, tag)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        cx.done(List::new(ty.clone(), items))
+    })
+}
+
+// ------ To Map ---------------------------------------------------------------
+fn to_map_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "convert a table into a map, using a column for the keys and a column for the values
+the keys are expected to be strings and the values numbers, use flags to specify otherwise
+keys can be Bool, Num, Dec, Str, Date, or Dur values
+rows with the same key are replaced, the last row taking precedence"
+            .into(),
+        params: vec![
+            HelpParameter::Required("key-col".into()),
+            HelpParameter::Required("value-col".into()),
+        ],
+        flags: vec![
+            ("key <type>", "the type of the key column entries"),
+            ("value <type>", "the type of the value column entries"),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "map file names to their sizes. type: Map<Str,Num>",
+                code: "ls | to-map name size",
+            },
+            HelpExample {
+                desc: "map file names to their types. type: Map<Str,Str>",
+                code: "ls | to-map name type --value Str",
+            },
+        ],
+        ..HelpMessage::new("to-map")
+    }
+}
+
+fn to_map_table_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Tab)?;
+
+    let mut arg_flags = ["key", "value"]
+        .iter()
+        .filter_map(|&f| blk.get_flag(f))
+        .collect::<Vec<_>>();
+    let mut args = concrete_args(&mut blk, Some(Ty::Nil))?;
+    arg_flags.sort_by_key(|f| std::cmp::Reverse(f.start));
+    let (mut kty, mut vty) = (Ty::Str, Ty::Num);
+    for flag in arg_flags {
+        if flag.str() == "key" {
+            let arg = take_flag_args(&mut args, &flag, Some(1), "key Num")?.remove(0);
+            kty = type_flag_arg(&arg, &blk, "key Num")?;
+            if !Map::is_key_ty(&kty) {
+                return Err(Error::invalid_flag_value(
+                    &arg.tag,
+                    format!("`{}` cannot be used as a map key", kty),
+                    "keys can be Bool, Num, Dec, Str, Date, or Dur values".to_string(),
+                ));
+            }
+        } else {
+            let arg = take_flag_args(&mut args, &flag, Some(1), "value Str")?.remove(0);
+            vty = type_flag_arg(&arg, &blk, "value Str")?;
+        }
+    }
+
+    if args.len() < 2 {
+        return Err(Error::insufficient_args(
+            blk.blk_tag(),
+            args.len() as u8,
+            None,
+        ));
+    } else if args.len() > 2 {
+        return Err(Error::unused_args(args[2..].iter().map(|a| &a.tag)));
+    }
+    for arg in &args {
+        if arg.out_ty() != &Ty::Str {
+            return Err(Error::unexp_arg_output_ty(&Ty::Str, arg.out_ty(), &arg.tag));
+        }
+    }
+    let vcol = args.pop().expect("two arguments");
+    let kcol = args.pop().expect("two arguments");
+    let oty = Type::Map(Arc::new(kty.clone()), Arc::new(vty.clone()));
+
+    blk.insert_anon_type_into_compiler(oty.clone());
+    blk.assert_output(oty.clone());
+
+    blk.eval(oty, move |table, cx| {
+        let table = Table::try_from(table)?;
+        let kname: Str = kcol.resolve(|| Value::Nil, &cx)?.try_into()?;
+        let vname: Str = vcol.resolve(|| Value::Nil, &cx)?.try_into()?;
+        let kidx = TableRow::col_idx(&table, &kname, &kcol.tag)?;
+        let vidx = TableRow::col_idx(&table, &vname, &vcol.tag)?;
+
+        let mut map = Map::new(kty.clone(), vty.clone());
+        for idx in 1..table.rows_len() {
+            let k = TableRow::entry_at(&table, idx, kidx);
+            let k = TableRow::cnv_value(k, &kty, idx, &kname, &kcol.tag)?;
+            let v = TableRow::entry_at(&table, idx, vidx);
+            let v = TableRow::cnv_value(v, &vty, idx, &vname, &vcol.tag)?;
+            map.insert(k, v);
+        }
+
+        cx.done(map)
+    })
+}
+
 // ------ To Str ---------------------------------------------------------------
 fn to_str_bool_help() -> HelpMessage {
     HelpMessage {
@@ -839,6 +1437,109 @@ fn to_str_str_intrinsic(mut blk: Block) -> Result<Step> {
     blk.eval_o(|v, c| c.done_o(Str::try_from(v)?))
 }
 
+fn to_str_date_help() -> HelpMessage {
+    HelpMessage {
+        desc: "format the date as a string
+the format uses `strftime` specifiers, ie `%d/%m/%Y %H:%M`
+without a format, the date is formatted as `yyyy-mm-dd [hh:mm:ss]`"
+            .into(),
+        params: vec![HelpParameter::Optional("fmt".into())],
+        examples: vec![
+            HelpExample {
+                desc: "format the current date as day/month/year",
+                code: "now | to-str '%d/%m/%Y'",
+            },
+            HelpExample {
+                desc: "get the name of the day of the week",
+                code: "now | to-str '%A'",
+            },
+        ],
+        ..HelpMessage::new("to-str")
+    }
+}
+
+fn to_str_date_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Date)?;
+    blk.assert_output(Ty::Str);
+
+    let fmt = datetime::fmt_arg(&mut blk)?;
+    let blktag = blk.blk_tag().clone();
+
+    blk.eval_o(move |v, cx| {
+        let dt = DateTime::try_from(v)?;
+        let s = match &fmt {
+            Some(fmt) => fmt.format(&dt).map_err(|e| {
+                Error::eval(
+                    &blktag,
+                    format!("could not format the date: {}", e),
+                    String::from("within this block"),
+                    None,
+                )
+            })?,
+            None => dt.to_string(),
+        };
+        cx.done_o(Str::from(s))
+    })
+}
+
+fn to_str_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "render the table as a string
+without a flag the table is summarised as `<table [rows,cols]>`"
+            .into(),
+        flags: vec![
+            ("md", "render as a markdown (GitHub pipe) table"),
+            ("html", "render as a html table"),
+        ],
+        examples: vec![
+            HelpExample {
+                desc: "render a table as markdown",
+                code: "open table.csv | to-str --md",
+            },
+            HelpExample {
+                desc: "render a table as html",
+                code: "open table.csv | to-str --html",
+            },
+        ],
+        ..HelpMessage::new("to-str")
+    }
+}
+
+fn to_str_table_intrinsic(mut blk: Block) -> Result<Step> {
+    blk.assert_input(&Ty::Tab)?;
+    blk.assert_output(Ty::Str);
+
+    let md = blk.get_flag("md");
+    let html = blk.get_flag("html");
+    if let (Some(_), Some(html)) = (&md, &html) {
+        return Err(Error::unused_flags(once(html)));
+    }
+
+    let render: Option<fn(&Table, &mut dyn std::io::Write) -> std::io::Result<()>> =
+        match (md, html) {
+            (Some(_), _) => Some(print::write_md_table),
+            (None, Some(_)) => Some(print::write_html_table),
+            (None, None) => None,
+        };
+
+    let blk_tag = blk.blk_tag().clone();
+    blk.eval_o(move |v, cx| {
+        let table = Table::try_from(v)?;
+        let s = match render {
+            Some(render) => {
+                let mut buf = Vec::new();
+                render(&table, &mut buf).map_err(|e| Error::io(&blk_tag, e))?;
+                Str::from(String::from_utf8_lossy(&buf).into_owned())
+            }
+            None => print::fmt_cell(
+                &Entry::Obj(Value::Tab(table)),
+                &mut numfmt::Formatter::default(),
+            ),
+        };
+        cx.done_o(s)
+    })
+}
+
 fn to_str_help() -> HelpMessage {
     HelpMessage {
         desc: "convert the input into a string".into(),
@@ -857,6 +1558,81 @@ fn to_str_intrinsic(mut blk: Block) -> Result<Step> {
     })
 }
 
+// ------ To Table -------------------------------------------------------------
+fn to_table_help() -> HelpMessage {
+    HelpMessage {
+        desc: "convert a list or a map into a table
+a list becomes a single column table, with the header `value`
+a map becomes a table of its entries, with the headers `key` and `value`
+headers can be specified, one for each column"
+            .into(),
+        params: vec![HelpParameter::Optional("headers..".into())],
+        examples: vec![
+            HelpExample {
+                desc: "a table of numbers",
+                code: "List 1 2 3 | to-table",
+            },
+            HelpExample {
+                desc: "a table of words, under the header 'word'",
+                code: "\\ 'the quick brown fox' | split | to-table word",
+            },
+            HelpExample {
+                desc: "a table of file names and sizes",
+                code: "ls | to-map name size | to-table name size",
+            },
+        ],
+        ..HelpMessage::new("to-table")
+    }
+}
+
+fn to_table_intrinsic(mut blk: Block) -> Result<Step> {
+    let defaults = match blk.in_ty() {
+        Ty::List(_) => &["value"][..],
+        Ty::Map(..) => &["key", "value"][..],
+        x => return Err(Error::wrong_op_input_type(x, blk.op_tag())),
+    };
+    blk.assert_output(Ty::Tab);
+
+    let mut headers = Vec::with_capacity(defaults.len());
+    let mut surplus = Vec::new();
+    for i in 0..blk.args_len() {
+        let arg = blk.next_arg()?;
+        if i < defaults.len() {
+            headers.push(arg.supplied(Ty::Nil)?.returns(Ty::Str)?.concrete()?);
+        } else {
+            surplus.push(arg.tag().clone());
+        }
+    }
+    // there is a header name for each column, any more are an error
+    if !surplus.is_empty() {
+        return Err(Error::unused_args(surplus.iter()));
+    }
+
+    blk.eval_o(move |input, cx| {
+        let mut header = Vec::with_capacity(defaults.len());
+        for (i, default) in defaults.iter().enumerate() {
+            let h: Str = match headers.get(i) {
+                Some(h) => h.resolve(|| Value::Nil, &cx)?.try_into()?,
+                None => Str::from(*default),
+            };
+            header.push(o(h));
+        }
+
+        let rows = match input {
+            Value::Map(map) => map
+                .iter()
+                .map(|(k, v)| vec![Entry::from(k.clone()), Entry::from(v.clone())])
+                .collect::<Vec<_>>(),
+            x => List::try_from(x)?
+                .iter()
+                .map(|x| vec![Entry::from(x.clone())])
+                .collect(),
+        };
+        let rows = once(header).chain(rows).collect::<Vec<_>>();
+        cx.done_o(Table::from(::table::Table::from(rows)))
+    })
+}
+
 // ------ Tuple ----------------------------------------------------------------
 fn tuple_help() -> HelpMessage {
     variadic_help(
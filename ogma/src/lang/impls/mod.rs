@@ -1,5 +1,7 @@
 mod intrinsics;
 
+pub use intrinsics::{parse_table, write_as};
+
 use crate::prelude::*;
 use ast::{Location, Tag};
 use eng::{Block, Step};
@@ -34,6 +36,7 @@ pub enum OperationCategory {
     Morphism,
     Pipeline,
     Diagnostics,
+    Time,
     UserDefined,
 }
 
@@ -48,6 +51,7 @@ impl fmt::Display for OperationCategory {
             OperationCategory::Io => write!(f, "io"),
             OperationCategory::Pipeline => write!(f, "pipeline"),
             OperationCategory::Diagnostics => write!(f, "diagnostics"),
+            OperationCategory::Time => write!(f, "time"),
             OperationCategory::UserDefined => write!(f, "user-defined"),
         }
     }
@@ -66,9 +70,13 @@ struct Keys {
 }
 
 impl Keys {
-    /// Get the [`Impl`] keyed on `ty`. If none are present, tries to get an impl keyed on `None`.
+    /// Get the [`Impl`] keyed on `ty`. If none are present, tries to get an impl keyed on the
+    /// generic type ([`Type::generic`]), and then an impl keyed on `None`.
     fn get_impl(&self, ty: &Type) -> Option<&Impl> {
-        self.tys.get(ty).or(self.agnostic.as_ref())
+        self.tys
+            .get(ty)
+            .or_else(|| ty.generic().and_then(|g| self.tys.get(&g)))
+            .or(self.agnostic.as_ref())
     }
 
     fn iter(&self) -> impl Iterator<Item = (Option<&Type>, &Impl)> {
@@ -11,4 +11,4 @@ pub(crate) mod types;
 pub use defs::{construct_def_table, process_definition, recognise_definition, Definitions};
 pub use impls::ImplEntry;
 pub use syntax::{ast, parse};
-pub use types::{AsType, OgmaData, Table, Type, Value};
+pub use types::{AsType, DateTime, Duration, List, Map, OgmaData, Table, Type, Value};
@@ -5,9 +5,11 @@ use ::libs::{
     divvy::Str,
     parking_lot::{const_rwlock, RwLock},
 };
+use ::rust_decimal::Decimal;
+use ::time::OffsetDateTime;
 use ast::Location;
 use lang::help::HelpMessage;
-use std::{convert::TryFrom, fmt, hash, ops, sync::Arc};
+use std::{borrow::Cow, convert::TryFrom, fmt, hash, ops, sync::Arc};
 use table::Entry;
 
 // ###### TABLE ################################################################
@@ -55,6 +57,132 @@ impl Drop for Table {
     }
 }
 
+// ###### DATE TIME ############################################################
+/// A point in time, with a UTC offset.
+///
+/// Date-times are ordered by the instant they represent, irrespective of the offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DateTime(pub OffsetDateTime);
+
+impl DateTime {
+    /// The current date-time, in UTC.
+    pub fn now() -> Self {
+        DateTime(OffsetDateTime::now_utc())
+    }
+}
+
+impl From<std::time::SystemTime> for DateTime {
+    fn from(t: std::time::SystemTime) -> Self {
+        DateTime(OffsetDateTime::from(t))
+    }
+}
+
+impl ops::Deref for DateTime {
+    type Target = OffsetDateTime;
+    fn deref(&self) -> &OffsetDateTime {
+        &self.0
+    }
+}
+
+/// Formats as `2021-03-04 13:45:00`, dropping the time if it is midnight, and suffixing the
+/// offset if it is not UTC.
+impl fmt::Display for DateTime {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ::time::macros::format_description as fd;
+
+        let dt = self.0;
+        let date = dt.format(fd!("[year]-[month]-[day]"));
+        let time = (dt.time() != ::time::Time::MIDNIGHT)
+            .then(|| dt.format(fd!(" [hour]:[minute]:[second]")))
+            .transpose();
+        let offset = (!dt.offset().is_utc())
+            .then(|| dt.format(fd!(" [offset_hour sign:mandatory]:[offset_minute]")))
+            .transpose();
+
+        match (date, time, offset) {
+            (Ok(date), Ok(time), Ok(offset)) => write!(
+                f,
+                "{}{}{}",
+                date,
+                time.unwrap_or_default(),
+                offset.unwrap_or_default()
+            ),
+            _ => Err(fmt::Error),
+        }
+    }
+}
+
+// ###### DURATION #############################################################
+/// A signed span of time.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Duration(pub ::time::Duration);
+
+impl From<std::time::Duration> for Duration {
+    fn from(d: std::time::Duration) -> Self {
+        Duration(::time::Duration::new(
+            d.as_secs().min(i64::MAX as u64) as i64,
+            d.subsec_nanos() as i32,
+        ))
+    }
+}
+
+impl ops::Deref for Duration {
+    type Target = ::time::Duration;
+    fn deref(&self) -> &::time::Duration {
+        &self.0
+    }
+}
+
+/// Formats as `1d 2h 30min 4.5s`, spans less than a second are formatted with the largest
+/// sub-second unit, such as `250ms`.
+impl fmt::Display for Duration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let d = self.0;
+        if d.is_zero() {
+            return write!(f, "0s");
+        }
+        if d.is_negative() {
+            write!(f, "-")?;
+        }
+        let d = d.abs();
+
+        // trailing zeros are trimmed off the fractional part
+        let frac = |x: f64| {
+            let s = format!("{:.3}", x);
+            s.trim_end_matches('0').trim_end_matches('.').to_string()
+        };
+
+        if d < ::time::Duration::SECOND {
+            let nanos = d.subsec_nanoseconds() as f64;
+            return if nanos >= 1e6 {
+                write!(f, "{}ms", frac(nanos / 1e6))
+            } else if nanos >= 1e3 {
+                write!(f, "{}us", frac(nanos / 1e3))
+            } else {
+                write!(f, "{}ns", nanos)
+            };
+        }
+
+        let secs = d.whole_seconds();
+        let mut parts = Vec::with_capacity(4);
+        for (unit, x) in [
+            ("d", secs / 86_400),
+            ("h", secs % 86_400 / 3_600),
+            ("min", secs % 3_600 / 60),
+        ] {
+            if x > 0 {
+                parts.push(format!("{}{}", x, unit));
+            }
+        }
+        let s = (secs % 60) as f64 + d.subsec_nanoseconds() as f64 / 1e9;
+        if s > 0.0 {
+            parts.push(format!("{}s", frac(s)));
+        }
+
+        write!(f, "{}", parts.join(" "))
+    }
+}
+
 // ###### TYPE #################################################################
 /// Ogma data types.
 ///
@@ -67,14 +195,29 @@ pub enum Type {
     Bool,
     /// A numeric type.
     Num,
+    /// An exact decimal type.
+    Dec,
     /// A string type.
     Str,
+    /// A date-time type.
+    Date,
+    /// A duration type.
+    Dur,
     /// A table type.
     Tab,
     /// A table row type.
     TabRow,
+    /// A homogeneous list type, with the element type.
+    List(Arc<Type>),
+    /// A key/value map type, with the key and value types.
+    Map(Arc<Type>, Arc<Type>),
     /// An ogma-defined type.
     Def(Arc<TypeDef>),
+    /// A type parameter, such as the `T` in `List<T>`.
+    ///
+    /// Only used to key implementations of generic types, no value has this type and it can not
+    /// be defined by users.
+    Param(char),
 }
 
 impl Type {
@@ -85,10 +228,28 @@ impl Type {
             Nil => "nothing value".into(),
             Bool => "boolean value\ntrue | false".into(),
             Num => "number value\n100 | -1 | 3.14 | -1.23e-5".into(),
+            Dec => "exact decimal value\nconstruct with `to-dec`, ie `\\ '0.10' | to-dec`".into(),
             Str => "string value".into(),
+            Date => {
+                "date-time value\nconstruct with `to-date` or `now`, ie `\\ '2021-03-04' | to-date`"
+                    .into()
+            }
+            Dur => "duration value\nconstruct with `dur`, ie `dur 3d` or `dur 1h30min`".into(),
             Tab => "table value".into(),
             TabRow => "table row".into(),
+            Type::List(ty) => format!(
+                "list of `{}` values\nconstruct with `List` or `to-list`, ie `List a b c`",
+                Tuple::tyname(ty)
+            )
+            .into(),
+            Type::Map(k, v) => format!(
+                "map of `{}` keys to `{}` values\nconstruct from a table with `to-map`, ie `ls | to-map name size`",
+                Tuple::tyname(k),
+                Tuple::tyname(v)
+            )
+            .into(),
             Def(tydef) => return tydef.help(),
+            Param(p) => format!("type parameter `{}`, standing in for any type", p).into(),
         };
 
         HelpMessage {
@@ -104,6 +265,37 @@ impl Type {
             _ => false,
         }
     }
+
+    /// The generic type which an input of this type also matches implementations keyed on.
+    ///
+    /// Any `List<..>` input matches implementations keyed on `List<T>` ([`List::generic_ty`]), and
+    /// any `Map<..>` input those keyed on `Map<K,V>` ([`Map::generic_ty`]).
+    pub fn generic(&self) -> Option<Type> {
+        match self {
+            Type::List(_) => Some(List::generic_ty()),
+            Type::Map(..) => Some(Map::generic_ty()),
+            _ => None,
+        }
+    }
+
+    /// Returns if this type is a generic key, such as `List<T>` or `Map<K,V>`.
+    pub fn is_generic(&self) -> bool {
+        self.generic().as_ref() == Some(self)
+    }
+
+    /// Parse a type name, including tuple names such as `U_Num-Str_` and the generic names
+    /// `List<T>` and `Map<K,V>`.
+    pub fn parse_name(name: &str, tys: &Types) -> Option<Type> {
+        if name.starts_with("U_") {
+            Tuple::parse_name(name, tys)
+        } else if name.starts_with("List<") {
+            List::parse_name(name, tys)
+        } else if name.starts_with("Map<") {
+            Map::parse_name(name, tys)
+        } else {
+            tys.get_using_str(name).cloned()
+        }
+    }
 }
 
 impl fmt::Display for Type {
@@ -112,10 +304,16 @@ impl fmt::Display for Type {
             Type::Nil => "Nil",
             Type::Bool => "Bool",
             Type::Num => "Number",
+            Type::Dec => "Decimal",
             Type::Str => "String",
+            Type::Date => "Date",
+            Type::Dur => "Duration",
             Type::Tab => "Table",
             Type::TabRow => "TableRow",
+            Type::List(x) => return write!(f, "List<{}>", Tuple::tyname(x)),
+            Type::Map(k, v) => return write!(f, "Map<{},{}>", Tuple::tyname(k), Tuple::tyname(v)),
             Type::Def(x) => x.name.str(),
+            Type::Param(p) => return write!(f, "{}", p),
         };
 
         write!(f, "{}", ident)
@@ -139,12 +337,22 @@ pub enum Value {
     Bool(bool),
     /// Number value.
     Num(Number),
+    /// Exact decimal value.
+    Dec(Decimal),
     /// String value.
     Str(Str),
+    /// Date-time value.
+    Date(DateTime),
+    /// Duration value.
+    Dur(Duration),
     /// Table value.
     Tab(Table),
     /// Table row.
     TabRow(TableRow),
+    /// List value.
+    List(List),
+    /// Map value.
+    Map(Map),
     /// User defined data value.
     Ogma(OgmaData),
 }
@@ -157,9 +365,14 @@ impl Value {
             Nil => Type::Nil,
             Bool(_) => Type::Bool,
             Num(_) => Type::Num,
+            Dec(_) => Type::Dec,
             Str(_) => Type::Str,
+            Date(_) => Type::Date,
+            Dur(_) => Type::Dur,
             Tab(_) => Type::Tab,
             TabRow(_) => Type::TabRow,
+            Value::List(x) => x.ty(),
+            Value::Map(x) => x.ty(),
             Ogma(x) => Type::Def(Arc::clone(x.ty())),
         }
     }
@@ -230,7 +443,7 @@ impl From<$type> for Value {
     };
 }
 
-prim_type_impls!(bool=>Bool, Number=>Num, Str=>Str, Table=>Tab, TableRow=>TabRow);
+prim_type_impls!(bool=>Bool, Number=>Num, Decimal=>Dec, Str=>Str, DateTime=>Date, Duration=>Dur, Table=>Tab, TableRow=>TabRow);
 
 // ----- additionals that don't fit pattern -----
 impl AsType for () {
@@ -273,6 +486,62 @@ impl From<OgmaData> for Value {
         Value::Ogma(x)
     }
 }
+/// Lists are generic over their element type, so the ogma type is the generic `List<T>`, see
+/// [`List::generic_ty`].
+impl AsType for List {
+    fn as_type() -> Type {
+        List::generic_ty()
+    }
+}
+impl TryFrom<Value> for List {
+    type Error = Error;
+    fn try_from(v: Value) -> Result<Self> {
+        match v {
+            Value::List(x) => Ok(x),
+            x => Err(Error {
+                cat: err::Category::Evaluation,
+                desc: format!(
+                    "converting value into `List` failed, value has type `{}`",
+                    x.ty()
+                ),
+                ..Error::default()
+            }),
+        }
+    }
+}
+impl From<List> for Value {
+    fn from(x: List) -> Self {
+        Value::List(x)
+    }
+}
+/// Maps are generic over their key and value types, so the ogma type is the generic `Map<K,V>`,
+/// see [`Map::generic_ty`].
+impl AsType for Map {
+    fn as_type() -> Type {
+        Map::generic_ty()
+    }
+}
+impl TryFrom<Value> for Map {
+    type Error = Error;
+    fn try_from(v: Value) -> Result<Self> {
+        match v {
+            Value::Map(x) => Ok(x),
+            x => Err(Error {
+                cat: err::Category::Evaluation,
+                desc: format!(
+                    "converting value into `Map` failed, value has type `{}`",
+                    x.ty()
+                ),
+                ..Error::default()
+            }),
+        }
+    }
+}
+impl From<Map> for Value {
+    fn from(x: Map) -> Self {
+        Value::Map(x)
+    }
+}
 // ----- end -----
 
 // ###### USER TYPES ###########################################################
@@ -291,7 +560,10 @@ impl Types {
         map.insert(Str::from("Nil"), Type::Nil);
         map.insert(Str::from("Bool"), Type::Bool);
         map.insert(Str::from("Num"), Type::Num);
+        map.insert(Str::from("Dec"), Type::Dec);
         map.insert(Str::from("Str"), Type::Str);
+        map.insert(Str::from("Date"), Type::Date);
+        map.insert(Str::from("Dur"), Type::Dur);
         map.insert(Str::from("Table"), Type::Tab);
         map.insert(Str::from("TableRow"), Type::TabRow);
 
@@ -657,9 +929,14 @@ fn add_kserd_fields(
             Value::Nil => Kserd::new_unit(),
             Value::Bool(b) => Kserd::new_bool(b),
             Value::Num(n) => Kserd::new(KValue::Num(n)),
+            Value::Dec(d) => Kserd::new_string(d.to_string()),
             Value::Str(s) => Kserd::new_string(s.to_string()),
+            Value::Date(d) => Kserd::new_string(d.to_string()),
+            Value::Dur(d) => Kserd::new_string(d.to_string()),
             Value::Tab(_) => Kserd::new_str("<table>"),
             Value::TabRow(_) => Kserd::new_str("<table row>"),
+            Value::List(_) => Kserd::new_str("<list>"),
+            Value::Map(_) => Kserd::new_str("<map>"),
             Value::Ogma(data) => data.into_kserd()?,
         };
         map.insert(field.name.to_string().into(), value);
@@ -800,19 +1077,265 @@ impl PartialEq for TableRow {
     }
 }
 
+// ###### LIST #################################################################
+/// A homogeneous list of values.
+///
+/// The element type is kept alongside the items, so that empty lists are still typed.
+#[derive(Debug, PartialEq, Clone)]
+pub struct List {
+    ty: Arc<Type>,
+    items: Arc<Vec<Value>>,
+}
+
+impl List {
+    /// Build a new [`List`], where each item is of the element type `ty`.
+    pub fn new(ty: Type, items: Vec<Value>) -> Self {
+        debug_assert!(
+            items.iter().all(|x| x.ty() == ty),
+            "all list items are expected to be of type `{}`",
+            ty
+        );
+        List {
+            ty: Arc::new(ty),
+            items: Arc::new(items),
+        }
+    }
+
+    /// The element type.
+    pub fn elem_ty(&self) -> &Type {
+        &self.ty
+    }
+
+    /// The list type, `List<T>`.
+    pub fn ty(&self) -> Type {
+        Type::List(Arc::clone(&self.ty))
+    }
+
+    /// Make a mutable reference to the items by clone-on-write.
+    pub fn make_mut(&mut self) -> &mut Vec<Value> {
+        Arc::make_mut(&mut self.items)
+    }
+
+    /// The generic list type, `List<T>`.
+    ///
+    /// List commands are keyed on this type, with the element type available from the block's
+    /// input type.
+    pub fn generic_ty() -> Type {
+        Type::List(Arc::new(Type::Param('T')))
+    }
+
+    /// Parse a list type name, such as `List<Str>`.
+    pub fn parse_name(name: &str, tys: &Types) -> Option<Type> {
+        let inner = name.strip_prefix("List<")?.strip_suffix('>')?;
+        Type::parse_name(inner, tys).map(|ty| Type::List(Arc::new(ty)))
+    }
+}
+
+impl ops::Deref for List {
+    type Target = [Value];
+    fn deref(&self) -> &[Value] {
+        self.items.as_slice()
+    }
+}
+
+// ###### MAP ##################################################################
+/// A key/value map, where the keys and values have a single type each.
+///
+/// Entries keep their insertion order, and the keys are hashed for constant time lookups.
+#[derive(Debug, Clone)]
+pub struct Map {
+    kty: Arc<Type>,
+    vty: Arc<Type>,
+    entries: Arc<MapEntries>,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MapEntries {
+    items: Vec<(Value, Value)>,
+    index: HashMap<MapKey, usize>,
+}
+
+/// A hashable map key.
+///
+/// Numbers are compared on their `f64` representation, so `1` and `1.0` are the same key.
+/// `-0.0` and `0.0` are the same key, as are all NaNs.
+#[derive(Debug, Clone)]
+struct MapKey(Value);
+
+impl MapKey {
+    /// The normalised bits of a number key, so that equal keys hash the same.
+    fn num_bits(n: &Number) -> u64 {
+        let x = n.as_f64();
+        if x.is_nan() {
+            f64::NAN.to_bits()
+        } else {
+            (x + 0.0).to_bits() // -0.0 + 0.0 = 0.0
+        }
+    }
+}
+
+impl PartialEq for MapKey {
+    fn eq(&self, other: &Self) -> bool {
+        match (&self.0, &other.0) {
+            (Value::Num(a), Value::Num(b)) => Self::num_bits(a) == Self::num_bits(b),
+            (a, b) => a == b,
+        }
+    }
+}
+
+impl Eq for MapKey {}
+
+impl hash::Hash for MapKey {
+    fn hash<H: hash::Hasher>(&self, state: &mut H) {
+        match &self.0 {
+            Value::Bool(x) => x.hash(state),
+            Value::Num(x) => Self::num_bits(x).hash(state),
+            Value::Dec(x) => x.hash(state),
+            Value::Str(x) => x.hash(state),
+            Value::Date(x) => x.hash(state),
+            Value::Dur(x) => x.hash(state),
+            // only key types are hashed, see `Map::is_key_ty`
+            _ => (),
+        }
+    }
+}
+
+impl Map {
+    /// Build an empty [`Map`], with keys of type `kty` and values of type `vty`.
+    pub fn new(kty: Type, vty: Type) -> Self {
+        debug_assert!(
+            Self::is_key_ty(&kty),
+            "`{}` cannot be used as a map key",
+            kty
+        );
+        Map {
+            kty: Arc::new(kty),
+            vty: Arc::new(vty),
+            entries: Default::default(),
+        }
+    }
+
+    /// Returns if values of this type can be used as keys.
+    pub fn is_key_ty(ty: &Type) -> bool {
+        use Type::*;
+        matches!(ty, Bool | Num | Dec | Str | Date | Dur)
+    }
+
+    /// The key type.
+    pub fn key_ty(&self) -> &Type {
+        &self.kty
+    }
+
+    /// The value type.
+    pub fn value_ty(&self) -> &Type {
+        &self.vty
+    }
+
+    /// The map type, `Map<K,V>`.
+    pub fn ty(&self) -> Type {
+        Type::Map(Arc::clone(&self.kty), Arc::clone(&self.vty))
+    }
+
+    /// The number of entries.
+    pub fn len(&self) -> usize {
+        self.entries.items.len()
+    }
+
+    /// Returns `true` if there are no entries.
+    pub fn is_empty(&self) -> bool {
+        self.entries.items.is_empty()
+    }
+
+    /// Get the value under `key`.
+    pub fn get(&self, key: &Value) -> Option<&Value> {
+        self.entries
+            .index
+            .get(&MapKey(key.clone()))
+            .map(|&i| &self.entries.items[i].1)
+    }
+
+    /// Returns `true` if the map has an entry under `key`.
+    pub fn contains_key(&self, key: &Value) -> bool {
+        self.entries.index.contains_key(&MapKey(key.clone()))
+    }
+
+    /// Insert an entry, cloning the entries if they are shared.
+    ///
+    /// If the key already exists, the value is replaced in place and the old value is returned.
+    pub fn insert(&mut self, key: Value, value: Value) -> Option<Value> {
+        debug_assert!(key.ty() == *self.kty && value.ty() == *self.vty);
+        let MapEntries { items, index } = Arc::make_mut(&mut self.entries);
+        match index.get(&MapKey(key.clone())) {
+            Some(&i) => Some(std::mem::replace(&mut items[i].1, value)),
+            None => {
+                index.insert(MapKey(key.clone()), items.len());
+                items.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Iterate over the entries, in insertion order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&Value, &Value)> {
+        self.entries.items.iter().map(|(k, v)| (k, v))
+    }
+
+    /// The generic map type, `Map<K,V>`.
+    ///
+    /// Map commands are keyed on this type, with the key and value types available from the
+    /// block's input type.
+    pub fn generic_ty() -> Type {
+        Type::Map(Arc::new(Type::Param('K')), Arc::new(Type::Param('V')))
+    }
+
+    /// Parse a map type name, such as `Map<Str,Num>`.
+    pub fn parse_name(name: &str, tys: &Types) -> Option<Type> {
+        let inner = name.strip_prefix("Map<")?.strip_suffix('>')?;
+        // split on the first comma that is not within a nested type
+        let mut depth = 0;
+        let (i, _) = inner.char_indices().find(|&(_, c)| {
+            match c {
+                '<' => depth += 1,
+                '>' => depth -= 1,
+                _ => (),
+            }
+            c == ',' && depth == 0
+        })?;
+        let k = Type::parse_name(&inner[..i], tys).filter(Self::is_key_ty)?;
+        let v = Type::parse_name(&inner[i + 1..], tys)?;
+        Some(Type::Map(Arc::new(k), Arc::new(v)))
+    }
+}
+
+/// Maps are equal if they have the same types and entries, irrespective of the entry order.
+impl PartialEq for Map {
+    fn eq(&self, other: &Self) -> bool {
+        self.kty == other.kty
+            && self.vty == other.vty
+            && self.len() == other.len()
+            && self.iter().all(|(k, v)| other.get(k) == Some(v))
+    }
+}
+
 // ###### TUPLES ###############################################################
 // tuples are not defined in the types, instead they are defined in the HIR phase.
 pub struct Tuple;
 impl Tuple {
-    fn tyname(ty: &Type) -> &str {
+    fn tyname(ty: &Type) -> Cow<str> {
         match ty {
-            Type::Nil => "Nil",
-            Type::Bool => "Bool",
-            Type::Num => "Num",
-            Type::Str => "Str",
-            Type::Tab => "Table",
-            Type::TabRow => "TableRow",
-            Type::Def(x) => x.name.str(),
+            Type::Nil => "Nil".into(),
+            Type::Bool => "Bool".into(),
+            Type::Num => "Num".into(),
+            Type::Dec => "Dec".into(),
+            Type::Str => "Str".into(),
+            Type::Date => "Date".into(),
+            Type::Dur => "Dur".into(),
+            Type::Tab => "Table".into(),
+            Type::TabRow => "TableRow".into(),
+            Type::List(x) => format!("List<{}>", Self::tyname(x)).into(),
+            Type::Map(k, v) => format!("Map<{},{}>", Self::tyname(k), Self::tyname(v)).into(),
+            Type::Def(x) => x.name.str().into(),
+            Type::Param(p) => p.to_string().into(),
         }
     }
 
@@ -914,7 +1437,7 @@ impl Tuple {
                 }
                 Some(Type::Def(Arc::new(Self::ty(args))))
             }
-            Split::Ty(t) => tys.get_using_str(t).cloned(),
+            Split::Ty(t) => Type::parse_name(t, tys),
         }
     }
 }
@@ -1115,6 +1638,137 @@ mod tests {
         )
     }
 
+    #[test]
+    fn list_parse_name_testing() {
+        let defs = crate::prelude::Definitions::new();
+        let tys = defs.types();
+        let list = |t| Type::List(Arc::new(t));
+
+        assert_eq!(super::List::parse_name("List<Str>", tys), Some(list(Str)));
+        assert_eq!(
+            super::List::parse_name("List<List<Num>>", tys),
+            Some(list(list(Num)))
+        );
+        assert_eq!(super::List::parse_name("List<Foo>", tys), None);
+        assert_eq!(super::List::parse_name("List<Str", tys), None);
+        assert_eq!(
+            Tuple::parse_name("U_List<Str>-Num_", tys),
+            Some(Def(Arc::new(Tuple::ty(vec![list(Str), Num]))))
+        );
+
+        assert_eq!(&list(list(Str)).to_string(), "List<List<Str>>");
+    }
+
+    #[test]
+    fn list_generic_testing() {
+        let list = |t| Type::List(Arc::new(t));
+        let generic = super::List::generic_ty();
+
+        assert_eq!(&generic.to_string(), "List<T>");
+        assert!(generic.is_generic());
+        assert!(!list(Str).is_generic());
+        assert_eq!(list(Str).generic(), Some(generic.clone()));
+        assert_eq!(list(list(Num)).generic(), Some(generic.clone()));
+        assert_eq!(Str.generic(), None);
+        assert_eq!(<super::List as AsType>::as_type(), generic);
+
+        // a user defined `T` is not the type parameter
+        let defs = &mut crate::prelude::Definitions::new();
+        crate::lang::process_definition("def-ty T { x:Num }", Default::default(), None, defs)
+            .unwrap();
+        let t = Type::parse_name("List<T>", defs.types()).unwrap();
+        assert_eq!(&t.to_string(), "List<T>");
+        assert_ne!(t, generic);
+        assert!(!t.is_generic());
+        assert_eq!(t.generic(), Some(generic));
+    }
+
+    #[test]
+    fn map_generic_testing() {
+        let map = |k, v| Type::Map(Arc::new(k), Arc::new(v));
+        let generic = super::Map::generic_ty();
+
+        assert_eq!(&generic.to_string(), "Map<K,V>");
+        assert!(generic.is_generic());
+        assert!(!map(Str, Num).is_generic());
+        assert_eq!(map(Str, Num).generic(), Some(generic.clone()));
+        assert_eq!(map(Num, map(Str, Bool)).generic(), Some(generic.clone()));
+        assert_ne!(super::List::generic_ty(), generic);
+        assert_eq!(<super::Map as AsType>::as_type(), generic);
+    }
+
+    #[test]
+    fn map_parse_name_testing() {
+        let defs = crate::prelude::Definitions::new();
+        let tys = defs.types();
+        let map = |k, v| Type::Map(Arc::new(k), Arc::new(v));
+        let list = |t| Type::List(Arc::new(t));
+
+        assert_eq!(
+            super::Map::parse_name("Map<Str,Num>", tys),
+            Some(map(Str, Num))
+        );
+        assert_eq!(
+            super::Map::parse_name("Map<Num,Map<Str,List<Bool>>>", tys),
+            Some(map(Num, map(Str, list(Bool))))
+        );
+        assert_eq!(
+            super::List::parse_name("List<Map<Str,Num>>", tys),
+            Some(list(map(Str, Num)))
+        );
+        assert_eq!(super::Map::parse_name("Map<Str>", tys), None);
+        assert_eq!(super::Map::parse_name("Map<Str,Foo>", tys), None);
+        // keys must be hashable
+        assert_eq!(super::Map::parse_name("Map<Table,Num>", tys), None);
+        assert_eq!(super::Map::parse_name("Map<List<Str>,Num>", tys), None);
+
+        assert_eq!(&map(Str, list(Num)).to_string(), "Map<Str,List<Num>>");
+    }
+
+    #[test]
+    fn map_testing() {
+        let mut map = super::Map::new(Num, Str);
+        let s = |x: &str| Value::Str(x.into());
+
+        assert_eq!(map.insert(Value::Num(1.into()), s("one")), None);
+        assert_eq!(map.insert(Value::Num(2.into()), s("two")), None);
+        assert_eq!(map.insert(Value::Num(1.0.into()), s("uno")), Some(s("one")));
+        assert_eq!(map.insert(Value::Num((-0.0).into()), s("zero")), None);
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&Value::Num(1.0.into())), Some(&s("uno")));
+        assert_eq!(map.get(&Value::Num(0.into())), Some(&s("zero")));
+        assert!(!map.contains_key(&Value::Num(3.into())));
+
+        // replaced entries keep their position
+        let keys = map.iter().map(|(k, _)| k.clone()).collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                Value::Num(1.into()),
+                Value::Num(2.into()),
+                Value::Num((-0.0).into())
+            ]
+        );
+
+        // maps are copy-on-write
+        let mut b = map.clone();
+        b.insert(Value::Num(3.into()), s("three"));
+        assert_eq!(map.len(), 3);
+        assert_eq!(b.len(), 4);
+        assert_ne!(map, b);
+
+        // NaNs are all the same key
+        let nan = || Value::Num(f64::NAN.into());
+        assert_eq!(map.insert(nan(), s("nan")), None);
+        assert_eq!(
+            map.insert(Value::Num((-f64::NAN).into()), s("NaN")),
+            Some(s("nan"))
+        );
+        assert_eq!(map.get(&nan()), Some(&s("NaN")));
+        assert_eq!(map.len(), 4);
+    }
+
     #[test]
     fn split_parse_testing() {
         use Split::*;
@@ -23,7 +23,10 @@ mod prelude {
         defs::Definitions,
         impls::{Implementation, Implementations},
         syntax::ast::{self, Tag},
-        types::{self, AsType, OgmaData, Table, TableRow, Tuple, Type, Value},
+        types::{
+            self, AsType, DateTime, Duration, List, Map, OgmaData, Table, TableRow, Tuple, Type,
+            Value,
+        },
     };
     pub(crate) use super::output::print;
     pub(crate) use super::rt;
@@ -4,4 +4,7 @@ pub(crate) mod print;
 
 // Public API
 
-pub use print::{print_error, print_ogma_data, print_table};
+pub use print::{
+    print_error, print_list, print_map, print_ogma_data, print_table, write_html_table,
+    write_md_table,
+};
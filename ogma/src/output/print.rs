@@ -78,6 +78,109 @@ pub fn print_table(table: &Table, wtr: &mut dyn Write) -> io::Result<()> {
     writeln!(wtr, "{}", out)
 }
 
+/// Write the [`Table`](::table::DataTable) as a GitHub flavoured Markdown pipe table.
+///
+/// The first row is used as the header. Pipes are escaped and line breaks are replaced with
+/// `<br>` so that each row stays on a single line.
+pub fn write_md_table(table: &Table, wtr: &mut dyn Write) -> io::Result<()> {
+    fn write_row<'a, I>(wtr: &mut dyn Write, row: I, fmtr: &mut Formatter) -> io::Result<()>
+    where
+        I: Iterator<Item = &'a Entry<Value>>,
+    {
+        write!(wtr, "|")?;
+        for e in row {
+            let s = fmt_export_cell(e, fmtr);
+            let s = s
+                .replace('|', "\\|")
+                .replace("\r\n", "<br>")
+                .replace('\n', "<br>");
+            write!(wtr, " {} |", s)?;
+        }
+        writeln!(wtr)
+    }
+
+    let mut header_fmtr = Formatter::new();
+    let mut cell_fmtr = Formatter::default();
+
+    let mut rows = table.rows();
+    if let Some(header) = rows.next() {
+        write_row(wtr, header, &mut header_fmtr)?;
+        write!(wtr, "|")?;
+        for _ in 0..table.cols_len() {
+            write!(wtr, " --- |")?;
+        }
+        writeln!(wtr)?;
+    }
+
+    for row in rows {
+        write_row(wtr, row, &mut cell_fmtr)?;
+    }
+
+    Ok(())
+}
+
+/// Write the [`Table`](::table::DataTable) as a HTML `<table>` element.
+///
+/// The first row is used as the header. Cell contents are HTML escaped.
+pub fn write_html_table(table: &Table, wtr: &mut dyn Write) -> io::Result<()> {
+    fn write_row<'a, I>(
+        wtr: &mut dyn Write,
+        row: I,
+        fmtr: &mut Formatter,
+        tag: &str,
+    ) -> io::Result<()>
+    where
+        I: Iterator<Item = &'a Entry<Value>>,
+    {
+        write!(wtr, "<tr>")?;
+        for e in row {
+            let s = fmt_export_cell(e, fmtr);
+            write!(wtr, "<{}>{}</{}>", tag, html_escape(&s), tag)?;
+        }
+        writeln!(wtr, "</tr>")
+    }
+
+    let mut header_fmtr = Formatter::new();
+    let mut cell_fmtr = Formatter::default();
+
+    writeln!(wtr, "<table>")?;
+    let mut rows = table.rows();
+    if let Some(header) = rows.next() {
+        writeln!(wtr, "<thead>")?;
+        write_row(wtr, header, &mut header_fmtr, "th")?;
+        writeln!(wtr, "</thead>")?;
+    }
+    writeln!(wtr, "<tbody>")?;
+    for row in rows {
+        write_row(wtr, row, &mut cell_fmtr, "td")?;
+    }
+    writeln!(wtr, "</tbody>")?;
+    writeln!(wtr, "</table>")
+}
+
+/// Formats a cell for exporting, where a nil entry is left blank.
+fn fmt_export_cell(entry: &Entry<Value>, numfmtr: &mut Formatter) -> Str {
+    match entry {
+        Entry::Nil | Entry::Obj(Value::Nil) => Str::default(),
+        e => fmt_cell(e, numfmtr),
+    }
+}
+
+fn html_escape(s: &str) -> String {
+    let mut x = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => x.push_str("&amp;"),
+            '<' => x.push_str("&lt;"),
+            '>' => x.push_str("&gt;"),
+            '"' => x.push_str("&quot;"),
+            '\'' => x.push_str("&#39;"),
+            c => x.push(c),
+        }
+    }
+    x
+}
+
 /// Prints the processing error. Uses colour and assumes printing is to the terminal.
 /// Use [`Error::print`] if this is not the case.
 pub fn print_error(err: &Error, wtr: &mut dyn Write) -> io::Result<()> {
@@ -126,13 +229,37 @@ pub fn fmt_cell(entry: &Entry<Value>, numfmtr: &mut Formatter) -> Str {
         Nil | Obj(V::Nil) => Str::from("-"),
         Num(n) | Obj(V::Num(n)) => Str::new(numfmtr.fmt(n.as_f64())),
         Obj(V::Bool(b)) => b.to_string().into(),
+        Obj(V::Dec(d)) => d.to_string().into(),
         Obj(V::Str(s)) => s.clone(),
+        Obj(V::Date(d)) => d.to_string().into(),
+        Obj(V::Dur(d)) => d.to_string().into(),
         Obj(V::Tab(t)) => format!("<table [{},{}]>", t.rows_len(), t.cols_len()).into(),
         Obj(V::TabRow(_)) => Str::from("<table row>"), // this should not be reachable.
+        Obj(V::List(x)) => print_list(x, numfmtr).into(),
+        Obj(V::Map(x)) => print_map(x, numfmtr).into(),
         Obj(V::Ogma(x)) => print_ogma_data(x.clone()).into(),
     }
 }
 
+/// Prints a list as `[a, b, c]`, with each item formatted as a cell.
+pub fn print_list(list: &types::List, numfmtr: &mut Formatter) -> String {
+    let items = list
+        .iter()
+        .map(|x| fmt_cell(&Entry::from(x.clone()), numfmtr).to_string())
+        .collect::<Vec<_>>();
+    format!("[{}]", items.join(", "))
+}
+
+/// Prints a map as `{k: v, k: v}`, with each key and value formatted as a cell.
+pub fn print_map(map: &types::Map, numfmtr: &mut Formatter) -> String {
+    let mut f = |x: &Value| fmt_cell(&Entry::from(x.clone()), numfmtr).to_string();
+    let items = map
+        .iter()
+        .map(|(k, v)| format!("{}: {}", f(k), f(v)))
+        .collect::<Vec<_>>();
+    format!("{{{}}}", items.join(", "))
+}
+
 /// Serialises `OgmaData` into [`::kserd::Kserd`] and the formats it into a string.
 pub fn print_ogma_data(data: types::OgmaData) -> String {
     use kserd::ToKserd;
@@ -243,6 +370,51 @@ mod tests {
 │ 28.0           ┆ -   ┆ -   ┆ ...           ┆ -    ┆ -   ┆ -   │
 │ 29.0           ┆ -   ┆ -   ┆ ...           ┆ -    ┆ -   ┆ -   │
 └────────────────┴─────┴─────┴───────────────┴──────┴─────┴─────┘
+",
+        );
+    }
+
+    fn export_table() -> Table {
+        use Entry::*;
+
+        let mut table = Table::default();
+        let t = table.make_mut();
+        t.add_row(once(o("a")).chain(once(o("b | c"))));
+        t.add_row(once(Num(2.5.into())).chain(once(o("<foo> & \"bar\"\nzog"))));
+        t.add_row(once(Nil).chain(once(o("x"))));
+        table
+    }
+
+    #[test]
+    fn md_table_writing() {
+        let mut wtr = Vec::new();
+        write_md_table(&export_table(), &mut wtr).unwrap();
+        check_table(
+            &wtr,
+            r#"| a | b \| c |
+| --- | --- |
+| 2.5 | <foo> & "bar"<br>zog |
+|  | x |
+"#,
+        );
+    }
+
+    #[test]
+    fn html_table_writing() {
+        let mut wtr = Vec::new();
+        write_html_table(&export_table(), &mut wtr).unwrap();
+        check_table(
+            &wtr,
+            "<table>
+<thead>
+<tr><th>a</th><th>b | c</th></tr>
+</thead>
+<tbody>
+<tr><td>2.5</td><td>&lt;foo&gt; &amp; &quot;bar&quot;
+zog</td></tr>
+<tr><td></td><td>x</td></tr>
+</tbody>
+</table>
 ",
         );
     }
@@ -0,0 +1,360 @@
+//! A persistent, on-disk cache of parsed tables.
+//!
+//! Tables are stored in a compact binary format under a cache directory, one file per path and
+//! read options. Each file records the source file's [`Stamp`] (size, modification time, and
+//! inode), and a cached table is only loaded if it still matches the source file.
+use super::fscache::Stamp;
+use crate::prelude::*;
+use ::kserd::Number;
+use libs::parking_lot::RwLock;
+use std::{
+    convert::TryFrom,
+    hash::{Hash, Hasher},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// The file header, the trailing byte is the format version.
+const MAGIC: &[u8] = b"OGMATAB\x02";
+
+static DIR: RwLock<Option<PathBuf>> = libs::parking_lot::const_rwlock(None);
+
+/// Set the directory to persist parsed tables in. `None` disables the on-disk cache (default).
+///
+/// The directory is created if it does not exist.
+pub fn set_cache_dir(dir: Option<PathBuf>) -> io::Result<()> {
+    if let Some(dir) = &dir {
+        std::fs::create_dir_all(dir)?;
+    }
+    *DIR.write() = dir;
+    Ok(())
+}
+
+/// The directory tables are persisted in, if enabled.
+pub fn cache_dir() -> Option<PathBuf> {
+    DIR.read().clone()
+}
+
+/// The cache file for a `path` and `opts` within `dir`.
+fn cache_file(dir: &Path, path: &Path, opts: &str) -> PathBuf {
+    let mut hasher = libs::rustc_hash::FxHasher::default();
+    path.hash(&mut hasher);
+    opts.hash(&mut hasher);
+    dir.join(format!("{:016x}.tab", hasher.finish()))
+}
+
+/// Load the table read from `path` with `opts` from the cache directory, if it exists and
+/// matches the source file's current `stamp`.
+pub fn load(path: &Path, opts: &str, stamp: Stamp) -> Option<Table> {
+    let dir = cache_dir()?;
+    load_from(&cache_file(&dir, path, opts), path, opts, stamp)
+        .ok()
+        .flatten()
+}
+
+/// Store the table read from `path` with `opts` into the cache directory, if enabled.
+///
+/// Tables containing values which can not be persisted (such as user defined types) are not
+/// stored.
+pub fn store(path: &Path, opts: &str, stamp: Stamp, table: &Table) -> io::Result<()> {
+    let dir = match cache_dir() {
+        Some(d) => d,
+        None => return Ok(()),
+    };
+    let file = cache_file(&dir, path, opts);
+
+    // write to a temporary file and rename to avoid partially written cache files
+    let tmp = file.with_extension("tmp");
+    let r = std::fs::File::create(&tmp)
+        .map(BufWriter::new)
+        .and_then(|mut wtr| {
+            write_header(&mut wtr, path, opts, stamp)?;
+            write_table(&mut wtr, table)?;
+            wtr.flush()
+        })
+        .and_then(|_| std::fs::rename(&tmp, &file));
+    if r.is_err() {
+        std::fs::remove_file(&tmp).ok();
+    }
+    r
+}
+
+fn load_from(file: &Path, path: &Path, opts: &str, stamp: Stamp) -> io::Result<Option<Table>> {
+    let mut rdr = BufReader::new(std::fs::File::open(file)?);
+
+    let mut magic = [0u8; MAGIC.len()];
+    rdr.read_exact(&mut magic)?;
+    if magic != MAGIC
+        || read_str(&mut rdr)? != path_to_str(path)
+        || read_str(&mut rdr)? != opts
+        || read_stamp(&mut rdr)? != stamp
+    {
+        return Ok(None);
+    }
+
+    read_table(&mut rdr).map(Some)
+}
+
+fn path_to_str(path: &Path) -> String {
+    path.display().to_string()
+}
+
+// ------ Encoding -------------------------------------------------------------
+// Integers are little endian, strings are length prefixed UTF-8.
+// Entries are tagged with a single byte.
+const NIL: u8 = 0;
+const NUM: u8 = 1;
+const OBJ_NIL: u8 = 2;
+const OBJ_BOOL: u8 = 3;
+const OBJ_NUM: u8 = 4;
+const OBJ_STR: u8 = 5;
+const OBJ_TAB: u8 = 6;
+
+fn write_header<W: Write>(wtr: &mut W, path: &Path, opts: &str, stamp: Stamp) -> io::Result<()> {
+    wtr.write_all(MAGIC)?;
+    write_str(wtr, &path_to_str(path))?;
+    write_str(wtr, opts)?;
+    write_u64(wtr, stamp.size)?;
+    write_u64(wtr, stamp.secs)?;
+    wtr.write_all(&stamp.nanos.to_le_bytes())?;
+    write_u64(wtr, stamp.inode)
+}
+
+fn read_stamp<R: Read>(rdr: &mut R) -> io::Result<Stamp> {
+    let size = read_u64(rdr)?;
+    let secs = read_u64(rdr)?;
+    let mut nanos = [0; 4];
+    rdr.read_exact(&mut nanos)?;
+    let inode = read_u64(rdr)?;
+    Ok(Stamp {
+        size,
+        secs,
+        nanos: u32::from_le_bytes(nanos),
+        inode,
+    })
+}
+
+fn write_table<W: Write>(wtr: &mut W, table: &Table) -> io::Result<()> {
+    write_u64(wtr, table.rows_len() as u64)?;
+    write_u64(wtr, table.cols_len() as u64)?;
+    wtr.write_all(&[table.header as u8])?;
+    for row in table.rows() {
+        for entry in row {
+            write_entry(wtr, entry)?;
+        }
+    }
+    Ok(())
+}
+
+fn read_table<R: Read>(rdr: &mut R) -> io::Result<Table> {
+    let rows = read_u64(rdr)? as usize;
+    let cols = read_u64(rdr)? as usize;
+    let mut header = [0];
+    rdr.read_exact(&mut header)?;
+
+    let rows = (0..rows)
+        .map(|_| {
+            (0..cols)
+                .map(|_| read_entry(rdr))
+                .collect::<io::Result<Vec<_>>>()
+        })
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut table = ::table::Table::from(rows);
+    table.header = header[0] != 0;
+
+    Ok(Table::from(table))
+}
+
+fn write_entry<W: Write>(wtr: &mut W, entry: &Entry<Value>) -> io::Result<()> {
+    match entry {
+        Entry::Nil => wtr.write_all(&[NIL]),
+        Entry::Num(n) => {
+            wtr.write_all(&[NUM])?;
+            write_str(wtr, &n.to_string())
+        }
+        Entry::Obj(Value::Nil) => wtr.write_all(&[OBJ_NIL]),
+        Entry::Obj(Value::Bool(b)) => wtr.write_all(&[OBJ_BOOL, *b as u8]),
+        Entry::Obj(Value::Num(n)) => {
+            wtr.write_all(&[OBJ_NUM])?;
+            write_str(wtr, &n.to_string())
+        }
+        Entry::Obj(Value::Str(s)) => {
+            wtr.write_all(&[OBJ_STR])?;
+            write_str(wtr, s)
+        }
+        Entry::Obj(Value::Tab(t)) => {
+            wtr.write_all(&[OBJ_TAB])?;
+            write_table(wtr, t)
+        }
+        Entry::Obj(x) => Err(invalid_data(format!(
+            "values of type `{}` can not be cached",
+            x.ty()
+        ))),
+    }
+}
+
+fn read_entry<R: Read>(rdr: &mut R) -> io::Result<Entry<Value>> {
+    let mut tag = [0];
+    rdr.read_exact(&mut tag)?;
+    Ok(match tag[0] {
+        NIL => Entry::Nil,
+        NUM => Entry::Num(read_num(rdr)?),
+        OBJ_NIL => Entry::Obj(Value::Nil),
+        OBJ_BOOL => {
+            let mut b = [0];
+            rdr.read_exact(&mut b)?;
+            Entry::Obj(Value::Bool(b[0] != 0))
+        }
+        OBJ_NUM => Entry::Obj(Value::Num(read_num(rdr)?)),
+        OBJ_STR => Entry::Obj(Value::Str(Str::from(read_str(rdr)?))),
+        OBJ_TAB => Entry::Obj(Value::Tab(read_table(rdr)?)),
+        x => return Err(invalid_data(format!("unknown entry tag {}", x))),
+    })
+}
+
+fn read_num<R: Read>(rdr: &mut R) -> io::Result<Number> {
+    let s = read_str(rdr)?;
+    Number::from_str(&s).map_err(|_| invalid_data(format!("invalid number `{}`", s)))
+}
+
+fn write_u64<W: Write>(wtr: &mut W, x: u64) -> io::Result<()> {
+    wtr.write_all(&x.to_le_bytes())
+}
+
+fn read_u64<R: Read>(rdr: &mut R) -> io::Result<u64> {
+    let mut buf = [0; 8];
+    rdr.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn write_str<W: Write>(wtr: &mut W, s: &str) -> io::Result<()> {
+    let len = u32::try_from(s.len()).map_err(|_| invalid_data("string is too long to cache"))?;
+    wtr.write_all(&len.to_le_bytes())?;
+    wtr.write_all(s.as_bytes())
+}
+
+fn read_str<R: Read>(rdr: &mut R) -> io::Result<String> {
+    let mut len = [0; 4];
+    rdr.read_exact(&mut len)?;
+    let len = u64::from(u32::from_le_bytes(len));
+    // the length is untrusted, so the buffer only grows as the data is read
+    let mut buf = Vec::new();
+    rdr.by_ref().take(len).read_to_end(&mut buf)?;
+    if buf.len() as u64 != len {
+        return Err(io::ErrorKind::UnexpectedEof.into());
+    }
+    String::from_utf8(buf).map_err(invalid_data)
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn o(s: &str) -> Entry<Value> {
+        Entry::Obj(Value::Str(Str::new(s)))
+    }
+
+    fn table() -> Table {
+        let nested = Table::from(::table::Table::from(vec![
+            vec![o("x")],
+            vec![Entry::Num(1.into())],
+        ]));
+        let mut t = ::table::Table::from(vec![
+            vec![o("a"), o("b"), o("c"), o("d")],
+            vec![
+                Entry::Num(3.into()),
+                Entry::Num((-2.5).into()),
+                Entry::Nil,
+                Entry::Obj(Value::Bool(true)),
+            ],
+            vec![
+                o("ünïcödé, \"quoted\"\n"),
+                Entry::Obj(Value::Num(1.5e6.into())),
+                Entry::Obj(Value::Nil),
+                Entry::Obj(Value::Tab(nested)),
+            ],
+        ]);
+        t.header = true;
+        Table::from(t)
+    }
+
+    #[test]
+    fn table_encoding_round_trip() {
+        let t = table();
+        let mut buf = Vec::new();
+        write_table(&mut buf, &t).unwrap();
+        let x = read_table(&mut buf.as_slice()).unwrap();
+        assert_eq!(x, t);
+        assert!(x.header);
+
+        // truncated data fails
+        assert!(read_table(&mut &buf[..buf.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn oversized_str_length_fails() {
+        let mut buf = u32::MAX.to_le_bytes().to_vec();
+        buf.extend_from_slice(b"abc");
+        let e = read_str(&mut buf.as_slice()).unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn validated_on_load() {
+        let dir = std::env::temp_dir().join("ogma-diskcache-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let src = dir.join("src.csv");
+        std::fs::write(&src, "a,b\n1,2").unwrap();
+        let stamp = Stamp::of(&src).unwrap();
+        let file = cache_file(&dir, &src, "");
+
+        let t = table();
+        let mut wtr = BufWriter::new(std::fs::File::create(&file).unwrap());
+        write_header(&mut wtr, &src, "", stamp).unwrap();
+        write_table(&mut wtr, &t).unwrap();
+        drop(wtr);
+
+        assert_eq!(load_from(&file, &src, "", stamp).unwrap(), Some(t));
+        // differing options
+        assert_eq!(load_from(&file, &src, "delim=;", stamp).unwrap(), None);
+        // differing path
+        assert_eq!(
+            load_from(&file, Path::new("foo.csv"), "", stamp).unwrap(),
+            None
+        );
+        // source file changed
+        let changed = Stamp {
+            size: stamp.size + 1,
+            ..stamp
+        };
+        assert_eq!(load_from(&file, &src, "", changed).unwrap(), None);
+        let changed = Stamp {
+            nanos: stamp.nanos.wrapping_add(1),
+            ..stamp
+        };
+        assert_eq!(load_from(&file, &src, "", changed).unwrap(), None);
+        let changed = Stamp {
+            inode: stamp.inode.wrapping_add(1),
+            ..stamp
+        };
+        assert_eq!(load_from(&file, &src, "", changed).unwrap(), None);
+
+        // cache file names are distinct
+        assert_ne!(
+            cache_file(&dir, &src, ""),
+            cache_file(&dir, &src, "delim=;")
+        );
+        assert_ne!(
+            cache_file(&dir, &src, ""),
+            cache_file(&dir, Path::new("foo.csv"), "")
+        );
+    }
+}
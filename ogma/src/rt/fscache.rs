@@ -1,13 +1,16 @@
 //! A filesystem cache system.
 
+use super::diskcache;
 use crate::prelude::*;
 use crate::Mutex;
 use ::libs::parking_lot::Once;
 use std::{
     convert::TryFrom,
     error,
+    mem::size_of,
     path::{Path, PathBuf},
     result::Result,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
     time::{Duration, Instant},
 };
 
@@ -18,61 +21,273 @@ use std::{
 
 const LIFESPAN: Duration = Duration::from_secs(60 * 3); // 3 minutes
 const DEBOUNCE: Duration = Duration::from_millis(5); // 5ms fs watching
+const DEFAULT_LIMIT: usize = 1 << 30; // 1 GiB
 static INIT: Once = Once::new();
 
-#[derive(PartialEq, Eq, Hash)]
-struct Key(String, Type);
-type Value = (Instant, types::Value);
-type Map = HashMap<Key, Value>;
+/// A file's metadata, used to check that a cached value is still fresh.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Stamp {
+    pub size: u64,
+    pub secs: u64,
+    pub nanos: u32,
+    /// The inode on unix systems (`0` otherwise), which changes if a file is replaced.
+    pub inode: u64,
+}
+
+impl Stamp {
+    /// Read the stamp of the file at `path`.
+    pub fn of(path: &Path) -> std::io::Result<Self> {
+        let meta = std::fs::metadata(path)?;
+        let mtime = meta
+            .modified()?
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+
+        #[cfg(unix)]
+        let inode = std::os::unix::fs::MetadataExt::ino(&meta);
+        #[cfg(not(unix))]
+        let inode = 0;
+
+        Ok(Stamp {
+            size: meta.len(),
+            secs: mtime.as_secs(),
+            nanos: mtime.subsec_nanos(),
+            inode,
+        })
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct Key {
+    path: String,
+    ty: Type,
+    /// Discriminates values read along the same path with differing options.
+    opts: String,
+}
+
+struct Value {
+    used: Instant,
+    stamp: Stamp,
+    value: types::Value,
+    /// Approximate size of `value`, in bytes.
+    bytes: usize,
+}
+
+#[derive(Default)]
+struct Map {
+    map: HashMap<Key, Value>,
+    /// Total approximate size of the cached values, in bytes.
+    bytes: usize,
+}
 
 /// A filesystem cache which caches the [`Value`] along a _path_.
 ///
 /// This is useful for caching large datasets, usually [`Table`]s which have a non-trival
 /// parsing cost.
-#[derive(Default)]
+///
+/// The cache is bounded by an approximate memory limit, once exceeded the least recently used
+/// values are evicted.
 pub struct FsCache {
     map: Mutex<Map>,
+    limit: AtomicUsize,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+/// A snapshot of the [`FsCache`] statistics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Number of cached values.
+    pub entries: usize,
+    /// Approximate size of the cached values, in bytes.
+    pub bytes: usize,
+    /// The memory limit, in bytes.
+    pub limit: usize,
+    /// Number of lookups which returned a cached value.
+    pub hits: u64,
+    /// Number of lookups which did not return a cached value.
+    pub misses: u64,
 }
 
 impl Key {
-    fn from<T: AsType>(path: &Path) -> Self {
-        Key(path_to_str(path), T::as_type())
+    fn from<T: AsType>(path: &Path, opts: &str) -> Self {
+        Key {
+            path: path_to_str(path),
+            ty: T::as_type(),
+            opts: opts.to_string(),
+        }
+    }
+}
+
+impl Map {
+    fn insert(&mut self, key: Key, value: Value) {
+        self.bytes += value.bytes;
+        if let Some(old) = self.map.insert(key, value) {
+            self.bytes -= old.bytes;
+        }
+    }
+
+    fn remove(&mut self, key: &Key) {
+        if let Some(old) = self.map.remove(key) {
+            self.bytes -= old.bytes;
+        }
+    }
+
+    fn retain<F: FnMut(&Key, &Value) -> bool>(&mut self, mut f: F) {
+        let bytes = &mut self.bytes;
+        self.map.retain(|k, v| {
+            let keep = f(k, v);
+            if !keep {
+                *bytes -= v.bytes;
+            }
+            keep
+        });
+    }
+
+    /// Evict the least recently used values until the total size is within `limit`.
+    fn evict_lru(&mut self, limit: usize) {
+        while self.bytes > limit {
+            let lru = self
+                .map
+                .iter()
+                .min_by_key(|(_, v)| v.used)
+                .map(|(k, _)| k.clone());
+            match lru {
+                Some(k) => self.remove(&k),
+                None => break,
+            }
+        }
+    }
+}
+
+impl Default for FsCache {
+    fn default() -> Self {
+        FsCache {
+            map: Default::default(),
+            limit: AtomicUsize::new(DEFAULT_LIMIT),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
     }
 }
 
 impl FsCache {
     /// Fetch a value from the cache along `path`.
     ///
+    /// `opts` is a key describing how the value was read (for instance the delimiter used to parse
+    /// a table). Use an empty string if there are no options.
+    ///
+    /// The cached value is only returned if the file's metadata (see [`Stamp`]) is unchanged since
+    /// it was inserted, otherwise it is evicted.
     /// If a cache hit occurs it resets the use timer to keep this item alive.
-    pub fn get<T>(&self, path: &Path) -> Option<T>
+    pub fn get<T>(&self, path: &Path, opts: &str) -> Option<T>
     where
         T: AsType,
         T: TryFrom<types::Value>,
     {
-        std::thread::sleep(DEBOUNCE * 5); // we sleep for the 5 x debounce duration to give time for the fs watcher to catch up
+        let key = Key::from::<T>(path, opts);
+        let stamp = Stamp::of(path).ok();
 
-        let key = Key::from::<T>(path);
-        let mut lock = self.map.lock();
-        lock.get_mut(&key)
-            .map(|x| {
-                x.0 = Instant::now();
-                x.1.clone()
+        let value = {
+            let mut lock = self.map.lock();
+            let fresh = match (lock.map.get(&key), stamp) {
+                (Some(x), Some(stamp)) => x.stamp == stamp,
+                _ => false,
+            };
+            if !fresh {
+                lock.remove(&key);
+            }
+
+            lock.map.get_mut(&key).map(|x| {
+                x.used = Instant::now();
+                x.value.clone()
             })
-            .and_then(|x| T::try_from(x).ok())
+        };
+
+        // fallback to the persistent cache for tables
+        // the lock is not held while loading to not block other lookups on disk IO
+        let value = value.or_else(|| {
+            let stamp = stamp?;
+            let v = (key.ty == Type::Tab)
+                .then(|| diskcache::load(path, opts, stamp))
+                .flatten()
+                .map(types::Value::Tab)?;
+            let mut lock = self.map.lock();
+            lock.insert(key, Value::new(stamp, v.clone()));
+            lock.evict_lru(self.limit());
+            Some(v)
+        });
+
+        let counter = if value.is_some() {
+            &self.hits
+        } else {
+            &self.misses
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+
+        value.and_then(|x| T::try_from(x).ok())
     }
 
-    /// Insert the value `T` along path.
-    pub fn insert<T>(&self, path: &Path, value: T)
+    /// Insert the value `T` along path, keyed with `opts`.
+    ///
+    /// `stamp` is recorded to validate freshness, it must be taken (with [`Stamp::of`]) _before_
+    /// the file is read, so a write during the read leaves the cached value stale rather than
+    /// marking stale data as fresh. If the file's metadata could not be read (`None`), the value
+    /// is not cached.
+    /// Tables are also written to the persistent cache, if it is enabled.
+    /// Inserting may evict the least recently used values to stay within the memory limit, values
+    /// larger than the limit are not cached in memory.
+    pub fn insert<T>(&self, path: &Path, opts: &str, stamp: Option<Stamp>, value: T)
     where
         T: AsType,
         T: Into<types::Value>,
     {
-        let key = Key::from::<T>(path);
-        self.map.lock().insert(key, (Instant::now(), value.into()));
+        let stamp = match stamp {
+            Some(x) => x,
+            None => return,
+        };
+        let key = Key::from::<T>(path, opts);
+        let value = value.into();
+        if let types::Value::Tab(table) = &value {
+            diskcache::store(path, opts, stamp, table).ok(); // failing to persist is not an error
+        }
+        let value = Value::new(stamp, value);
+        let limit = self.limit();
+        let mut lock = self.map.lock();
+        if value.bytes > limit {
+            lock.remove(&key); // do not keep a stale value around
+        } else {
+            lock.insert(key, value);
+            lock.evict_lru(limit);
+        }
+    }
+
+    /// The approximate memory limit of cached values, in bytes.
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// Set the approximate memory limit of cached values, in bytes.
+    /// Least recently used values are evicted if the cache exceeds the new limit.
+    pub fn set_limit(&self, bytes: usize) {
+        self.limit.store(bytes, Ordering::Relaxed);
+        self.map.lock().evict_lru(bytes);
+    }
+
+    /// Get a snapshot of the cache statistics.
+    pub fn stats(&self) -> CacheStats {
+        let lock = self.map.lock();
+        CacheStats {
+            entries: lock.map.len(),
+            bytes: lock.bytes,
+            limit: self.limit(),
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
     }
 
     fn remove_expired(&self, age: Duration) {
-        self.map.lock().retain(|_, v| v.0.elapsed() < age);
+        self.map.lock().retain(|_, v| v.used.elapsed() < age);
     }
 
     fn remove_path_changes<I, P>(&self, paths: I)
@@ -82,11 +297,55 @@ impl FsCache {
     {
         let paths: HashSet<String> = paths.map(|p| path_to_str(p.as_ref())).collect();
         if !paths.is_empty() {
-            self.map.lock().retain(|k, _| !paths.contains(&k.0));
+            self.map.lock().retain(|k, _| !paths.contains(&k.path));
+        }
+    }
+}
+
+impl Value {
+    fn new(stamp: Stamp, value: types::Value) -> Self {
+        Value {
+            used: Instant::now(),
+            stamp,
+            bytes: approx_size(&value),
+            value,
         }
     }
 }
 
+/// Set the approximate memory limit of the global [`FSCACHE`], in bytes (default 1 GiB).
+pub fn set_cache_limit(bytes: usize) {
+    FSCACHE.set_limit(bytes)
+}
+
+/// Get a snapshot of the global [`FSCACHE`] statistics.
+pub fn cache_stats() -> CacheStats {
+    FSCACHE.stats()
+}
+
+/// The approximate heap and inline size of a value, in bytes.
+///
+/// Table rows count their whole table, since the cached row keeps it alive.
+fn approx_size(value: &types::Value) -> usize {
+    use types::Value::*;
+
+    let entry = |e: &Entry<types::Value>| match e {
+        Entry::Obj(v) => approx_size(v),
+        _ => size_of::<Entry<types::Value>>(),
+    };
+
+    size_of::<types::Value>()
+        + match value {
+            Nil | Bool(_) | Num(_) | Dec(_) | Date(_) | Dur(_) => 0,
+            Str(s) => s.len(),
+            Tab(t) => t.rows().flatten().map(entry).sum(),
+            TabRow(r) => approx_size(&Tab(r.table.clone())),
+            List(x) => x.iter().map(approx_size).sum(),
+            Map(x) => x.iter().map(|(k, v)| approx_size(k) + approx_size(v)).sum(),
+            Ogma(x) => x.data().iter().map(approx_size).sum(),
+        }
+}
+
 /// Ensures the [`FSCACHE`] is initialised.
 ///
 /// Calling this multiple times is safe, once initialised the initialised map will be kept
@@ -156,3 +415,105 @@ fn watch_fs(canon_root: PathBuf) -> Result<(), Box<dyn error::Error>> {
         FSCACHE.remove_path_changes(drain);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stamp_validation() {
+        let dir = std::env::temp_dir().join("ogma-fscache-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = &dir.join("stamp.txt");
+        std::fs::write(path, "hello").unwrap();
+
+        let cache = FsCache::default();
+        cache.insert(path, "", Stamp::of(path).ok(), Str::from("hello"));
+        assert_eq!(cache.get::<Str>(path, ""), Some(Str::from("hello")));
+        assert_eq!(cache.get::<Str>(path, "delim=;"), None);
+
+        // changing the file invalidates the cached value, without waiting on the watcher
+        std::fs::write(path, "hello world").unwrap();
+        assert_eq!(cache.get::<Str>(path, ""), None);
+        assert!(cache.map.lock().map.is_empty());
+
+        // a write after the stamp is taken leaves the value stale
+        let stamp = Stamp::of(path).ok();
+        std::fs::write(path, "hello there").unwrap();
+        cache.insert(path, "", stamp, Str::from("hello world"));
+        assert_eq!(cache.get::<Str>(path, ""), None);
+
+        // a missing file is never a hit
+        cache.insert(path, "", Stamp::of(path).ok(), Str::from("hello world"));
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(cache.get::<Str>(path, ""), None);
+
+        // values are not cached if the file can not be read
+        cache.insert(path, "", Stamp::of(path).ok(), Str::from("hello"));
+        assert!(cache.map.lock().map.is_empty());
+    }
+
+    #[test]
+    fn lru_eviction_and_stats() {
+        let dir = std::env::temp_dir().join("ogma-fscache-test");
+        std::fs::create_dir_all(&dir).unwrap();
+        let paths = ["lru-a.txt", "lru-b.txt", "lru-c.txt"].map(|p| dir.join(p));
+        for p in &paths {
+            std::fs::write(p, "x").unwrap();
+        }
+        let [a, b, c] = &paths;
+        let s = |n| Str::from("x".repeat(n));
+        let size = approx_size(&types::Value::Str(s(100)));
+
+        let cache = FsCache::default();
+        cache.set_limit(size * 2);
+        cache.insert(a, "", Stamp::of(a).ok(), s(100));
+        cache.insert(b, "", Stamp::of(b).ok(), s(100));
+        assert_eq!(cache.stats().entries, 2);
+        assert_eq!(cache.stats().bytes, size * 2);
+
+        // using `a` makes `b` the least recently used
+        assert!(cache.get::<Str>(a, "").is_some());
+        cache.insert(c, "", Stamp::of(c).ok(), s(100));
+        assert!(cache.get::<Str>(b, "").is_none());
+        assert!(cache.get::<Str>(a, "").is_some());
+        assert!(cache.get::<Str>(c, "").is_some());
+
+        // values larger than the limit are not cached
+        cache.insert(b, "", Stamp::of(b).ok(), s(size * 3));
+        assert!(cache.get::<Str>(b, "").is_none());
+
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                entries: 2,
+                bytes: size * 2,
+                limit: size * 2,
+                hits: 3,
+                misses: 2,
+            }
+        );
+
+        // lowering the limit evicts
+        cache.set_limit(size);
+        assert_eq!(cache.stats().entries, 1);
+        assert!(cache.get::<Str>(c, "").is_some());
+    }
+
+    #[test]
+    fn approx_sizes() {
+        let base = size_of::<types::Value>();
+        assert_eq!(approx_size(&types::Value::Nil), base);
+        assert_eq!(approx_size(&types::Value::Str("hello".into())), base + 5);
+
+        let t = Table::from(::table::Table::from(vec![
+            vec![Entry::Nil, Entry::Num(1.into())],
+            vec![Entry::Obj(types::Value::Str("hello".into())), Entry::Nil],
+        ]));
+        let entry = size_of::<Entry<types::Value>>();
+        assert_eq!(
+            approx_size(&types::Value::Tab(t)),
+            base + entry * 3 + base + 5
+        );
+    }
+}
@@ -1,7 +1,13 @@
 //! Runtime items.
 
 pub mod bat;
+pub(crate) mod diskcache;
 pub(crate) mod fscache;
 mod process;
+pub(crate) mod sandbox;
 
+pub use crate::lang::impls::{parse_table, write_as};
+pub use diskcache::{cache_dir, set_cache_dir};
+pub use fscache::{cache_stats, set_cache_limit, CacheStats};
 pub use process::{handle_help, process_expression};
+pub use sandbox::{Policy, Sandbox, Symlinks};
@@ -25,7 +25,12 @@ where
     let expr = lang::syntax::parse::expression(expr, loc, defs).map_err(|e| e.0)?;
     handle_help(&expr, defs)?;
     let eng::FullCompilation { eval_stack, env } = eng::compile(expr, defs, I::as_type())?;
-    let cx = eng::Context { root, wd, env };
+    let cx = eng::Context {
+        root,
+        wd,
+        env,
+        sandbox: defs.sandbox(),
+    };
     let output = eval_stack.eval(seed.into(), cx)?.0;
 
     Ok(output)
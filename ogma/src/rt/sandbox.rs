@@ -0,0 +1,379 @@
+//! Filesystem sandboxing.
+//!
+//! Intrinsics which touch the filesystem resolve user supplied paths through the [`Sandbox`] of
+//! the [`Definitions`](crate::lang::Definitions) (see `Definitions::set_sandbox`), which confines
+//! paths to the root directory and applies the symlink policy.
+//! Resolved paths within the root are returned _relative to the root_.
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+/// Which paths can be accessed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Policy {
+    /// Paths are confined to the root directory, and nothing can be written.
+    ReadOnly,
+    /// Paths are confined to the root directory (default).
+    RootConfined,
+    /// Any path can be read and written.
+    Unrestricted,
+}
+
+/// How paths through symlinks are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Symlinks {
+    /// Symlinks are followed, the _target_ is subject to the [`Policy`] (default).
+    Follow,
+    /// Paths through symlinks (below the root) are rejected.
+    Deny,
+}
+
+/// A filesystem access policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sandbox {
+    pub policy: Policy,
+    pub symlinks: Symlinks,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Sandbox::new()
+    }
+}
+
+impl Sandbox {
+    /// A root confined sandbox which follows symlinks.
+    pub const fn new() -> Self {
+        Sandbox {
+            policy: Policy::RootConfined,
+            symlinks: Symlinks::Follow,
+        }
+    }
+
+    /// Resolve an existing `path` for reading.
+    ///
+    /// `path` is relative to the working directory `wd`, which is relative to `root` (either can
+    /// be absolute). The returned path is relative to the root, or absolute if the policy allows
+    /// paths outside the root.
+    pub fn read(&self, root: &Path, wd: &Path, path: &Path) -> io::Result<PathBuf> {
+        let joined = root.join(wd).join(path);
+        let canon = joined
+            .canonicalize()
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", e, path.display())))?;
+        self.check_symlinks(root, &joined)?;
+        self.confine(root, canon)
+    }
+
+    /// Resolve `path` for writing. The file, and its parent directories, need not exist.
+    ///
+    /// Paths are treated the same as [`Sandbox::read`].
+    pub fn write(&self, root: &Path, wd: &Path, path: &Path) -> io::Result<PathBuf> {
+        if self.policy == Policy::ReadOnly {
+            return Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "cannot write, the filesystem is read-only",
+            ));
+        }
+
+        let joined = root.join(wd).join(path);
+
+        // canonicalise the longest existing ancestor, the remainder must be plain names
+        let mut existing = joined.as_path();
+        let mut rest = Vec::new();
+        while existing.symlink_metadata().is_err() {
+            match (existing.parent(), existing.components().next_back()) {
+                (Some(parent), Some(Component::Normal(name))) => {
+                    rest.push(name);
+                    existing = parent;
+                }
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid path: {}", path.display()),
+                    ))
+                }
+            }
+        }
+        let existing = if existing.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            existing
+        };
+
+        let mut canon = existing
+            .canonicalize()
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", e, path.display())))?;
+        canon.extend(rest.into_iter().rev());
+
+        self.check_symlinks(root, &joined)?;
+        self.confine(root, canon)
+    }
+
+    /// Resolve `path` for modifying the directory entry itself, such as removing or renaming it.
+    ///
+    /// Unlike [`Sandbox::write`], a symlink as the final component is not followed, so the link
+    /// itself is resolved. The final component must be a name (not `.` or `..`).
+    pub fn modify(&self, root: &Path, wd: &Path, path: &Path) -> io::Result<PathBuf> {
+        let p = wd.join(path);
+        match (p.parent(), p.components().next_back()) {
+            (Some(parent), Some(Component::Normal(name))) => self
+                .write(root, Path::new(""), parent)
+                .map(|parent| parent.join(name)),
+            _ => Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("invalid path: {}", path.display()),
+            )),
+        }
+    }
+
+    /// Returns if the canonical `path` can be accessed within the canonical `root`.
+    pub fn allows(&self, canon_root: &Path, canon_path: &Path) -> bool {
+        self.policy == Policy::Unrestricted || canon_path.starts_with(canon_root)
+    }
+
+    /// Returns if symlinks are followed.
+    pub fn follows_symlinks(&self) -> bool {
+        self.symlinks == Symlinks::Follow
+    }
+
+    fn confine(&self, root: &Path, canon: PathBuf) -> io::Result<PathBuf> {
+        let root = root
+            .canonicalize()
+            .unwrap_or_else(|_| std::path::PathBuf::from("."));
+
+        match canon.strip_prefix(&root) {
+            Ok(x) => Ok(x.to_path_buf()),
+            Err(_) if self.allows(&root, &canon) => Ok(canon),
+            Err(_) => Err(io::Error::new(
+                io::ErrorKind::PermissionDenied,
+                "cannot move above root directory",
+            )),
+        }
+    }
+
+    /// Checks each existing component of `joined` below the `root` is not a symlink, if symlinks
+    /// are denied.
+    fn check_symlinks(&self, root: &Path, joined: &Path) -> io::Result<()> {
+        if self.follows_symlinks() {
+            return Ok(());
+        }
+
+        let skip = if joined.starts_with(root) {
+            root.components().count()
+        } else {
+            0
+        };
+
+        let mut p = PathBuf::new();
+        for (i, c) in joined.components().enumerate() {
+            p.push(c);
+            let is_link = p
+                .symlink_metadata()
+                .map(|m| m.file_type().is_symlink())
+                .unwrap_or_default();
+            if i >= skip && is_link {
+                return Err(io::Error::new(
+                    io::ErrorKind::PermissionDenied,
+                    format!("cannot follow symlink: {}", p.display()),
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a root directory with `data/a.csv` and a sibling `outside` directory.
+    fn setup(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join("ogma-sandbox-test").join(name);
+        let root = dir.join("root");
+        std::fs::create_dir_all(root.join("data")).unwrap();
+        std::fs::create_dir_all(dir.join("outside")).unwrap();
+        std::fs::write(root.join("data/a.csv"), "a").unwrap();
+        std::fs::write(dir.join("outside/b.csv"), "b").unwrap();
+        root
+    }
+
+    fn sandbox(policy: Policy, symlinks: Symlinks) -> Sandbox {
+        Sandbox { policy, symlinks }
+    }
+
+    #[test]
+    fn reading_confined() {
+        let root = &setup("reading");
+        let sb = Sandbox::default();
+        let read = |p: &str| sb.read(root, Path::new(""), Path::new(p));
+
+        assert_eq!(read("data/a.csv").unwrap(), Path::new("data/a.csv"));
+        assert_eq!(read("data/../data/a.csv").unwrap(), Path::new("data/a.csv"));
+        assert_eq!(read("./data").unwrap(), Path::new("data"));
+        assert!(read("data/nope.csv").is_err());
+
+        // escaping with ..
+        let e = read("../outside/b.csv").unwrap_err();
+        assert_eq!(e.to_string(), "cannot move above root directory");
+        assert!(read("..").is_err());
+        assert!(read("data/../../outside").is_err());
+
+        // relative to the working directory
+        assert_eq!(
+            sb.read(root, Path::new("data"), Path::new("a.csv"))
+                .unwrap(),
+            Path::new("data/a.csv")
+        );
+        assert!(sb
+            .read(root, Path::new("data"), Path::new("../../outside"))
+            .is_err());
+
+        // escaping with absolute paths
+        let abs = root.join("../outside/b.csv");
+        assert!(read(&abs.to_string_lossy()).is_err());
+        let abs = root.join("data/a.csv");
+        assert_eq!(
+            read(&abs.to_string_lossy()).unwrap(),
+            Path::new("data/a.csv")
+        );
+    }
+
+    #[test]
+    fn writing_confined() {
+        let root = &setup("writing");
+        let sb = Sandbox::default();
+        let write = |p: &str| sb.write(root, Path::new(""), Path::new(p));
+
+        assert_eq!(write("data/a.csv").unwrap(), Path::new("data/a.csv"));
+        assert_eq!(write("new.csv").unwrap(), Path::new("new.csv"));
+        assert_eq!(write("new/dir/x.csv").unwrap(), Path::new("new/dir/x.csv"));
+        assert_eq!(write("data/../y.csv").unwrap(), Path::new("y.csv"));
+
+        assert!(write("../outside/c.csv").is_err());
+        assert!(write("../c.csv").is_err());
+        assert!(write("new/../../c.csv").is_err());
+        assert!(write(&root.join("../c.csv").to_string_lossy()).is_err());
+        assert!(write("/c.csv").is_err());
+    }
+
+    #[test]
+    fn modifying_confined() {
+        let root = &setup("modifying");
+        let sb = Sandbox::default();
+        let modify = |p: &str| sb.modify(root, Path::new(""), Path::new(p));
+
+        assert_eq!(modify("data/a.csv").unwrap(), Path::new("data/a.csv"));
+        assert_eq!(modify("data").unwrap(), Path::new("data"));
+        assert_eq!(modify("new.csv").unwrap(), Path::new("new.csv"));
+        assert_eq!(
+            sb.modify(root, Path::new("data"), Path::new("a.csv"))
+                .unwrap(),
+            Path::new("data/a.csv")
+        );
+
+        // the root itself can not be modified
+        assert!(modify(".").is_err());
+        assert!(modify("data/..").is_err());
+        assert!(modify("..").is_err());
+        assert!(modify("../outside/b.csv").is_err());
+        assert!(modify(&root.join("../outside/b.csv").to_string_lossy()).is_err());
+
+        let sb = sandbox(Policy::ReadOnly, Symlinks::Follow);
+        assert!(sb
+            .modify(root, Path::new(""), Path::new("data/a.csv"))
+            .is_err());
+    }
+
+    #[test]
+    fn read_only() {
+        let root = &setup("read-only");
+        let sb = sandbox(Policy::ReadOnly, Symlinks::Follow);
+
+        assert!(sb
+            .read(root, Path::new(""), Path::new("data/a.csv"))
+            .is_ok());
+        assert!(sb
+            .read(root, Path::new(""), Path::new("../outside/b.csv"))
+            .is_err());
+        let e = sb
+            .write(root, Path::new(""), Path::new("data/a.csv"))
+            .unwrap_err();
+        assert_eq!(e.to_string(), "cannot write, the filesystem is read-only");
+    }
+
+    #[test]
+    fn unrestricted() {
+        let root = &setup("unrestricted");
+        let sb = sandbox(Policy::Unrestricted, Symlinks::Follow);
+        let outside = root.join("../outside/b.csv").canonicalize().unwrap();
+
+        assert_eq!(
+            sb.read(root, Path::new(""), Path::new("data/a.csv"))
+                .unwrap(),
+            Path::new("data/a.csv")
+        );
+        assert_eq!(
+            sb.read(root, Path::new(""), Path::new("../outside/b.csv"))
+                .unwrap(),
+            outside
+        );
+        assert_eq!(
+            sb.write(root, Path::new(""), Path::new("../outside/b.csv"))
+                .unwrap(),
+            outside
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let root = &setup("symlinks");
+        symlink("data", root.join("inside-link")).ok();
+        symlink("../outside", root.join("outside-link")).ok();
+
+        let sb = Sandbox::default();
+        // links within the root are followed
+        assert_eq!(
+            sb.read(root, Path::new(""), Path::new("inside-link/a.csv"))
+                .unwrap(),
+            Path::new("data/a.csv")
+        );
+        // links can not be used to escape
+        assert!(sb
+            .read(root, Path::new(""), Path::new("outside-link/b.csv"))
+            .is_err());
+        assert!(sb
+            .write(root, Path::new(""), Path::new("outside-link/c.csv"))
+            .is_err());
+        // modifying resolves the link itself, not its target
+        assert_eq!(
+            sb.modify(root, Path::new(""), Path::new("outside-link"))
+                .unwrap(),
+            Path::new("outside-link")
+        );
+
+        let sb = sandbox(Policy::RootConfined, Symlinks::Deny);
+        let e = sb
+            .read(root, Path::new(""), Path::new("inside-link/a.csv"))
+            .unwrap_err()
+            .to_string();
+        assert!(e.starts_with("cannot follow symlink: "), "{}", e);
+        assert!(sb
+            .write(root, Path::new(""), Path::new("inside-link/c.csv"))
+            .is_err());
+        assert!(sb
+            .read(root, Path::new(""), Path::new("data/a.csv"))
+            .is_ok());
+
+        // a symlinked root is fine
+        let linked_root = root.join("../linked-root");
+        symlink("root", &linked_root).ok();
+        assert!(sb
+            .read(&linked_root, Path::new(""), Path::new("data/a.csv"))
+            .is_ok());
+    }
+}
@@ -38,6 +38,47 @@ fn add_help_msg() {
         &x,
         r#"Help: `+`
 --> shell:0
+ | ---- Input Type: Date ----
+ | add a number of days, or a duration, to a date
+ | fractional days add hours, minutes, and seconds
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => + args..
+ | 
+ | Examples:
+ |  the date a week from now
+ |  => now | + 7
+ | 
+ |  add 12 hours to a date
+ |  => \ '2021-03-04' | to-date | + 0.5
+ | 
+ |  add a duration to a date
+ |  => now | + {dur 1h30min}
+ | 
+ | ---- Input Type: Decimal ----
+ | add decimals together
+ | number arguments are converted to decimals
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => + args..
+ | 
+ | Examples:
+ |  add 0.2 to 0.1, exactly
+ |  => \ 0.1 | to-dec | + 0.2
+ | 
+ | ---- Input Type: Duration ----
+ | add durations together
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => + args..
+ | 
+ | Examples:
+ |  add 30 minutes to an hour
+ |  => dur 1h | + {dur 30min}
+ | 
  | ---- Input Type: Number ----
  | add numbers together
  | -variadic-: more than one argument can be specified
@@ -356,6 +397,19 @@ fn div_help_msg() {
         &x,
         "Help: `/`
 --> shell:0
+ | ---- Input Type: Decimal ----
+ | divide a decimal by the arguments
+ | number arguments are converted to decimals
+ | dividing by 0 is an error
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => / args..
+ | 
+ | Examples:
+ |  split 10 four ways
+ |  => \\ 10 | to-dec | / 4
+ | 
  | ---- Input Type: Number ----
  | divide arguments against one another
  | dividing by 0 will result in infinity (∞)
@@ -470,6 +524,18 @@ fn mul_help_msg() {
         &x,
         "Help: `*`
 --> shell:0
+ | ---- Input Type: Decimal ----
+ | multiply decimals together
+ | number arguments are converted to decimals
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => * args..
+ | 
+ | Examples:
+ |  the price of three items
+ |  => \\ '19.99' | to-dec | * 3
+ | 
  | ---- Input Type: Number ----
  | multiply arguments together
  | -variadic-: more than one argument can be specified
@@ -534,6 +600,58 @@ fn root_testing() {
     assert_eq!(x, Ok(Value::Bool(true)));
 }
 
+// ------ Round ----------------------------------------------------------------
+#[test]
+fn round_help_msg() {
+    let src = "round --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `round`
+--> shell:0
+ | ---- Input Type: Decimal ----
+ | round a decimal to a number of decimal places, defaults to 0 places
+ | midpoints are rounded away from zero (half-up), use --even for banker's rounding
+ | 
+ | Usage:
+ |  => round [places]
+ | 
+ | Flags:
+ |  --even: round midpoints to the nearest even digit
+ | 
+ | Examples:
+ |  round to 2 places, half-up
+ |  => \ '2.345' | to-dec | round 2
+ | 
+ |  round to 2 places, using banker's rounding
+ |  => \ '2.345' | to-dec | round --even 2
+"#
+    );
+}
+
+#[test]
+fn round_testing() {
+    let defs = &Definitions::new();
+    let s = |x: &str| Ok(Value::Str(x.into()));
+
+    let x = process_w_nil("\\ '2.345' | to-dec | round 2 | to-str", defs);
+    assert_eq!(x, s("2.35"));
+    let x = process_w_nil("\\ '2.345' | to-dec | round --even 2 | to-str", defs);
+    assert_eq!(x, s("2.34"));
+    let x = process_w_nil("\\ '2.5' | to-dec | round | to-str", defs);
+    assert_eq!(x, s("3"));
+    let x = process_w_nil("\\ '2.5' | to-dec | round --even | to-str", defs);
+    assert_eq!(x, s("2"));
+    let x = process_w_nil("\\ '-2.5' | to-dec | round | to-str", defs);
+    assert_eq!(x, s("-3"));
+
+    let x = process_w_nil("\\ '2.5' | to-dec | round 1.5", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("expecting a whole number of decimal places, found `1.5`"));
+}
+
 // ------ Sub ------------------------------------------------------------------
 #[test]
 fn sub_help_msg() {
@@ -543,6 +661,48 @@ fn sub_help_msg() {
         &x,
         "Help: `-`
 --> shell:0
+ | ---- Input Type: Date ----
+ | subtract a number of days, or a duration, from a date
+ | fractional days subtract hours, minutes, and seconds
+ | subtracting a date returns the duration between the dates
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => - args..
+ | 
+ | Examples:
+ |  the date a week ago
+ |  => now | - 7
+ | 
+ |  subtract a duration from a date
+ |  => now | - {dur 12h}
+ | 
+ |  the time since a date
+ |  => now | - {\\ '2021-03-04' | to-date}
+ | 
+ | ---- Input Type: Decimal ----
+ | subtract arguments from a decimal
+ | number arguments are converted to decimals
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => - args..
+ | 
+ | Examples:
+ |  subtract 0.9 from 1, exactly
+ |  => \\ 1 | to-dec | - 0.9
+ | 
+ | ---- Input Type: Duration ----
+ | subtract durations from one another
+ | -variadic-: more than one argument can be specified
+ | 
+ | Usage:
+ |  => - args..
+ | 
+ | Examples:
+ |  subtract an hour from a day
+ |  => dur 1d | - {dur 1h}
+ | 
  | ---- Input Type: Number ----
  | subtract arguments from one another
  | -variadic-: more than one argument can be specified
@@ -570,3 +730,114 @@ fn sub_testing() {
     let x = process_w_num("let $x | \\ 1 | - 1 2 $x", defs);
     assert_eq!(x, Ok(Value::Num((-5).into()))); // 1 - 1 - 2 - 3
 }
+
+// ------ To Dec ---------------------------------------------------------------
+#[test]
+fn to_dec_help_msg() {
+    let src = "to-dec --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `to-dec`
+--> shell:0
+ | ---- Input Type: Number ----
+ | convert a number into an exact decimal
+ | the shortest representation of the number is used, so 0.1 is exactly 0.1
+ | 
+ | Usage:
+ |  => to-dec
+ | 
+ | Examples:
+ |  convert 0.1 into a decimal
+ |  => \ 0.1 | to-dec
+ | 
+ |  sum a column exactly
+ |  => open ledger.csv | fold {\ 0 | to-dec} + $row.amount
+ | 
+ | ---- Input Type: String ----
+ | parse a string as an exact decimal
+ | the digits are kept exactly, including any trailing zeros
+ | 
+ | Usage:
+ |  => to-dec
+ | 
+ | Examples:
+ |  parse a currency amount
+ |  => \ '1234.50' | to-dec
+"#
+    );
+}
+
+#[test]
+fn to_dec_testing() {
+    let defs = &Definitions::new();
+    let s = |x: &str| Ok(Value::Str(x.into()));
+
+    let x = process_w_nil("\\ 0.1 | to-dec | to-str", defs);
+    assert_eq!(x, s("0.1"));
+    let x = process_w_nil("\\ '1234.50' | to-dec | to-str", defs);
+    assert_eq!(x, s("1234.50"));
+    let x = process_w_nil("\\ '1.5e3' | to-dec | to-str", defs);
+    assert_eq!(x, s("1500"));
+
+    let x = process_w_nil("\\ abc | to-dec", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("could not parse `abc` as a decimal"));
+}
+
+#[test]
+fn dec_arithmetic() {
+    let defs = &Definitions::new();
+    let s = |x: &str| Ok(Value::Str(x.into()));
+
+    let x = process_w_nil("\\ 0.1 | to-dec | + 0.2 | to-str", defs);
+    assert_eq!(x, s("0.3"));
+    let x = process_w_nil("\\ 0.1 | to-dec | + 0.2 | = {\\ 0.3 | to-dec}", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+    let x = process_w_nil("\\ 1 | to-dec | + {\\ '0.25' | to-dec} | to-str", defs);
+    assert_eq!(x, s("1.25"));
+    let x = process_w_nil("\\ 1 | to-dec | - 0.9 | to-str", defs);
+    assert_eq!(x, s("0.1"));
+    let x = process_w_nil("\\ '19.99' | to-dec | * 3 | to-str", defs);
+    assert_eq!(x, s("59.97"));
+    let x = process_w_nil("\\ 10 | to-dec | ÷ 4 | to-str", defs);
+    assert_eq!(x, s("2.5"));
+
+    // summing does not drift
+    let x = process_w_nil("range 0 10 | fold {\\ 0 | to-dec} + 0.1 | to-str", defs);
+    assert_eq!(x, s("1.0"));
+
+    let x = process_w_nil("\\ 1 | to-dec | / 0", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("cannot divide `1` by zero"));
+}
+
+#[test]
+fn dec_cmp() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("\\ '0.30' | to-dec | = {\\ 0.3 | to-dec}", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+    let x = process_w_nil("\\ 1 | to-dec | > {\\ '0.99' | to-dec}", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    // numbers are converted to decimals
+    let x = process_w_nil("\\ '0.30' | to-dec | = 0.3", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+    let x = process_w_nil("\\ 0.1 | to-dec | + 0.2 | eq 0.3", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+    let x = process_w_nil("\\ '-0.01' | to-dec | > 0", defs);
+    assert_eq!(x, Ok(Value::Bool(false)));
+    let x = process_w_nil("\\ '-0.01' | to-dec | cmp 0 | = Ord::Lt", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    // filtering a decimal column against a number
+    let x = process_w_table("map first to-dec | filter first --Dec > 0 | len", defs);
+    assert_eq!(x, Ok(Value::Num(1.into())));
+    let x = process_w_table("map first to-dec | filter first --Dec = 0 | len", defs);
+    assert_eq!(x, Ok(Value::Num(1.into())));
+}
@@ -22,6 +22,39 @@ fn cmp_help_test() {
  | Usage:
  |  => cmp rhs:Bool
  | 
+ | ---- Input Type: Date ----
+ | compare <rhs> to input.
+ | dates are compared by the instant they represent, irrespective of the UTC offset
+ | 
+ | Usage:
+ |  => cmp rhs:Date
+ | 
+ | Examples:
+ |  compare a date to now
+ |  => \\ '2021-03-04' | to-date | cmp now
+ | 
+ | ---- Input Type: Decimal ----
+ | compare <rhs> to input.
+ | decimals are compared by value, irrespective of trailing zeros
+ | a number <rhs> is converted to a decimal
+ | 
+ | Usage:
+ |  => cmp rhs:Dec
+ | 
+ | Examples:
+ |  compare a decimal to a number
+ |  => \\ '0.30' | to-dec | cmp 0.3
+ | 
+ | ---- Input Type: Duration ----
+ | compare <rhs> to input.
+ | 
+ | Usage:
+ |  => cmp rhs:Dur
+ | 
+ | Examples:
+ |  compare 90 minutes to an hour
+ |  => dur 90min | cmp {dur 1h}
+ | 
  | ---- Input Type: Nil ----
  | compare <rhs> to input. Nil types are always equal.
  | 
@@ -223,6 +256,27 @@ fn eq_help_test() {
  | Usage:
  |  => eq rhs:Bool
  | 
+ | ---- Input Type: Date ----
+ | returns if <rhs> is equal to input
+ | dates are equal if they represent the same instant, irrespective of the UTC offset
+ | 
+ | Usage:
+ |  => eq rhs:Date
+ | 
+ | ---- Input Type: Decimal ----
+ | returns if <rhs> is equal to input
+ | decimals are equal if they have the same value, irrespective of trailing zeros
+ | a number <rhs> is converted to a decimal
+ | 
+ | Usage:
+ |  => eq rhs:Dec
+ | 
+ | ---- Input Type: Duration ----
+ | returns if <rhs> is equal to input
+ | 
+ | Usage:
+ |  => eq rhs:Dur
+ | 
  | ---- Input Type: Nil ----
  | returns if <rhs> is equal to input
  | 
@@ -0,0 +1,279 @@
+use super::*;
+
+fn date_str(src: &str) -> Result<Value> {
+    process_w_nil(src, &Definitions::new())
+}
+
+// ------ To Date --------------------------------------------------------------
+#[test]
+fn to_date_help_msg() {
+    let src = "to-date --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `to-date`
+--> shell:0
+ | ---- Input Type: Number ----
+ | convert a unix timestamp (seconds since 1970-01-01 UTC) into a date
+ | 
+ | Usage:
+ |  => to-date
+ | 
+ | Examples:
+ |  one billion seconds
+ |  => \ 1e9 | to-date
+ | 
+ | ---- Input Type: String ----
+ | parse a string into a date
+ | the format uses `strftime` specifiers, ie `%d/%m/%Y %H:%M`
+ | without a format, RFC 3339 and `yyyy-mm-dd [hh:mm[:ss]]` dates are recognised
+ | dates without an offset are UTC, dates without a time are at midnight
+ | 
+ | Usage:
+ |  => to-date [fmt]
+ | 
+ | Examples:
+ |  parse an ISO 8601 date
+ |  => \ '2021-03-04' | to-date
+ | 
+ |  parse a date with a format
+ |  => \ '04/03/2021 13:45' | to-date '%d/%m/%Y %H:%M'
+"#
+    );
+}
+
+#[test]
+fn to_date_testing() {
+    let x = date_str("\\ '2021-03-04' | to-date | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04".into())));
+
+    let x = date_str("\\ '2021-03-04 13:45' | to-date | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04 13:45:00".into())));
+
+    let x = date_str("\\ '2021-03-04T13:45:00+10:00' | to-date | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04 13:45:00 +10:00".into())));
+
+    // displayed dates can be parsed back
+    let x = date_str("\\ '2021-03-04 13:45:00 +10:00' | to-date | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04 13:45:00 +10:00".into())));
+
+    let x = date_str("\\ '04/03/2021' | to-date '%d/%m/%Y' | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04".into())));
+
+    let x = date_str("\\ 1e9 | to-date | to-str");
+    assert_eq!(x, Ok(Value::Str("2001-09-09 01:46:40".into())));
+}
+
+#[test]
+fn to_date_errs() {
+    let x = date_str("\\ 'foo' | to-date").unwrap_err().to_string();
+    println!("{}", x);
+    assert!(x.contains("`foo` is not a recognised date"));
+
+    let x = date_str("\\ '2021-13-01' | to-date '%Y-%m-%d'")
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("could not parse `2021-13-01` as a date"));
+
+    let x = date_str("\\ '2021' | to-date '%Q'")
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.starts_with("Parsing Error: invalid date format: unsupported specifier `%Q`"));
+
+    let x = date_str("\\ '2021' | to-date '%Y%'")
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("the format ends with a lone `%`"));
+}
+
+// ------ Components -----------------------------------------------------------
+#[test]
+fn date_components() {
+    let date = "\\ '2021-03-04 13:45:30' | to-date";
+    for (cmd, exp) in [
+        ("year", 2021),
+        ("month", 3),
+        ("day", 4),
+        ("hour", 13),
+        ("minute", 45),
+        ("second", 30),
+        ("weekday", 4),
+    ] {
+        let x = date_str(&format!("{} | {}", date, cmd));
+        assert_eq!(x, Ok(Value::Num(exp.into())), "{}", cmd);
+    }
+}
+
+// ------ Formatting -----------------------------------------------------------
+#[test]
+fn date_to_str_fmt() {
+    let date = "\\ '2021-03-04 13:45:30' | to-date";
+
+    let x = date_str(&format!("{} | to-str '%d/%m/%Y'", date));
+    assert_eq!(x, Ok(Value::Str("04/03/2021".into())));
+
+    let x = date_str(&format!("{} | to-str '%A %e %B, %I:%M %p'", date));
+    assert_eq!(x, Ok(Value::Str("Thursday  4 March, 01:45 PM".into())));
+
+    let x = date_str(&format!("{} | to-str '%F [%T] 100%%'", date));
+    assert_eq!(x, Ok(Value::Str("2021-03-04 [13:45:30] 100%".into())));
+}
+
+// ------ Arithmetic -----------------------------------------------------------
+#[test]
+fn date_arithmetic() {
+    let x = date_str("\\ '2021-03-04' | to-date | + 1 0.5 | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-05 12:00:00".into())));
+
+    let x = date_str("\\ '2021-03-04' | to-date | - 4 | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-02-28".into())));
+
+    // offsets are kept
+    let x = date_str("\\ '2021-03-04T13:00:00+10:00' | to-date | + 1 | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-05 13:00:00 +10:00".into())));
+}
+
+// ------ Comparisons ----------------------------------------------------------
+#[test]
+fn date_cmp() {
+    use std::cmp::Ordering;
+
+    let x = date_str("\\ '2021-03-04' | to-date | cmp {\\ '2021-03-05' | to-date}");
+    assert_eq!(x, Ok(Ordering::Less.into()));
+
+    let x = date_str("\\ '2021-03-04' | to-date | > {\\ '2021-03-03' | to-date}");
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    // the same instant, with different offsets
+    let x = date_str("\\ '2021-03-04T10:00:00+10:00' | to-date | = {\\ '2021-03-04' | to-date}");
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    let x = date_str("\\ '2021-03-04T10:00:00+10:00' | to-date | cmp {\\ '2021-03-04' | to-date}");
+    assert_eq!(x, Ok(Ordering::Equal.into()));
+}
+
+#[test]
+fn ls_modified_is_date() {
+    let defs = &Definitions::new();
+    let x = process_w_nil("ls | filter modified --Date < {now | + 1} | len", defs);
+    match x {
+        Ok(Value::Num(n)) => assert!(n.as_f64() > 0.0),
+        x => panic!("expecting a number, found {:?}", x),
+    }
+
+    let x = process_w_nil("ls | sort modified", defs);
+    assert!(matches!(x, Ok(Value::Tab(_))));
+}
+
+// ------ Dur ------------------------------------------------------------------
+#[test]
+fn dur_help_msg() {
+    let src = "dur --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `dur`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | construct a duration from a span such as `3d` or `1h30min`
+ | units: ns, us, ms, s, m|min, h|hr, d|day, w|wk
+ | numbers can be fractional, ie `1.5h`
+ | 
+ | Usage:
+ |  => dur span
+ | 
+ | Examples:
+ |  three days
+ |  => dur 3d
+ | 
+ |  list the files modified in the last 90 minutes
+ |  => ls | filter modified --Date > {now | - {dur 90min}}
+"#
+    );
+}
+
+#[test]
+fn dur_testing() {
+    for (span, exp) in [
+        ("3d", "3d"),
+        ("90min", "1h 30min"),
+        ("1.5h", "1h 30min"),
+        ("2w", "14d"),
+        ("'1d 2h 3m 4.5s'", "1d 2h 3min 4.5s"),
+        ("1h30min", "1h 30min"),
+        ("-2h", "-2h"),
+        ("250ms", "250ms"),
+        ("1.5us", "1.5us"),
+        ("20ns", "20ns"),
+        ("0s", "0s"),
+    ] {
+        let x = date_str(&format!("dur {} | to-str", span));
+        assert_eq!(x, Ok(Value::Str(exp.into())), "{}", span);
+    }
+}
+
+#[test]
+fn dur_errs() {
+    for (span, exp) in [
+        ("3x", "unknown unit `x`"),
+        ("h", "expecting a number at `h`"),
+        ("3d4", "`4` is missing a unit"),
+    ] {
+        let x = date_str(&format!("dur {}", span)).unwrap_err().to_string();
+        println!("{}", x);
+        assert!(
+            x.contains(&format!(
+                "could not parse `{}` as a duration: {}",
+                span, exp
+            )),
+            "{}",
+            span
+        );
+    }
+}
+
+#[test]
+fn dur_arithmetic() {
+    let x = date_str("dur 1h | + {dur 30min} {dur 15s} | to-str");
+    assert_eq!(x, Ok(Value::Str("1h 30min 15s".into())));
+
+    let x = date_str("dur 1d | - {dur 1h} | to-str");
+    assert_eq!(x, Ok(Value::Str("23h".into())));
+
+    let x = date_str("dur 1h | - {dur 2h} | to-str");
+    assert_eq!(x, Ok(Value::Str("-1h".into())));
+
+    // dates can be offset by durations and days
+    let x = date_str("\\ '2021-03-04' | to-date | + {dur 1h30min} | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-04 01:30:00".into())));
+
+    let x = date_str("\\ '2021-03-04' | to-date | + 1 {dur 12h} | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-05 12:00:00".into())));
+
+    let x = date_str("\\ '2021-03-04' | to-date | - {dur 1d} | to-str");
+    assert_eq!(x, Ok(Value::Str("2021-03-03".into())));
+
+    // subtracting dates returns a duration
+    let x = date_str("\\ '2021-03-05 12:00' | to-date | - {\\ '2021-03-04' | to-date} | to-str");
+    assert_eq!(x, Ok(Value::Str("1d 12h".into())));
+
+    let x = date_str("\\ '2021-03-04' | to-date | - {\\ '2021-03-05' | to-date} | > {dur 0s}");
+    assert_eq!(x, Ok(Value::Bool(false)));
+}
+
+#[test]
+fn dur_cmp() {
+    use std::cmp::Ordering;
+
+    let x = date_str("dur 90min | cmp {dur 1h}");
+    assert_eq!(x, Ok(Ordering::Greater.into()));
+
+    let x = date_str("dur 60min | = {dur 1h}");
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    let x = date_str("dur 1d | < {dur 23h}");
+    assert_eq!(x, Ok(Value::Bool(false)));
+}
@@ -114,21 +114,21 @@ fn list_defs() {
 
         assert_eq!(
             s,
-            "┌────────────────┬─────────────┬────────┬──────────┬──────┬────────────────────────────┐
-│ name           ┆ category    ┆ input  ┆ location ┆ line ┆ code                       │
-╞════════════════╪═════════════╪════════╪══════════╪══════╪════════════════════════════╡
-│ !=             ┆ cmp         ┆ -      ┆ <ogma>   ┆ -    ┆ != (rhs) { eq $rhs | not } │
-│ *              ┆ arithmetic  ┆ Number ┆ <ogma>   ┆ -    ┆ -                          │
-│ +              ┆ arithmetic  ┆ Number ┆ <ogma>   ┆ -    ┆ -                          │
-│ +              ┆ arithmetic  ┆ String ┆ <ogma>   ┆ -    ┆ -                          │
-│ +              ┆ arithmetic  ┆ Table  ┆ <ogma>   ┆ -    ┆ -                          │
-│ 85 rows elided ┆ ...         ┆ ...    ┆ ...      ┆ ...  ┆ ...                        │
-│ to-str         ┆ pipeline    ┆ Number ┆ <ogma>   ┆ -    ┆ -                          │
-│ to-str         ┆ pipeline    ┆ String ┆ <ogma>   ┆ -    ┆ -                          │
-│ typify         ┆ diagnostics ┆ -      ┆ <ogma>   ┆ -    ┆ -                          │
-│ ×              ┆ arithmetic  ┆ Number ┆ <ogma>   ┆ -    ┆ -                          │
-│ ÷              ┆ arithmetic  ┆ Number ┆ <ogma>   ┆ -    ┆ -                          │
-└────────────────┴─────────────┴────────┴──────────┴──────┴────────────────────────────┘
+            "┌─────────────────┬────────────┬─────────┬──────────┬──────┬────────────────────────────┐
+│ name            ┆ category   ┆ input   ┆ location ┆ line ┆ code                       │
+╞═════════════════╪════════════╪═════════╪══════════╪══════╪════════════════════════════╡
+│ !=              ┆ cmp        ┆ -       ┆ <ogma>   ┆ -    ┆ != (rhs) { eq $rhs | not } │
+│ *               ┆ arithmetic ┆ Decimal ┆ <ogma>   ┆ -    ┆ -                          │
+│ *               ┆ arithmetic ┆ Number  ┆ <ogma>   ┆ -    ┆ -                          │
+│ +               ┆ arithmetic ┆ Date    ┆ <ogma>   ┆ -    ┆ -                          │
+│ +               ┆ arithmetic ┆ Decimal ┆ <ogma>   ┆ -    ┆ -                          │
+│ 145 rows elided ┆ ...        ┆ ...     ┆ ...      ┆ ...  ┆ ...                        │
+│ year            ┆ time       ┆ Date    ┆ <ogma>   ┆ -    ┆ -                          │
+│ ×               ┆ arithmetic ┆ Decimal ┆ <ogma>   ┆ -    ┆ -                          │
+│ ×               ┆ arithmetic ┆ Number  ┆ <ogma>   ┆ -    ┆ -                          │
+│ ÷               ┆ arithmetic ┆ Decimal ┆ <ogma>   ┆ -    ┆ -                          │
+│ ÷               ┆ arithmetic ┆ Number  ┆ <ogma>   ┆ -    ┆ -                          │
+└─────────────────┴────────────┴─────────┴──────────┴──────┴────────────────────────────┘
 "
         );
     } else {
@@ -12,6 +12,7 @@ fn benchmark_help_msg() {
  | ---- Input Type: <any> ----
  | time the expression evaluation
  | pipes <input> to <expr>
+ | returns a table with the `duration` of the evaluation
  | 
  | Usage:
  |  => benchmark expr
@@ -31,15 +32,10 @@ fn benchmark_test() {
     let defs = &Definitions::new();
     // filter out the row to just get the headers
     let x = process_w_num(
-        "eq 3 | benchmark and { \\ 2 | > 1 } | filter duration = 2",
+        "eq 3 | benchmark and { \\ 2 | > 1 } | filter duration --Dur > {dur 1d}",
         defs,
     );
-    let exp = vec![vec![
-        o("duration"),
-        o("seconds"),
-        o("milliseconds"),
-        o("microseconds"),
-    ]];
+    let exp = vec![vec![o("duration")]];
     check_is_table(x, exp);
 }
 
@@ -144,3 +140,49 @@ fn typify_test_expressions() {
     );
     assert_eq!(x, s("{:Nil ls:Table |:Table fold:Str '':Str {:Str +:Str {:Str \\:TableRow $row:TableRow |:TableRow get:Str foo:Str }:Str }:Str |:Str =:Bool bar:Str }:Bool"));
 }
+
+// ------ Cache Stats ----------------------------------------------------------
+#[test]
+fn cache_stats_help_msg() {
+    let src = "cache-stats --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        "Help: `cache-stats`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | output statistics of the file cache
+ | the cache holds parsed files, evicting the least recently used
+ | files once the memory limit is exceeded
+ | 
+ | Usage:
+ |  => cache-stats
+ | 
+ | Examples:
+ |  show the number of cached files and their approximate size
+ |  => cache-stats
+"
+    );
+}
+
+#[test]
+fn cache_stats_test() {
+    let defs = &Definitions::new();
+    process_w_nil("open tests/diamonds.csv", defs).unwrap();
+    let x = process_w_nil("cache-stats", defs).unwrap();
+    let t = match x {
+        Value::Tab(t) => t,
+        x => panic!("expecting table: {:?}", x),
+    };
+    assert_eq!(
+        t.row(0).unwrap().cloned().collect::<Vec<_>>(),
+        vec![o("entries"), o("bytes"), o("limit"), o("hits"), o("misses")]
+    );
+    let get = |col| match t.row(1).unwrap().nth(col) {
+        Some(Entry::Num(n)) => n.as_f64(),
+        x => panic!("expecting number: {:?}", x),
+    };
+    assert!(get(0) >= 1.0);
+    assert!(get(1) > 0.0);
+    assert!(get(2) >= get(1));
+}
@@ -11,10 +11,17 @@ fn ls_help_msg() {
 --> shell:0
  | ---- Input Type: <any> ----
  | list out the filesystem contents in the current working dir
+ | recursive listings add a path column, relative to the working dir
+ | symlinked directories are followed once, and never outside the root
  | 
  | Usage:
  |  => ls [path]
  | 
+ | Flags:
+ |  --recurse: list the contents of sub-directories
+ |  --depth <n>: list recursively to a depth of n directories, 1 is the directory itself
+ |  --glob <pattern>: list recursively, keeping items matching the pattern. patterns without a `/` match the item name
+ | 
  | Examples:
  |  list the current working directory items
  |  => ls
@@ -22,6 +29,12 @@ fn ls_help_msg() {
  |  list directory items in `path`
  |  => ls path/to
  | 
+ |  list all items under `path`
+ |  => ls --recurse path/to
+ | 
+ |  list csv files at most two directories deep
+ |  => ls --depth 2 --glob '*.csv'
+ | 
  | ---- Input Type: Table ----
  | list out the headers of the table
  | 
@@ -84,7 +97,7 @@ fn ls_err() {
     if cfg!(windows) {
         assert_eq!(
         &x,
-        "Evaluation Error: an io error occurred: The system cannot find the path specified. (os error 3)
+        "Evaluation Error: an io error occurred: The system cannot find the file specified. (os error 2): noope
 --> shell:0
  | ls noope
  | ^^^^^^^^ within this block
@@ -93,7 +106,7 @@ fn ls_err() {
     } else {
         assert_eq!(
             &x,
-            "Evaluation Error: an io error occurred: No such file or directory (os error 2)
+            "Evaluation Error: an io error occurred: No such file or directory (os error 2): noope
 --> shell:0
  | ls noope
  | ^^^^^^^^ within this block
@@ -101,3 +114,1137 @@ fn ls_err() {
         );
     }
 }
+
+#[test]
+fn fs_sandbox_escapes() {
+    let defs = &Definitions::new();
+    let err = |expr: &str| process_w_nil(expr, defs).unwrap_err().to_string();
+    let outside = std::env::current_dir()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .join("Cargo.toml");
+    let outside = outside.to_string_lossy();
+
+    // escaping with ..
+    assert!(err("ls ..").contains("cannot move above root directory"));
+    assert!(err("ls io-test/../..").contains("cannot move above root directory"));
+    assert!(err("open ../Cargo.toml").contains("cannot move above root directory"));
+    assert!(err("\\ 1 | save ../escape.csv").contains("cannot move above root directory"));
+    assert!(err("\\ 1 | save io-test/new/../../../escape.csv").contains("invalid path"));
+
+    // escaping with absolute paths
+    assert!(err(&format!("open '{}'", outside)).contains("cannot move above root directory"));
+    assert!(err("\\ 1 | save /escape.csv").contains("cannot move above root directory"));
+    assert!(!Path::new("../escape.csv").exists());
+
+    // escaping with symlinks
+    #[cfg(unix)]
+    {
+        std::fs::create_dir_all("io-test").unwrap();
+        std::os::unix::fs::symlink("../..", "io-test/escape-link").ok();
+        assert!(err("ls io-test/escape-link").contains("cannot move above root directory"));
+        assert!(
+            err("open io-test/escape-link/Cargo.toml").contains("cannot move above root directory")
+        );
+        assert!(err("\\ 1 | save io-test/escape-link/escape.csv")
+            .contains("cannot move above root directory"));
+    }
+}
+
+#[test]
+fn fs_sandbox_read_only() {
+    let mut defs = Definitions::new();
+    defs.set_sandbox(Sandbox {
+        policy: Policy::ReadOnly,
+        symlinks: Symlinks::Follow,
+    });
+    let defs = &defs;
+    let p = io_test_file("read-only.csv", b"a,b\n1,2");
+
+    // reading is fine
+    let x = process_w_nil(&format!("open {}", p), defs);
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+
+    // but nothing can be written or removed
+    let err = |expr: &str| process_w_nil(expr, defs).unwrap_err().to_string();
+    assert!(err("\\ 1 | save io-test/read-only-saved.csv")
+        .contains("cannot write, the filesystem is read-only"));
+    assert!(!Path::new("io-test/read-only-saved.csv").exists());
+    assert!(err(&format!("rm {}", p)).contains("cannot write, the filesystem is read-only"));
+    assert!(Path::new(&p).exists());
+}
+
+#[test]
+fn ls_recurse() {
+    let defs = &Definitions::new();
+    std::fs::create_dir_all("io-test/walk/sub/deep").unwrap();
+    std::fs::write("io-test/walk/a.csv", "a").unwrap();
+    std::fs::write("io-test/walk/sub/b.csv", "b").unwrap();
+    std::fs::write("io-test/walk/sub/deep/c.txt", "c").unwrap();
+    #[cfg(unix)]
+    {
+        // a symlink loop is not followed more than once
+        std::os::unix::fs::symlink("..", "io-test/walk/sub/loop").ok();
+    }
+    let ls = |flags: &str| {
+        process_w_nil(
+            &format!(
+                "ls {} io-test/walk | filter name --Str != 'loop' | pick path type",
+                flags
+            ),
+            defs,
+        )
+    };
+
+    let x = ls("--recurse");
+    let exp = vec![
+        vec![o("path"), o("type")],
+        vec![o("io-test/walk/a.csv"), o("file")],
+        vec![o("io-test/walk/sub"), o("dir")],
+        vec![o("io-test/walk/sub/b.csv"), o("file")],
+        vec![o("io-test/walk/sub/deep"), o("dir")],
+        vec![o("io-test/walk/sub/deep/c.txt"), o("file")],
+    ];
+    check_is_table(x, exp);
+
+    let x = ls("--depth 2");
+    let exp = vec![
+        vec![o("path"), o("type")],
+        vec![o("io-test/walk/a.csv"), o("file")],
+        vec![o("io-test/walk/sub"), o("dir")],
+        vec![o("io-test/walk/sub/b.csv"), o("file")],
+        vec![o("io-test/walk/sub/deep"), o("dir")],
+    ];
+    check_is_table(x, exp);
+
+    let x = ls("--glob '*.csv'");
+    let exp = vec![
+        vec![o("path"), o("type")],
+        vec![o("io-test/walk/a.csv"), o("file")],
+        vec![o("io-test/walk/sub/b.csv"), o("file")],
+    ];
+    check_is_table(x, exp);
+
+    let x = ls("--depth 1 --glob '*.csv'");
+    let exp = vec![
+        vec![o("path"), o("type")],
+        vec![o("io-test/walk/a.csv"), o("file")],
+    ];
+    check_is_table(x, exp);
+
+    let x = ls("--glob 'sub/*/*'");
+    let exp = vec![
+        vec![o("path"), o("type")],
+        vec![o("io-test/walk/sub/deep/c.txt"), o("file")],
+    ];
+    check_is_table(x, exp);
+}
+
+#[test]
+fn ls_recurse_err() {
+    let defs = &Definitions::new();
+    let x = process_w_nil("ls --recurse ..", defs)
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        &x,
+        "Evaluation Error: an io error occurred: cannot move above root directory
+--> shell:0
+ | ls --recurse ..
+ | ^^^^^^^^^^^^^^^ within this block
+"
+    );
+
+    let x = process_w_nil("ls --depth 0", defs).unwrap_err().to_string();
+    assert_eq!(
+        &x,
+        "Semantics Error: depth must be a positive integer, found `0`
+--> shell:11
+ | ls --depth 0
+ |            ^ invalid flag value
+--> help: use `--depth 1` to list only the directory
+"
+    );
+
+    let x = process_w_nil("ls --depth two", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.starts_with("Semantics Error: expecting argument with output type `Num`"));
+}
+
+// ------ Open -----------------------------------------------------------------
+/// Writes `contents` to `io-test/<name>`, returning the path to use with `open`.
+fn io_test_file(name: &str, contents: &[u8]) -> String {
+    std::fs::create_dir_all("io-test").unwrap();
+    let path = format!("io-test/{}", name);
+    std::fs::write(&path, contents).unwrap();
+    path
+}
+
+#[test]
+fn open_by_extension() {
+    let defs = &Definitions::new();
+    let exp = vec![vec![o("a"), o("b")], vec![n(1), o("foo, bar")]];
+
+    let p = io_test_file("ext.tsv", b"a\tb\n1\tfoo, bar");
+    check_is_table(process_w_nil(&format!("open {}", p), defs), exp.clone());
+
+    let p = io_test_file("ext.psv", b"a|b\n1|foo, bar");
+    check_is_table(process_w_nil(&format!("open '{}'", p), defs), exp.clone());
+
+    let p = io_test_file("ext.csv", b"a,b\n1,\"foo, bar\"");
+    check_is_table(process_w_nil(&format!("open {}", p), defs), exp);
+}
+
+#[test]
+fn open_with_delim_flag() {
+    let defs = &Definitions::new();
+    let p = io_test_file("delim.txt", b"a;b\n1;2");
+
+    let x = process_w_nil(&format!("open --delim ';' {}", p), defs);
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+
+    // the flag can follow the file
+    let x = process_w_nil(&format!("open {} --delim ';'", p), defs);
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+
+    // cached tables do not leak between delimiters
+    let x = process_w_nil(&format!("open {}", p), defs);
+    check_is_table(x, vec![vec![o("a;b")], vec![o("1;2")]]);
+
+    let p = io_test_file("delim-tab.txt", b"a\tb\n1\t2");
+    let x = process_w_nil(&format!("open --delim tab {}", p), defs);
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+}
+
+#[test]
+fn open_delim_errs() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("open --delim ';;' file.csv", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.starts_with("Semantics Error: delimiter must be a single character, found `;;`"));
+    assert!(x.contains("--> help: use `\\t` or `tab` for a tab character"));
+
+    let x = process_w_nil("open file.csv --delim", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("`--delim` expects a value"));
+
+    let x = process_w_nil("open --Str --delim ';' file.csv", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: not expecting flags: `delim`
+--> shell:13
+ | open --Str --delim ';' file.csv
+ |              ^^^^^ flag not supported
+--> help: try using the `--help` flag to view requirements
+"
+    );
+}
+
+#[test]
+fn open_json() {
+    let defs = &Definitions::new();
+    let exp = vec![
+        vec![o("a"), o("b"), o("c")],
+        vec![n(1), o("foo"), Entry::Nil],
+        vec![n(2.5), Entry::Nil, Entry::Obj(Value::Bool(true))],
+    ];
+
+    let p = io_test_file(
+        "open.json",
+        br#"[{"a": 1, "b": "foo"}, {"a": 2.5, "b": null, "c": true}]"#,
+    );
+    check_is_table(process_w_nil(&format!("open {}", p), defs), exp.clone());
+
+    let p = io_test_file(
+        "open.ndjson",
+        b"{\"a\": 1, \"b\": \"foo\"}\n{\"a\": 2.5, \"c\": true}\n",
+    );
+    check_is_table(process_w_nil(&format!("open {}", p), defs), exp);
+
+    let p = io_test_file("bad.json", b"[1, 2]");
+    let x = process_w_nil(&format!("open {}", p), defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("expecting each JSON value to be an object"));
+}
+
+// ------ Save -----------------------------------------------------------------
+#[test]
+fn save_decimals() {
+    let defs = &Definitions::new();
+    let p = io_test_file("dec.csv", b"item,amount\na,0.1\nb,0.2");
+
+    process_w_nil(
+        &format!(
+            "open {} | map amount to-dec | save io-test/dec-saved.csv",
+            p
+        ),
+        defs,
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string("io-test/dec-saved.csv").unwrap(),
+        "item,amount\na,0.1\nb,0.2"
+    );
+
+    // summing as decimals is exact
+    let x = process_w_nil(
+        &format!("open {} | fold {{\\ 0 | to-dec}} + $row.amount | to-str", p),
+        defs,
+    );
+    assert_eq!(x, Ok(Value::Str("0.3".into())));
+}
+
+#[test]
+fn save_json() {
+    let defs = &Definitions::new();
+    let p = io_test_file("save.csv", b"a,b\n1,foo\n2,bar");
+    let exp = vec![
+        vec![o("a"), o("b")],
+        vec![n(1), o("foo")],
+        vec![n(2), o("bar")],
+    ];
+
+    process_w_nil(&format!("open {} | save io-test/saved.json", p), defs).unwrap();
+    assert_eq!(
+        std::fs::read_to_string("io-test/saved.json").unwrap(),
+        r#"[{"a":1,"b":"foo"},{"a":2,"b":"bar"}]"#
+    );
+    let x = process_w_nil("open io-test/saved.json", defs);
+    check_is_table(x, exp.clone());
+
+    process_w_nil(&format!("open {} | save io-test/saved.ndjson", p), defs).unwrap();
+    assert_eq!(
+        std::fs::read_to_string("io-test/saved.ndjson").unwrap(),
+        "{\"a\":1,\"b\":\"foo\"}\n{\"a\":2,\"b\":\"bar\"}\n"
+    );
+    let x = process_w_nil("open io-test/saved.ndjson", defs);
+    check_is_table(x, exp);
+}
+
+#[test]
+fn save_csv_round_trip() {
+    let defs = &Definitions::new();
+    let exp = vec![
+        vec![o("nil"), o("num"), o("str")],
+        vec![Nil, n(1), o("plain")],
+        vec![Nil, n(-2.5), o("foo, bar")],
+        vec![Nil, n(1.5e6), o("foo \"zog\" bar")],
+        vec![Nil, n(0), o("foo\nbar")],
+        vec![Nil, n(3), o("\"")],
+        vec![Nil, n(4), o("ünïcödé")],
+    ];
+
+    let p = io_test_file(
+        "round-trip.csv",
+        "nil,num,str\n,1,plain\n,-2.5,\"foo, bar\"\n,1.5e6,\"foo \"\"zog\"\" bar\"\n,0,\"foo\nbar\"\n,3,\"\"\"\"\n,4,ünïcödé"
+            .as_bytes(),
+    );
+    check_is_table(process_w_nil(&format!("open {}", p), defs), exp.clone());
+
+    let x = process_w_nil(
+        &format!("open {} | save io-test/round-trip-saved.csv", p),
+        defs,
+    );
+    check_is_table(x, exp.clone());
+    let x = process_w_nil("open io-test/round-trip-saved.csv", defs);
+    check_is_table(x, exp.clone());
+
+    // saved file is RFC 4180
+    let s = std::fs::read_to_string("io-test/round-trip-saved.csv").unwrap();
+    assert!(s.contains(",\"foo \"\"zog\"\" bar\"\n"));
+    assert!(s.contains(",\"foo\nbar\"\n"));
+    assert!(s.contains(",\"\"\"\"\n"));
+
+    let x = process_w_nil(
+        &format!("open {} | save --crlf io-test/round-trip-crlf.csv", p),
+        defs,
+    );
+    check_is_table(x, exp.clone());
+    let s = std::fs::read_to_string("io-test/round-trip-crlf.csv").unwrap();
+    assert!(s.starts_with("nil,num,str\r\n,1,plain\r\n"));
+    let x = process_w_nil("open io-test/round-trip-crlf.csv", defs);
+    check_is_table(x, exp);
+}
+
+#[test]
+fn compressed_round_trip() {
+    let defs = &Definitions::new();
+    let p = io_test_file("compress.tsv", b"a\tb\n1\tfoo\n2\tbar");
+    let exp = vec![
+        vec![o("a"), o("b")],
+        vec![n(1), o("foo")],
+        vec![n(2), o("bar")],
+    ];
+
+    for ext in ["csv.gz", "csv.zst", "tsv.gz", "json.zst"] {
+        let save = format!("io-test/compressed.{}", ext);
+        process_w_nil(&format!("open {} | save {}", p, save), defs).unwrap();
+        let bytes = std::fs::read(&save).unwrap();
+        assert!(bytes.starts_with(&[0x1f, 0x8b]) || bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+        check_is_table(process_w_nil(&format!("open {}", save), defs), exp.clone());
+    }
+
+    // magic bytes are detected without an extension
+    std::fs::copy("io-test/compressed.csv.gz", "io-test/compressed-gz.csv").unwrap();
+    let x = process_w_nil("open io-test/compressed-gz.csv", defs);
+    check_is_table(x, exp);
+}
+
+#[test]
+fn open_save_encodings() {
+    let defs = &Definitions::new();
+    let exp = vec![vec![o("name"), o("amount")], vec![o("Zoë"), o("€5")]];
+
+    // windows-1252
+    let p = io_test_file("cp1252.csv", b"name,amount\nZo\xEB,\x805");
+    assert!(process_w_nil(&format!("open {}", p), defs).is_err());
+    let x = process_w_nil(&format!("open --encoding windows-1252 {}", p), defs);
+    check_is_table(x, exp.clone());
+    let x = process_w_nil(&format!("open --Str --encoding latin1 {}", p), defs);
+    assert_eq!(x, Ok(Value::Str("name,amount\nZoë,€5".into())));
+
+    // utf-16 is detected with a bom when read back
+    let x = process_w_nil(
+        &format!(
+            "open --encoding windows-1252 {} | save --encoding utf-16le io-test/utf16.csv",
+            p
+        ),
+        defs,
+    );
+    check_is_table(x, exp.clone());
+    let bytes = std::fs::read("io-test/utf16.csv").unwrap();
+    assert!(bytes.starts_with(&[0xFF, 0xFE, b'n', 0]));
+    check_is_table(process_w_nil("open io-test/utf16.csv", defs), exp);
+
+    let x = process_w_nil("open --encoding foo file.csv", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: unknown encoding `foo`
+--> shell:16
+ | open --encoding foo file.csv
+ |                 ^^^ invalid flag value
+--> help: try `utf-8`, `utf-16le`, `utf-16be`, or `windows-1252`
+"
+    );
+}
+
+#[test]
+fn open_glob() {
+    let defs = &Definitions::new();
+    std::fs::create_dir_all("io-test/glob").unwrap();
+    io_test_file("glob/a.csv", b"month,sales\njan,1\nfeb,2");
+    io_test_file("glob/b.csv", b"sales,month,region\n3,mar,north");
+    io_test_file("glob/c.txt", b"not,a\ncsv,file");
+
+    let x = process_w_nil("open 'io-test/glob/*.csv'", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("month"), o("sales"), o("region")],
+            vec![o("jan"), n(1), Nil],
+            vec![o("feb"), n(2), Nil],
+            vec![o("mar"), n(3), o("north")],
+        ],
+    );
+
+    let x = process_w_nil("open --source-col file 'io-test/glob/?.csv'", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("file"), o("month"), o("sales"), o("region")],
+            vec![o("io-test/glob/a.csv"), o("jan"), n(1), Nil],
+            vec![o("io-test/glob/a.csv"), o("feb"), n(2), Nil],
+            vec![o("io-test/glob/b.csv"), o("mar"), n(3), o("north")],
+        ],
+    );
+
+    // source column also works for a single file
+    let x = process_w_nil("open --source-col file io-test/glob/b.csv", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("file"), o("sales"), o("month"), o("region")],
+            vec![o("io-test/glob/b.csv"), n(3), o("mar"), o("north")],
+        ],
+    );
+
+    let x = process_w_nil("open 'io-test/glob/*.xlsx'", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("no files match the pattern: io-test/glob/*.xlsx"));
+
+    let x = process_w_nil("open '../*.toml'", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("cannot move above root directory"));
+}
+
+#[test]
+fn open_glob_in_wd_with_pattern_chars() {
+    let defs = &Definitions::new();
+    std::fs::create_dir_all("io-test/[glob]").unwrap();
+    io_test_file("[glob]/a.csv", b"a,b\n1,2");
+
+    let x = process_expression(
+        (),
+        "open '*.csv'",
+        Location::Shell,
+        defs,
+        Path::new("."),
+        Path::new("io-test/[glob]"),
+    );
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+}
+
+#[test]
+fn persistent_table_cache() {
+    // resets the global cache dir, even if the test fails
+    struct Reset;
+    impl Drop for Reset {
+        fn drop(&mut self) {
+            ogma::rt::set_cache_dir(None).ok();
+        }
+    }
+
+    let defs = &Definitions::new();
+    let dir = std::env::temp_dir().join(format!("ogma-table-cache-{}", std::process::id()));
+    let dir = dir.as_path();
+    let _reset = Reset;
+    ogma::rt::set_cache_dir(Some(dir.to_path_buf())).unwrap();
+    assert_eq!(ogma::rt::cache_dir().as_deref(), Some(dir));
+
+    let p = io_test_file("persist.csv", b"a,b\n1,2");
+    let x = process_w_nil(&format!("open --delim ',' {}", p), defs);
+    check_is_table(x, vec![vec![o("a"), o("b")], vec![n(1), n(2)]]);
+
+    let cached = std::fs::read_dir(dir)
+        .unwrap()
+        .filter_map(|x| x.ok())
+        .any(|x| x.path().extension().map(|x| x == "tab").unwrap_or_default());
+    assert!(cached);
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+// ------ Fs Mutations ---------------------------------------------------------
+#[test]
+fn rm_help_msg() {
+    let src = "rm --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        "Help: `rm`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | remove a file or empty directory
+ | symlinks are removed, not their targets
+ | outputs a table of the actions taken
+ | 
+ | Usage:
+ |  => rm path
+ | 
+ | Flags:
+ |  --dry-run: resolve and check the paths without making changes
+ | 
+ | Examples:
+ |  remove a file
+ |  => rm file.csv
+ | 
+ |  check a file can be removed
+ |  => rm --dry-run file.csv
+ | 
+ | ---- Input Type: Table ----
+ | remove each item listed in a column of the table
+ | the column defaults to `path`, errors are noted in the result column
+ | 
+ | Usage:
+ |  => rm [col]
+ | 
+ | Flags:
+ |  --dry-run: resolve and check the paths without making changes
+ | 
+ | Examples:
+ |  preview removing all temporary files
+ |  => ls --glob '*.tmp' | rm --dry-run
+ | 
+ |  remove files in the working directory listed by name
+ |  => ls | filter ext --Str = tmp | rm name
+"
+    );
+}
+
+#[test]
+fn fs_mutations() {
+    let defs = &Definitions::new();
+    let dir = Path::new("io-test/fsops");
+    std::fs::remove_dir_all(dir).ok();
+    let exists = |p: &str| dir.join(p).exists();
+    let header = || vec![o("action"), o("path"), o("to"), o("result")];
+
+    // mkdir
+    let x = process_w_nil("mkdir --dry-run io-test/fsops/a", defs);
+    let exp = vec![
+        header(),
+        vec![o("mkdir"), o("io-test/fsops/a"), Nil, o("dry-run")],
+    ];
+    check_is_table(x, exp);
+    assert!(!exists("a"));
+
+    let x = process_w_nil("mkdir io-test/fsops/a", defs);
+    let exp = vec![
+        header(),
+        vec![o("mkdir"), o("io-test/fsops/a"), Nil, o("ok")],
+    ];
+    check_is_table(x, exp);
+    assert!(exists("a"));
+
+    std::fs::write(dir.join("x.csv"), "x").unwrap();
+    std::fs::write(dir.join("y.csv"), "y").unwrap();
+
+    // cp into a directory
+    let x = process_w_nil("cp io-test/fsops/x.csv io-test/fsops/a", defs);
+    let exp = vec![
+        header(),
+        vec![
+            o("cp"),
+            o("io-test/fsops/x.csv"),
+            o("io-test/fsops/a/x.csv"),
+            o("ok"),
+        ],
+    ];
+    check_is_table(x, exp);
+    assert!(exists("x.csv"));
+    assert!(exists("a/x.csv"));
+
+    // mv
+    process_w_nil("mv --dry-run io-test/fsops/y.csv io-test/fsops/z.csv", defs).unwrap();
+    assert!(exists("y.csv"));
+    process_w_nil("mv io-test/fsops/y.csv io-test/fsops/z.csv", defs).unwrap();
+    assert!(!exists("y.csv"));
+    assert_eq!(std::fs::read_to_string(dir.join("z.csv")).unwrap(), "y");
+
+    // bulk operations
+    let x = process_w_nil(
+        "ls --glob '*.csv' io-test/fsops | rm --dry-run | pick path result",
+        defs,
+    );
+    let exp = vec![
+        vec![o("path"), o("result")],
+        vec![o("io-test/fsops/a/x.csv"), o("dry-run")],
+        vec![o("io-test/fsops/x.csv"), o("dry-run")],
+        vec![o("io-test/fsops/z.csv"), o("dry-run")],
+    ];
+    check_is_table(x, exp);
+    assert!(exists("x.csv"));
+
+    let x = process_w_nil(
+        "ls --depth 1 --glob '*.csv' io-test/fsops | rm | pick path result",
+        defs,
+    );
+    let exp = vec![
+        vec![o("path"), o("result")],
+        vec![o("io-test/fsops/x.csv"), o("ok")],
+        vec![o("io-test/fsops/z.csv"), o("ok")],
+    ];
+    check_is_table(x, exp);
+    assert!(!exists("x.csv"));
+    assert!(!exists("z.csv"));
+    assert!(exists("a/x.csv"));
+
+    // errors in bulk operations are noted, names are relative to the working dir
+    let x = process_w_nil("ls io-test/fsops/a | rm name", defs).unwrap();
+    let x = match x {
+        Value::Tab(t) => t,
+        x => panic!("expecting table: {:?}", x),
+    };
+    assert!(matches!(
+        x.row(1).unwrap().nth(3),
+        Some(Entry::Obj(Value::Str(s))) if s.ends_with(": x.csv")
+    ));
+    assert!(exists("a/x.csv"));
+
+    let x = process_w_nil("\\ 'foo' | rm", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("expecting more than 0 arguments"));
+
+    // single path errors
+    let err = |expr: &str| process_w_nil(expr, defs).unwrap_err().to_string();
+    assert!(err("rm io-test/fsops/nope").contains("io-test/fsops/nope"));
+    assert!(err("rm io-test/fsops/a").contains("an io error occurred"));
+    assert!(err("cp io-test/fsops/a io-test/fsops/b").contains("cannot copy a directory"));
+    assert!(err("rm .").contains("invalid path: ."));
+    assert!(err("rm ../Cargo.toml").contains("cannot move above root directory"));
+    assert!(err("rm --dry-run ../Cargo.toml").contains("cannot move above root directory"));
+    assert!(err("cp io-test/fsops/a/x.csv ../x.csv").contains("cannot move above root directory"));
+    assert!(err("mv io-test/fsops/a ..").contains("cannot move above root directory"));
+    assert!(exists("a/x.csv"));
+
+    // missing column
+    let x = err("ls io-test/fsops | rm");
+    assert!(x.contains("header `path` not found"), "{}", x);
+}
+
+#[test]
+fn exec_help_msg() {
+    let src = "exec --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        "Help: `exec`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | run an external command, returning its standard output as a string
+ | a string input is piped to the command's stdin, a table input is piped as csv
+ | the command runs in the current working directory
+ | a non-zero exit status is an error, which includes the command's stderr
+ | flags for the command need to be quoted, ie `exec ls '-l'`
+ | process execution is disabled by default, enable with `ogma --allow-exec`
+ | 
+ | Usage:
+ |  => exec command [args..]
+ | 
+ | Flags:
+ |  --table: parse the standard output as a csv table
+ | 
+ | Examples:
+ |  list the git status
+ |  => exec git status '--short'
+ | 
+ |  pipe a table through an external filter
+ |  => open file.csv | exec --table grep 'foo'
+ | 
+"
+    );
+}
+
+#[test]
+fn exec_disabled_by_default() {
+    let defs = &Definitions::new();
+    let x = process_w_nil("exec echo hi", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: running external processes is disabled
+--> shell:0
+ | exec echo hi
+ | ^^^^ `exec` is not enabled for this session
+--> help: enable process execution with `ogma --allow-exec`
+"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn exec_processes() {
+    let defs = &mut Definitions::new();
+    defs.set_allow_exec(true);
+
+    let x = process_w_nil("exec echo hi", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("hi\n"))));
+
+    // string input is piped to stdin
+    let x = process_w_str("exec cat", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("Hello"))));
+
+    // table input is piped as csv
+    let x = process_w_table("exec --table cat", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("first"), o("snd"), o("Heading 3")],
+            vec![n(0), n(3), o("a")],
+            vec![n(1), n(20), o("b")],
+            vec![n(-30), n(100), o("z")],
+        ],
+    );
+
+    // runs in the working directory
+    let x = process_w_nil("exec pwd", defs).unwrap();
+    assert!(Str::try_from(x).unwrap().ends_with("ogma\n"));
+
+    // non-zero exit status errors
+    let x = process_w_nil("exec ls noope", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(x.contains("`ls` failed with exit status"), "{}", x);
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sql_query_help_msg() {
+    let src = "sql-query --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        "Help: `sql-query`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | run a SQL query against a SQLite database, returning the results as a table
+ | the database is opened read-only, the column names become the headers
+ | 
+ | Usage:
+ |  => sql-query database query
+ | 
+ | Examples:
+ |  query a database
+ |  => sql-query db.sqlite 'SELECT name, size FROM files WHERE size > 100'
+ | 
+ |  list the tables and indices in a database
+ |  => sql-query db.sqlite 'SELECT name, type FROM sqlite_master'
+ | 
+"
+    );
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite_round_trip() {
+    let defs = &Definitions::new();
+    let dir = Path::new("io-test/sqlite");
+    std::fs::remove_dir_all(dir).ok();
+
+    let exp = || {
+        vec![
+            vec![o("first"), o("snd"), o("Heading 3")],
+            vec![n(0), n(3), o("a")],
+            vec![n(1), n(20), o("b")],
+            vec![n(-30), n(100), o("z")],
+        ]
+    };
+
+    // save outputs the input
+    let x = process_w_table("save --table test io-test/sqlite/db.sqlite", defs);
+    check_is_table(x, exp());
+    assert!(dir.join("db.sqlite").exists());
+
+    // reads back the whole table
+    let x = process_w_nil("open --table test io-test/sqlite/db.sqlite", defs);
+    check_is_table(x, exp());
+
+    // the schema is typed from the values
+    let x = process_w_nil(
+        "sql-query io-test/sqlite/db.sqlite 'SELECT name, type FROM pragma_table_info(\"test\")'",
+        defs,
+    );
+    check_is_table(
+        x,
+        vec![
+            vec![o("name"), o("type")],
+            vec![o("first"), o("INTEGER")],
+            vec![o("snd"), o("INTEGER")],
+            vec![o("Heading 3"), o("TEXT")],
+        ],
+    );
+
+    let x = process_w_nil(
+        "sql-query io-test/sqlite/db.sqlite 'SELECT \"Heading 3\" AS h, snd * 1.5 AS x FROM test WHERE snd > 10'",
+        defs,
+    );
+    check_is_table(
+        x,
+        vec![
+            vec![o("h"), o("x")],
+            vec![o("b"), n(30.0)],
+            vec![o("z"), n(150.0)],
+        ],
+    );
+
+    // saving replaces the table
+    process_w_table("take 1 | save --table test io-test/sqlite/db.sqlite", defs).unwrap();
+    let x = process_w_nil(
+        "sql-query io-test/sqlite/db.sqlite 'SELECT count(*) AS n FROM test'",
+        defs,
+    );
+    check_is_table(x, vec![vec![o("n")], vec![n(1)]]);
+
+    // the only table is read by default
+    let x = process_w_nil("open io-test/sqlite/db.sqlite", defs);
+    check_is_table(x, vec![exp()[0].clone(), exp()[1].clone()]);
+
+    // a table is required when there is more than one
+    process_w_table("save --table other io-test/sqlite/db.sqlite", defs).unwrap();
+    let x = process_w_nil("open io-test/sqlite/db.sqlite", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("specify the database table with `--table <name>`, one of: other, test"),
+        "{}",
+        x
+    );
+    let x = process_w_nil("open --table other io-test/sqlite/db.sqlite", defs);
+    check_is_table(x, exp());
+
+    // other databases can not be attached
+    let x = process_w_nil(
+        "sql-query io-test/sqlite/db.sqlite 'ATTACH char(120) AS x'",
+        defs,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(x.contains("too many attached databases"), "{}", x);
+    let x = process_w_nil(
+        "sql-query io-test/sqlite/db.sqlite 'VACUUM INTO char(120)'",
+        defs,
+    )
+    .unwrap_err()
+    .to_string();
+    assert!(!Path::new("x").exists(), "{}", x);
+
+    // sandboxed
+    let x = process_w_nil("sql-query ../Cargo.toml 'SELECT 1'", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(x.contains("cannot move above root directory"), "{}", x);
+}
+
+#[cfg(feature = "workbook")]
+#[test]
+fn open_workbook() {
+    let defs = &Definitions::new();
+
+    // defaults to the first sheet
+    let x = process_w_nil("open tests/workbook.xlsx", defs);
+    let sales = || {
+        vec![
+            vec![o("region"), o("units"), o("price")],
+            vec![o("north"), n(10), n(2.5)],
+            vec![o("south"), n(20), n(3)],
+        ]
+    };
+    check_is_table(x, sales());
+
+    let x = process_w_nil("open --sheet Sales tests/workbook.xlsx", defs);
+    check_is_table(x, sales());
+
+    // numeric cells plug into the morphisms
+    let x = process_w_nil(
+        "open --sheet 0 tests/workbook.xlsx | fold 0 + $row.units",
+        defs,
+    );
+    assert_eq!(x, Ok(Value::Num(30.into())));
+
+    let x = process_w_nil("open --sheet 1 --range 'A3:B5' tests/workbook.xlsx", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("key"), o("value")],
+            vec![o("a"), n(1)],
+            vec![o("b"), n(2)],
+        ],
+    );
+
+    let x = process_w_nil("sheets tests/workbook.xlsx", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("index"), o("sheet")],
+            vec![n(0), o("Sales")],
+            vec![n(1), o("Notes")],
+        ],
+    );
+
+    let x = process_w_nil("open --sheet Nope tests/workbook.xlsx", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("sheet `Nope` not found, the workbook has sheets: Sales, Notes"),
+        "{}",
+        x
+    );
+
+    let x = process_w_nil("open --range A1-B2 tests/workbook.xlsx", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: invalid cell range `A1-B2`
+--> shell:13
+ | open --range A1-B2 tests/workbook.xlsx
+ |              ^^^^^ invalid flag value
+--> help: specify the range as the top-left and bottom-right cells, ie `--range 'A1:F200'`
+"
+    );
+
+    // an unquoted range splits on the `:`
+    let x = process_w_nil("open --range A1:B2 tests/workbook.xlsx", defs);
+    assert!(x.is_err());
+}
+
+#[cfg(feature = "columnar")]
+#[test]
+fn columnar_round_trip() {
+    let defs = &Definitions::new();
+    std::fs::remove_dir_all("io-test/columnar").ok();
+
+    let table = || {
+        vec![
+            vec![o("name"), o("qty"), o("price"), o("ok"), o("note")],
+            // above 2^53, which is not representable as a float
+            vec![
+                o("a"),
+                n(9_007_199_254_740_993i64),
+                n(2.5),
+                Entry::Obj(Value::Bool(true)),
+                Nil,
+            ],
+            vec![
+                o("b"),
+                n(-2),
+                n(3.0),
+                Entry::Obj(Value::Bool(false)),
+                o("x"),
+            ],
+            vec![o("c"), Nil, n(0.25), Nil, o("y")],
+        ]
+    };
+
+    for file in ["io-test/columnar/t.parquet", "io-test/columnar/t.arrow"] {
+        let t = Table::from(::table::Table::from(table()));
+        process(t, &format!("save {}", file), defs).unwrap();
+
+        let x = process_w_nil(&format!("open {}", file), defs);
+        check_is_table(x, table());
+
+        // projection, in the order specified
+        let x = process_w_nil(&format!("open {} --cols price name", file), defs);
+        check_is_table(
+            x,
+            vec![
+                vec![o("price"), o("name")],
+                vec![n(2.5), o("a")],
+                vec![n(3.0), o("b")],
+                vec![n(0.25), o("c")],
+            ],
+        );
+
+        let x = process_w_nil(&format!("open {} --cols nope", file), defs)
+            .unwrap_err()
+            .to_string();
+        assert!(x.contains("column `nope` not found"), "{}", x);
+    }
+
+    // decimals and dates keep their types
+    let typed = "map price --Str to-dec | map when --Str to-date";
+    for file in ["io-test/columnar/d.parquet", "io-test/columnar/d.arrow"] {
+        let t = || {
+            Table::from(::table::Table::from(vec![
+                vec![o("price"), o("when")],
+                vec![o("19.99"), o("2024-01-02T03:04:05Z")],
+                vec![o("5"), o("1969-12-31 23:59:59")],
+            ]))
+        };
+        let exp = process(t(), typed, defs).unwrap();
+        process(t(), &format!("{} | save {}", typed, file), defs).unwrap();
+
+        let x = process_w_nil(&format!("open {}", file), defs).unwrap();
+        assert_eq!(x, exp);
+    }
+
+    let x = process_w_nil("open tests/diamonds.csv --cols a", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("`--cols` is only supported for parquet and arrow files"),
+        "{}",
+        x
+    );
+
+    let x = process_w_nil("\\ 'foo' | save io-test/columnar/foo.parquet", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("only tables can be saved as parquet or arrow, found `String`"),
+        "{}",
+        x
+    );
+}
+
+#[test]
+fn open_fixed_width() {
+    let defs = &Definitions::new();
+    let file = io_test_file(
+        "fixed.txt",
+        b"date        desc          amount
+2021-01-02  coffee        -4.5
+2021-01-03  salary        100
+
+2021-01-04  \"rent\"        -20
+",
+    );
+
+    let exp = |header: Vec<Entry<Value>>| {
+        vec![
+            header,
+            vec![o("2021-01-02"), o("coffee"), n(-4.5)],
+            vec![o("2021-01-03"), o("salary"), n(100)],
+            vec![o("2021-01-04"), o("\"rent\""), n(-20)],
+        ]
+    };
+
+    let x = process_w_nil(&format!("open --fixed '0:12 12:26 26:' {}", file), defs);
+    check_is_table(x, exp(vec![o("date"), o("desc"), o("amount")]));
+
+    // named columns do not use the first line as the header
+    let file = io_test_file(
+        "fixed-named.txt",
+        b"2021-01-02  coffee        -4.5
+2021-01-03  salary        100
+2021-01-04  \"rent\"        -20
+",
+    );
+    let x = process_w_nil(
+        &format!("open --fixed 'd:0:10 what:12:26 amt:26:' {}", file),
+        defs,
+    );
+    check_is_table(x, exp(vec![o("d"), o("what"), o("amt")]));
+
+    let x = process_w_nil("open --fixed 'a:0:5 5:' foo.txt", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("either name every column, or none of them"),
+        "{}",
+        x
+    );
+
+    let x = process_w_nil("open --fixed '0:x' foo.txt", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(x.contains("invalid column `0:x`"), "{}", x);
+
+    // columns are separated by spaces
+    let x = process_w_nil("open --fixed '0:5,5:' foo.txt", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(x.contains("invalid column `0:5,5:`"), "{}", x);
+
+    let x = process_w_nil("open --delim ',' --fixed '0:' foo.txt", defs)
+        .unwrap_err()
+        .to_string();
+    assert!(
+        x.contains("`--fixed` cannot be used with `--delim`"),
+        "{}",
+        x
+    );
+}
@@ -13,6 +13,7 @@ type Result<T> = std::result::Result<T, ogma::Error>;
 mod annotation;
 mod arithmetic;
 mod cmp;
+mod datetime;
 mod definitions;
 mod diagnostics;
 mod errs;
@@ -258,6 +258,16 @@ fn filtering_help_msg() {
         &x,
         r#"Help: `filter`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | filter a list based on if an item matches a predicate
+ | 
+ | Usage:
+ |  => filter <predicate>
+ | 
+ | Examples:
+ |  keep the numbers greater than one
+ |  => List 1 2 3 | filter > 1
+ | 
  | ---- Input Type: String ----
  | filter a string based on if a character matches a predicate
  | 
@@ -431,6 +441,20 @@ fn string_filtering() {
     assert_eq!(x, Ok(Value::Str("Ho".into())));
 }
 
+#[test]
+fn list_filtering() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | filter > 1 | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[2, 3]".into())));
+
+    let x = process_w_nil(
+        "\\ 'the quick brown fox' | split | filter { len | = 5 } | to-str",
+        defs,
+    );
+    assert_eq!(x, Ok(Value::Str("[quick, brown]".into())));
+}
+
 // ------ Folding --------------------------------------------------------------
 #[test]
 fn fold_help_msg() {
@@ -440,6 +464,21 @@ fn fold_help_msg() {
         &x,
         "Help: `fold`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | fold (reduce) a list into single value
+ | fold takes a seed value and an accumulator expression
+ | the variable $item is available to query the list item
+ | 
+ | Usage:
+ |  => fold seed accumulator
+ | 
+ | Examples:
+ |  sum a list of numbers
+ |  => List 1 2 3 | fold 0 + $item
+ | 
+ |  count the characters in each word
+ |  => \\ 'the quick brown fox' | split | fold 0 + {\\ $item | len}
+ | 
  | ---- Input Type: Table ----
  | fold (reduce) table into single value
  | fold takes a seed value and an accumulator expression
@@ -469,6 +508,23 @@ fn fold_test() {
     assert_eq!(x, Ok(Value::Num((-100).into())));
 }
 
+#[test]
+fn list_fold_test() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | fold 0 + $item", defs);
+    assert_eq!(x, Ok(Value::Num(6.into())));
+
+    let x = process_w_nil(
+        "\\ 'the quick brown fox' | split | fold 0 + {\\ $item | len}",
+        defs,
+    );
+    assert_eq!(x, Ok(Value::Num(16.into())));
+
+    let x = process_w_nil("List --Num | fold 10 + $item", defs);
+    assert_eq!(x, Ok(Value::Num(10.into())));
+}
+
 #[test]
 fn fold_while_help_msg() {
     let src = "fold-while --help";
@@ -626,6 +682,21 @@ fn map_help_msg() {
         &x,
         "Help: `map`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | replace each item in a list with the result of an expression
+ | the input into the expression is the item
+ | the expression can return a different type, changing the type of the list
+ | 
+ | Usage:
+ |  => map value
+ | 
+ | Examples:
+ |  double each number
+ |  => List 1 2 3 | map * 2
+ | 
+ |  the length of each word. type: List<Num>
+ |  => \\ 'the quick brown fox' | split | map len
+ | 
  | ---- Input Type: Table ----
  | replace entry in column with result of an expression
  | `map` provides the variable `$row` which is the TableRow
@@ -712,6 +783,24 @@ fn map_testing() {
     check_is_table(x, exp);
 }
 
+#[test]
+fn list_map_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | map * 2 | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[2, 4, 6]".into())));
+
+    // the item type changes with the expression
+    let x = process_w_nil(
+        "\\ 'the quick brown fox' | split | map len | fold 0 + $item",
+        defs,
+    );
+    assert_eq!(x, Ok(Value::Num(16.into())));
+
+    let x = process_w_nil("List 1 2 | map to-str | nth 0", defs);
+    assert_eq!(x, Ok(Value::Str("1".into())));
+}
+
 // ------ Pick -----------------------------------------------------------------
 #[test]
 fn pick_help_msg() {
@@ -1182,6 +1271,17 @@ fn sort_help_msg() {
         &x,
         "Help: `sort`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | sort a list lowest to highest in a canonical fashion
+ | this sorts different value types, but NOT user-defined types
+ | 
+ | Usage:
+ |  => sort
+ | 
+ | Examples:
+ |  sort words alphabetically
+ |  => \\ 'the quick brown fox' | split | sort
+ | 
  | ---- Input Type: Table ----
  | sort a table by column headers
  | each header sorts the rows lowest to highest in a canonical fashion,
@@ -1221,6 +1321,27 @@ fn sort_testing() {
         vec![n(-30), n(100), o("z")],
     ];
     check_is_table(x, exp);
+
+    // numbers and decimals are sorted by value
+    let d = |s: &str| Entry::Obj(Value::Dec(s.parse().unwrap()));
+    let t = Table::from(::table::Table::from(vec![
+        vec![o("a")],
+        vec![n(2)],
+        vec![d("1.5")],
+        vec![n(-1)],
+        vec![d("0.25")],
+    ]));
+    let x = process(t, "sort a", defs);
+    check_is_table(
+        x,
+        vec![
+            vec![o("a")],
+            vec![n(-1)],
+            vec![d("0.25")],
+            vec![d("1.5")],
+            vec![n(2)],
+        ],
+    );
 }
 
 #[test]
@@ -1239,6 +1360,17 @@ fn empty_sort_err() {
     );
 }
 
+#[test]
+fn list_sort_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("\\ 'the quick brown fox' | split | sort | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[brown, fox, quick, the]".into())));
+
+    let x = process_w_nil("List 3 -1 2 | sort | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[-1, 2, 3]".into())));
+}
+
 // ------ Sort-by --------------------------------------------------------------
 #[test]
 fn sortby_help_msg() {
@@ -1387,6 +1519,50 @@ fn sortby_errors() {
     );
 }
 
+// ------ Split ----------------------------------------------------------------
+#[test]
+fn split_help_msg() {
+    let src = "split --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `split`
+--> shell:0
+ | ---- Input Type: String ----
+ | split a string into a list of strings
+ | the string is split on whitespace if no separator is specified
+ | 
+ | Usage:
+ |  => split [separator]
+ | 
+ | Examples:
+ |  split a sentence into words
+ |  => \ 'the quick brown fox' | split
+ | 
+ |  split comma separated values
+ |  => \ 'a,b,,c' | split ','
+"#
+    );
+}
+
+#[test]
+fn split_testing() {
+    let defs = &Definitions::new();
+
+    // whitespace is collapsed
+    let x = process_w_nil("\\ 'the quick  brown fox' | split | len", defs);
+    assert_eq!(x, Ok(Value::Num(4.into())));
+
+    let x = process_w_nil("\\ 'a,b,,c' | split ',' | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[a, b, , c]".into())));
+
+    // list types flow through definitions
+    let defs = &mut Definitions::new();
+    process_definition("def words Str () { split }", Location::Shell, None, defs).unwrap();
+    let x = process_w_nil("\\ 'the quick brown fox' | words | map len | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[3, 5, 5, 3]".into())));
+}
+
 // ------ Take -----------------------------------------------------------------
 #[test]
 fn take_help_msg() {
@@ -249,6 +249,20 @@ fn get_help_msg() {
  |  get the x field of a user defined Point type
  |  => Point 1 3 | get x
  | 
+ | ---- Input Type: Map<K,V> ----
+ | get the value under a key in a map
+ | a default can be given for missing keys
+ | 
+ | Usage:
+ |  => get key [default]
+ | 
+ | Examples:
+ |  get the size of the file 'README.md'
+ |  => ls | to-map name size | get 'README.md'
+ | 
+ |  get the size of the file 'foo', using a default of zero
+ |  => ls | to-map name size | get foo 0
+ | 
  | ---- Input Type: TableRow ----
  | extract a value out of a column in a table row.
  | optionally specify a default value if the get type does not match
@@ -492,6 +506,16 @@ fn len_help_msg() {
         &x,
         r#"Help: `len`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | return the number of items in a list
+ | 
+ | Usage:
+ |  => len
+ | 
+ | Examples:
+ |  number of words in a sentence
+ |  => \ 'the quick brown fox' | split | len
+ | 
  | ---- Input Type: String ----
  | return the number of characters in a string
  | 
@@ -810,6 +834,91 @@ fn variable_shadowing_soundness_02() {
     assert!(matches!(x, Ok(Value::Tab(_))));
 }
 
+// ------ List -----------------------------------------------------------------
+#[test]
+fn list_help_msg() {
+    let src = "List --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `List`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | construct a list of the result of each expression
+ | lists are homogeneous, each expression must return the same type
+ | lists have the type `List<Ty>`, use a type flag to construct an empty list
+ | 
+ | Usage:
+ |  => List [args..]
+ | 
+ | Flags:
+ |  --<type>: the type of the list items
+ | 
+ | Examples:
+ |  create a list of numbers. type: List<Num>
+ |  => List 1 2 3
+ | 
+ |  create a list of strings. type: List<Str>
+ |  => List foo bar 'hello world'
+ | 
+ |  create an empty list of strings
+ |  => List --Str
+"#
+    );
+}
+
+#[test]
+fn list_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | len", defs);
+    assert_eq!(x, Ok(Value::Num(3.into())));
+
+    let x = process_w_nil("List foo bar 'hello world' | nth 2", defs);
+    assert_eq!(x, Ok(Value::Str("hello world".into())));
+
+    let x = process_w_nil("\\ 3 | List {+ 1} {* 2} | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[4, 6]".into())));
+
+    // empty lists need a type flag
+    let x = process_w_nil("List --Str | len", defs);
+    assert_eq!(x, Ok(Value::Num(0.into())));
+
+    // lists are homogeneous
+    let x = process_w_nil("List 1 foo", defs);
+    assert!(x.is_err());
+
+    let x = process_w_nil("List", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert!(x.contains("expecting more than 0 arguments"));
+}
+
+#[test]
+fn list_nth_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | nth {len | - 1}", defs);
+    assert_eq!(x, Ok(Value::Num(3.into())));
+
+    let x = process_w_nil("List 1 2 3 | nth 3", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Evaluation Error: index is outside list bounds
+--> shell:17
+ | List 1 2 3 | nth 3
+ |                  ^ this resolves to `3`
+"
+    );
+
+    // list commands do not support other input types
+    let x = process_w_num("nth 0", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert!(x.contains("`nth` does not support `Number` input data"));
+}
+
 // ------ Nth ------------------------------------------------------------------
 #[test]
 fn nth_help_msg() {
@@ -819,6 +928,16 @@ fn nth_help_msg() {
         &x,
         r#"Help: `nth`
 --> shell:0
+ | ---- Input Type: List<T> ----
+ | retrieves the nth item of a list
+ | 
+ | Usage:
+ |  => nth index
+ | 
+ | Examples:
+ |  get the second word of a sentence
+ |  => \ 'the quick brown fox' | split | nth 1
+ | 
  | ---- Input Type: String ----
  | retrieves the nth character of a string
  | 
@@ -1053,6 +1172,56 @@ fn range_test() {
     );
 }
 
+// ------ To-list --------------------------------------------------------------
+#[test]
+fn to_list_help_msg() {
+    let src = "to-list --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `to-list`
+--> shell:0
+ | ---- Input Type: Table ----
+ | convert a table column into a list
+ | the first column is used if no column is specified
+ | the items are expected to be numbers, use a type flag to specify otherwise
+ | 
+ | Usage:
+ |  => to-list [col-name]
+ | 
+ | Flags:
+ |  --<type>: the type of the column entries
+ | 
+ | Examples:
+ |  list the numbers zero to nine
+ |  => range 0 10 | to-list
+ | 
+ |  list the file names in the directory
+ |  => ls | to-list name --Str
+"#
+    );
+}
+
+#[test]
+fn to_list_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table("to-list | fold 0 + $item", defs);
+    assert_eq!(x, Ok(Value::Num((-29).into())));
+
+    let x = process_w_table("to-list 'Heading 3' --Str | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("[a, b, z]".into())));
+
+    let x = process_w_table("to-list snd | nth 1", defs);
+    assert_eq!(x, Ok(Value::Num(20.into())));
+
+    let x = process_w_table("to-list 'Heading 3'", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("table entry for [row:1,col:'Heading 3'] did not have expected type"));
+}
+
 // ------ To-str ---------------------------------------------------------------
 #[test]
 fn to_str_help_msg() {
@@ -1074,6 +1243,21 @@ fn to_str_help_msg() {
  | Usage:
  |  => to-str
  | 
+ | ---- Input Type: Date ----
+ | format the date as a string
+ | the format uses `strftime` specifiers, ie `%d/%m/%Y %H:%M`
+ | without a format, the date is formatted as `yyyy-mm-dd [hh:mm:ss]`
+ | 
+ | Usage:
+ |  => to-str [fmt]
+ | 
+ | Examples:
+ |  format the current date as day/month/year
+ |  => now | to-str '%d/%m/%Y'
+ | 
+ |  get the name of the day of the week
+ |  => now | to-str '%A'
+ | 
  | ---- Input Type: Number ----
  | format the number as a string
  | 
@@ -1089,6 +1273,24 @@ fn to_str_help_msg() {
  | 
  | Usage:
  |  => to-str
+ | 
+ | ---- Input Type: Table ----
+ | render the table as a string
+ | without a flag the table is summarised as `<table [rows,cols]>`
+ | 
+ | Usage:
+ |  => to-str
+ | 
+ | Flags:
+ |  --md: render as a markdown (GitHub pipe) table
+ |  --html: render as a html table
+ | 
+ | Examples:
+ |  render a table as markdown
+ |  => open table.csv | to-str --md
+ | 
+ |  render a table as html
+ |  => open table.csv | to-str --html
 "#
     );
 }
@@ -1116,6 +1318,46 @@ fn to_str_testing() {
     let x = process_w_nil("\\ 1.234e6 | to-str 'yo [.0n] da'", defs);
     assert_eq!(x, Ok(Value::Str("yo 1,234,000 da".into())));
 
+    // tables
+    let x = process_w_nil("Table a b | append-row 'x|y' 'z' | to-str", defs);
+    assert_eq!(x, Ok(Value::Str("<table [2,2]>".into())));
+
+    let x = process_w_nil("Table a b | append-row 'x|y' 'z' | to-str --md", defs);
+    assert_eq!(
+        x,
+        Ok(Value::Str(
+            "| a | b |\n| --- | --- |\n| x\\|y | z |\n".into()
+        ))
+    );
+
+    let x = process_w_nil("Table a b | append-row 'x<y' 'z' | to-str --html", defs);
+    assert_eq!(
+        x,
+        Ok(Value::Str(
+            "<table>
+<thead>
+<tr><th>a</th><th>b</th></tr>
+</thead>
+<tbody>
+<tr><td>x&lt;y</td><td>z</td></tr>
+</tbody>
+</table>
+"
+            .into()
+        ))
+    );
+
+    let x = process_w_nil("Table a | to-str --md --html", defs).unwrap_err();
+    assert_eq!(
+        x.to_string(),
+        "Semantics Error: not expecting flags: `html`
+--> shell:24
+ | Table a | to-str --md --html
+ |                         ^^^^ flag not supported
+--> help: try using the `--help` flag to view requirements
+"
+    );
+
     let x = process_w_nil("\\ 1.234e6 | to-str '[.0n da'", defs)
         .unwrap_err()
         .to_string();
@@ -1128,3 +1370,445 @@ fn to_str_testing() {
           <https://daedalus.report/d/docs/ogma.book/05%20syntax%20and%20semantics/5.4%20number%20formatting.md?pwd-raw=docs>
 ");
 }
+
+// ------ To-table -------------------------------------------------------------
+#[test]
+fn to_table_help_msg() {
+    let src = "to-table --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `to-table`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | convert a list or a map into a table
+ | a list becomes a single column table, with the header `value`
+ | a map becomes a table of its entries, with the headers `key` and `value`
+ | headers can be specified, one for each column
+ | 
+ | Usage:
+ |  => to-table [headers..]
+ | 
+ | Examples:
+ |  a table of numbers
+ |  => List 1 2 3 | to-table
+ | 
+ |  a table of words, under the header 'word'
+ |  => \ 'the quick brown fox' | split | to-table word
+ | 
+ |  a table of file names and sizes
+ |  => ls | to-map name size | to-table name size
+"#
+    );
+}
+
+#[test]
+fn to_table_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_nil("List 1 2 3 | to-table", defs);
+    let exp = vec![vec![o("value")], vec![n(1)], vec![n(2)], vec![n(3)]];
+    check_is_table(x, exp);
+
+    let x = process_w_nil("List a b | to-table name", defs);
+    let exp = vec![vec![o("name")], vec![o("a")], vec![o("b")]];
+    check_is_table(x, exp);
+
+    // round trip
+    let x = process_w_table("to-list snd | to-table snd", defs);
+    let exp = vec![vec![o("snd")], vec![n(3)], vec![n(20)], vec![n(100)]];
+    check_is_table(x, exp);
+
+    // a header per column
+    let x = process_w_nil("List a b | to-table name other", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: too many arguments supplied
+--> shell:25
+ | List a b | to-table name other
+ |                          ^^^^^ this argument is unnecessary
+"
+    );
+}
+
+// ------ Map ------------------------------------------------------------------
+#[test]
+fn to_map_help_msg() {
+    let src = "to-map --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `to-map`
+--> shell:0
+ | ---- Input Type: Table ----
+ | convert a table into a map, using a column for the keys and a column for the values
+ | the keys are expected to be strings and the values numbers, use flags to specify otherwise
+ | keys can be Bool, Num, Dec, Str, Date, or Dur values
+ | rows with the same key are replaced, the last row taking precedence
+ | 
+ | Usage:
+ |  => to-map key-col value-col
+ | 
+ | Flags:
+ |  --key <type>: the type of the key column entries
+ |  --value <type>: the type of the value column entries
+ | 
+ | Examples:
+ |  map file names to their sizes. type: Map<Str,Num>
+ |  => ls | to-map name size
+ | 
+ |  map file names to their types. type: Map<Str,Str>
+ |  => ls | to-map name type --value Str
+"#
+    );
+}
+
+#[test]
+fn to_map_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table("to-map 'Heading 3' snd", defs);
+    assert_eq!(
+        x.map(|x| x.ty().to_string()),
+        Ok("Map<Str,Num>".to_string())
+    );
+
+    let x = process_w_table("to-map 'Heading 3' snd | to-table", defs);
+    let exp = vec![
+        vec![o("key"), o("value")],
+        vec![o("a"), n(3)],
+        vec![o("b"), n(20)],
+        vec![o("z"), n(100)],
+    ];
+    check_is_table(x, exp);
+
+    let x = process_w_table(
+        "to-map first 'Heading 3' --key Num --value Str | to-table k v",
+        defs,
+    );
+    let exp = vec![
+        vec![o("k"), o("v")],
+        vec![n(0), o("a")],
+        vec![n(1), o("b")],
+        vec![n(-30), o("z")],
+    ];
+    check_is_table(x, exp);
+
+    let x = process_w_table("to-map snd first --key Num --value Num | get 20", defs);
+    assert_eq!(x, Ok(Value::Num(1.into())));
+}
+
+#[test]
+fn to_map_errs() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table("to-map first snd", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("table entry for [row:1,col:'first'] did not have expected type"));
+
+    let x = process_w_table("to-map foo snd", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("header `foo` not found in table"));
+
+    let x = process_w_table("to-map first snd --key Foo", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("type `Foo` not defined"));
+
+    // `Table` is also a command, so it is quoted
+    let x = process_w_table("to-map first snd --key 'Table'", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("`Table` cannot be used as a map key"));
+
+    let x = process_w_table("to-map first snd --key", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("`--key` expects a value"));
+}
+
+#[test]
+fn map_get_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table("to-map 'Heading 3' snd | get b", defs);
+    assert_eq!(x, Ok(Value::Num(20.into())));
+
+    let x = process_w_table("to-map 'Heading 3' snd | get c 0", defs);
+    assert_eq!(x, Ok(Value::Num(0.into())));
+
+    // look up values for each row
+    let x = process_w_table(
+        "let {to-map first 'Heading 3' --key Num --value Str} $m | append { get first --Num | let $k | \\ $m | get $k } --h",
+        defs,
+    );
+    let exp = vec![
+        vec![o("first"), o("snd"), o("Heading 3"), o("h")],
+        vec![n(0), n(3), o("a"), o("a")],
+        vec![n(1), n(20), o("b"), o("b")],
+        vec![n(-30), n(100), o("z"), o("z")],
+    ];
+    check_is_table(x, exp);
+
+    let x = process_w_table("to-map 'Heading 3' snd | get c", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert!(x.contains("key not found in map"));
+    assert!(x.contains("this resolves to `c`"));
+
+    // the default must match the value type
+    let x = process_w_table("to-map 'Heading 3' snd | get c foo", defs);
+    assert!(x.is_err());
+}
+
+#[test]
+fn contains_help_msg() {
+    let src = "contains --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `contains`
+--> shell:0
+ | ---- Input Type: Map<K,V> ----
+ | returns if a map contains an entry under the key
+ | 
+ | Usage:
+ |  => contains key
+ | 
+ | Examples:
+ |  check if there is a file named 'README.md'
+ |  => ls | to-map name size | contains 'README.md'
+"#
+    );
+}
+
+#[test]
+fn contains_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table("to-map 'Heading 3' snd | contains b", defs);
+    assert_eq!(x, Ok(Value::Bool(true)));
+
+    let x = process_w_table("to-map 'Heading 3' snd | contains c", defs);
+    assert_eq!(x, Ok(Value::Bool(false)));
+
+    let x = process_w_table("contains b", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert!(x.contains("operation `contains` not defined"));
+    assert!(x.contains("`contains` is implemented for the following input types: Map<K,V>"));
+}
+
+#[test]
+fn insert_help_msg() {
+    let src = "insert --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `insert`
+--> shell:0
+ | ---- Input Type: Map<K,V> ----
+ | insert an entry into a map
+ | if the key already exists, the value is replaced
+ | 
+ | Usage:
+ |  => insert key value
+ | 
+ | Examples:
+ |  add a file 'foo' with a size of zero
+ |  => ls | to-map name size | insert foo 0
+"#
+    );
+}
+
+#[test]
+fn insert_testing() {
+    let defs = &Definitions::new();
+
+    // existing keys keep their position
+    let x = process_w_table(
+        "to-map 'Heading 3' snd | insert c 5 | insert a 0 | to-table",
+        defs,
+    );
+    let exp = vec![
+        vec![o("key"), o("value")],
+        vec![o("a"), n(0)],
+        vec![o("b"), n(20)],
+        vec![o("z"), n(100)],
+        vec![o("c"), n(5)],
+    ];
+    check_is_table(x, exp);
+}
+
+#[test]
+fn merge_help_msg() {
+    let src = "merge --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        r#"Help: `merge`
+--> shell:0
+ | ---- Input Type: Map<K,V> ----
+ | merge the entries of one or more maps into the input map
+ | the maps must have the same type
+ | entries with the same key are replaced, with the last map taking precedence
+ | 
+ | Usage:
+ |  => merge maps..
+ | 
+ | Examples:
+ |  merge the file sizes of two directories
+ |  => ls foo | to-map name size | merge {ls bar | to-map name size}
+"#
+    );
+}
+
+#[test]
+fn merge_testing() {
+    let defs = &Definitions::new();
+
+    let x = process_w_table(
+        "let {to-map 'Heading 3' first} $a | to-map 'Heading 3' snd | insert c 5 | merge $a {\\ $a | insert d 2} | to-table",
+        defs,
+    );
+    let exp = vec![
+        vec![o("key"), o("value")],
+        vec![o("a"), n(0)],
+        vec![o("b"), n(1)],
+        vec![o("z"), n(-30)],
+        vec![o("c"), n(5)],
+        vec![o("d"), n(2)],
+    ];
+    check_is_table(x, exp);
+
+    // maps must have the same type
+    let x = process_w_table(
+        "to-map 'Heading 3' snd | merge {to-map first 'Heading 3' --key Num --value Str}",
+        defs,
+    );
+    assert!(x.is_err());
+}
+
+#[test]
+fn arg_help_msg() {
+    let src = "arg --help";
+    let x = print_help(src, &Definitions::new());
+    assert_eq!(
+        &x,
+        "Help: `arg`
+--> shell:0
+ | ---- Input Type: <any> ----
+ | return the value of a script argument, supplied with `ogma file.ogma -- name=value`
+ | the name must be a literal, and is checked before evaluation
+ | if a default is given, the argument is parsed into the type of the default
+ | 
+ | Usage:
+ |  => arg name [default]
+ | 
+ | Examples:
+ |  open the file supplied with `-- file=data.csv`
+ |  => open { arg file }
+ | 
+ |  take a number of rows, defaulting to 10
+ |  => open file.csv | take { arg rows 10 }
+ | 
+"
+    );
+}
+
+#[test]
+fn arg_test() {
+    let defs = &mut Definitions::new();
+    defs.set_arg("file", "data.csv");
+    defs.set_arg("rows", "5");
+
+    let x = process_w_nil("arg file", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("data.csv"))));
+
+    // parsed into the type of the default
+    let x = process_w_nil("arg rows 10 | + 1", defs);
+    assert_eq!(x, Ok(Value::Num(6.into())));
+
+    let x = process_w_nil("arg missing 10 | + 1", defs);
+    assert_eq!(x, Ok(Value::Num(11.into())));
+
+    let x = process_w_nil("arg missing 'foo'", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("foo"))));
+
+    // checked before evaluation
+    let x = process_w_nil("arg missing", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: script argument `missing` not supplied
+--> shell:4
+ | arg missing
+ |     ^^^^^^^
+--> help: supply the argument with `ogma file.ogma -- missing=value`, or give `arg` a default
+"
+    );
+
+    let x = process_w_nil("arg file 10", defs).unwrap_err().to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: script argument `file` expects a `Number`, found `data.csv`
+--> shell:9
+ | arg file 10
+ |          ^^ the default value returns type `Number`
+"
+    );
+
+    let x = process_w_nil("arg { \\ file }", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Semantics Error: not expecting argument variant `expression`
+--> shell:4
+ | arg { \\ file }
+ |     ^^^^^^^^^^ argument variant `expression` is not supported here
+--> help: commands may require specific argument types, use `--help` to view requirements
+"
+    );
+}
+
+#[test]
+fn env_test() {
+    let defs = &Definitions::new();
+    std::env::set_var("OGMA_ENV_TEST", "foo");
+
+    let x = process_w_nil("env OGMA_ENV_TEST", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("foo"))));
+
+    let x = process_w_nil("env OGMA_ENV_TEST 'bar'", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("foo"))));
+
+    let x = process_w_nil("env OGMA_ENV_MISSING 'bar'", defs);
+    assert_eq!(x, Ok(Value::Str(Str::new("bar"))));
+
+    let x = process_w_nil("env OGMA_ENV_MISSING", defs)
+        .unwrap_err()
+        .to_string();
+    println!("{}", x);
+    assert_eq!(
+        &x,
+        "Evaluation Error: environment variable `OGMA_ENV_MISSING` not found
+--> shell:4
+ | env OGMA_ENV_MISSING
+ |     ^^^^^^^^^^^^^^^^ `OGMA_ENV_MISSING` resolves to `OGMA_ENV_MISSING`
+--> help: supply a default value, ie `env NAME 'default'`
+"
+    );
+}